@@ -0,0 +1,364 @@
+//! End-to-end MCP conformance check for a built server binary.
+//!
+//! Spawns the server over stdio against a local wiremock Brave backend, performs `initialize`
+//! and `tools/list`, runs a scripted set of tool calls, and validates the response envelopes.
+//! Packagers who patch or vendor the crate can run it against their own build:
+//!
+//! ```text
+//! cargo build && cargo run --example conformance [-- path/to/codex-brave-web-search]
+//! ```
+//!
+//! Without an argument the binary next to the example's target directory is used. The process
+//! exits non-zero on the first failed check.
+
+use codex_brave_web_search::constants::{
+    ENV_BRAVE_SEARCH_API_KEY, ENV_CACHE_TTL_SECS, ENV_ENDPOINT_SUGGEST, ENV_ENDPOINT_WEB,
+    ENV_RETRY_COUNT, ENV_TRANSPORT, TOOL_BRAVE_BATCH_STATUS, TOOL_BRAVE_COMPARE_PARAMS,
+    TOOL_BRAVE_RELOAD_KEY, TOOL_BRAVE_SCHEDULE_BATCH, TOOL_BRAVE_SUGGEST, TOOL_BRAVE_WEB_SEARCH,
+    TOOL_BRAVE_WEB_SEARCH_HELP, TOOL_BRAVE_WEB_SEARCH_STATUS, TOOL_BRAVE_WEB_SEARCH_SUMMARIZE,
+};
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, channel};
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const PROTOCOL_VERSION: &str = "2025-03-26";
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(15);
+const EXPECTED_TOOLS: &[&str] = &[
+    TOOL_BRAVE_WEB_SEARCH,
+    TOOL_BRAVE_WEB_SEARCH_HELP,
+    TOOL_BRAVE_WEB_SEARCH_STATUS,
+    TOOL_BRAVE_WEB_SEARCH_SUMMARIZE,
+    TOOL_BRAVE_SUGGEST,
+    TOOL_BRAVE_SCHEDULE_BATCH,
+    TOOL_BRAVE_BATCH_STATUS,
+    TOOL_BRAVE_COMPARE_PARAMS,
+    TOOL_BRAVE_RELOAD_KEY,
+];
+
+type CheckResult = Result<(), String>;
+type Check = fn(&mut Session) -> CheckResult;
+
+/// JSON-RPC session with the spawned server: one JSON message per line in each direction.
+struct Session {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    next_id: u64,
+}
+
+impl Session {
+    fn spawn(binary: &PathBuf, backend: &str) -> Result<Self, String> {
+        let mut child = Command::new(binary)
+            .env(ENV_TRANSPORT, "stdio")
+            .env(ENV_BRAVE_SEARCH_API_KEY, "conformance-key")
+            .env(ENV_ENDPOINT_WEB, format!("{backend}/web"))
+            .env(ENV_ENDPOINT_SUGGEST, format!("{backend}/suggest"))
+            .env(ENV_RETRY_COUNT, "0")
+            .env(ENV_CACHE_TTL_SECS, "0")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|error| format!("cannot start {}: {error}", binary.display()))?;
+        let stdin = child.stdin.take().ok_or("server stdin unavailable")?;
+        let stdout = child.stdout.take().ok_or("server stdout unavailable")?;
+
+        let (sender, lines) = channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            lines,
+            next_id: 1,
+        })
+    }
+
+    fn send(&mut self, message: &Value) -> CheckResult {
+        writeln!(self.stdin, "{message}")
+            .and_then(|()| self.stdin.flush())
+            .map_err(|error| format!("write to server failed: {error}"))
+    }
+
+    fn notify(&mut self, method: &str) -> CheckResult {
+        self.send(&json!({"jsonrpc": "2.0", "method": method}))
+    }
+
+    /// Sends a request and returns its `result`, skipping notifications and unrelated messages.
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(&json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))?;
+
+        loop {
+            let line = match self.lines.recv_timeout(RESPONSE_TIMEOUT) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(format!("{method}: no response within {RESPONSE_TIMEOUT:?}"));
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(format!("{method}: server closed stdout"));
+                }
+            };
+            let message: Value = serde_json::from_str(&line)
+                .map_err(|error| format!("{method}: non-JSON line on stdout ({error}): {line}"))?;
+            if message["jsonrpc"] != "2.0" {
+                return Err(format!("{method}: message without jsonrpc 2.0: {line}"));
+            }
+            if message["id"] != json!(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                return Err(format!("{method}: JSON-RPC error {error}"));
+            }
+            return message
+                .get("result")
+                .cloned()
+                .ok_or_else(|| format!("{method}: response without result: {line}"));
+        }
+    }
+
+    /// Calls a tool and returns `(is_error, parsed text payload)`.
+    fn call_tool(&mut self, name: &str, arguments: Value) -> Result<(bool, Value), String> {
+        let result = self.request("tools/call", json!({"name": name, "arguments": arguments}))?;
+        let text = result["content"]
+            .as_array()
+            .and_then(|content| content.iter().find_map(|item| item["text"].as_str()))
+            .ok_or_else(|| format!("{name}: tool result without text content"))?;
+        let payload = serde_json::from_str(text)
+            .map_err(|error| format!("{name}: tool text is not JSON ({error})"))?;
+        Ok((result["isError"].as_bool().unwrap_or(false), payload))
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn ensure(condition: bool, message: impl FnOnce() -> String) -> CheckResult {
+    if condition { Ok(()) } else { Err(message()) }
+}
+
+fn check_initialize(session: &mut Session) -> CheckResult {
+    let result = session.request(
+        "initialize",
+        json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": {"name": "codex-brave-conformance", "version": env!("CARGO_PKG_VERSION")}
+        }),
+    )?;
+    ensure(result["protocolVersion"].is_string(), || {
+        format!("initialize: missing protocolVersion: {result}")
+    })?;
+    ensure(result["serverInfo"]["name"].is_string(), || {
+        format!("initialize: missing serverInfo.name: {result}")
+    })?;
+    ensure(result["capabilities"]["tools"].is_object(), || {
+        format!("initialize: tools capability not advertised: {result}")
+    })?;
+    session.notify("notifications/initialized")
+}
+
+fn check_list_tools(session: &mut Session) -> CheckResult {
+    let result = session.request("tools/list", json!({}))?;
+    let tools = result["tools"]
+        .as_array()
+        .ok_or_else(|| format!("tools/list: missing tools array: {result}"))?;
+    for expected in EXPECTED_TOOLS {
+        let tool = tools
+            .iter()
+            .find(|tool| tool["name"] == *expected)
+            .ok_or_else(|| format!("tools/list: {expected} missing"))?;
+        ensure(tool["inputSchema"]["type"] == "object", || {
+            format!("tools/list: {expected} has no object inputSchema")
+        })?;
+    }
+    let search = tools
+        .iter()
+        .find(|tool| tool["name"] == TOOL_BRAVE_WEB_SEARCH)
+        .ok_or("tools/list: search tool missing")?;
+    ensure(
+        search["inputSchema"]["additionalProperties"] == false,
+        || "tools/list: search schema accepts unknown properties".to_string(),
+    )
+}
+
+fn check_help(session: &mut Session) -> CheckResult {
+    let (is_error, payload) =
+        session.call_tool(TOOL_BRAVE_WEB_SEARCH_HELP, json!({"topic": "params"}))?;
+    ensure(!is_error, || format!("help: unexpected error {payload}"))?;
+    ensure(payload["sections"]["parameters"].is_object(), || {
+        format!("help: missing sections.parameters: {payload}")
+    })
+}
+
+fn check_status(session: &mut Session) -> CheckResult {
+    let (is_error, payload) = session.call_tool(TOOL_BRAVE_WEB_SEARCH_STATUS, json!({}))?;
+    ensure(!is_error, || format!("status: unexpected error {payload}"))?;
+    ensure(payload["status"].is_string(), || {
+        format!("status: missing status: {payload}")
+    })?;
+    ensure(payload["key_config"]["has_key"] == true, || {
+        format!("status: API key from the environment not detected: {payload}")
+    })
+}
+
+fn check_search(session: &mut Session) -> CheckResult {
+    let (is_error, payload) = session.call_tool(
+        TOOL_BRAVE_WEB_SEARCH,
+        json!({"query": "conformance", "max_results": 2}),
+    )?;
+    ensure(!is_error, || format!("search: unexpected error {payload}"))?;
+    ensure(payload["api_version"].is_string(), || {
+        format!("search: missing api_version: {payload}")
+    })?;
+    let urls = payload["sections"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|section| section["results"].as_array().into_iter().flatten())
+        .filter_map(|result| result["url"].as_str())
+        .collect::<Vec<_>>();
+    ensure(
+        urls == ["https://example.com/one", "https://example.com/two"],
+        || format!("search: unexpected results {urls:?}"),
+    )?;
+    ensure(payload["meta"]["trace_id"].is_string(), || {
+        format!("search: missing meta.trace_id: {payload}")
+    })?;
+    ensure(payload.get("warnings").is_none_or(Value::is_array), || {
+        format!("search: warnings is not an array: {payload}")
+    })
+}
+
+fn check_error_envelopes(session: &mut Session) -> CheckResult {
+    for (arguments, code) in [
+        (json!({"query": "   "}), "INVALID_ARGUMENT"),
+        (
+            json!({"query": "x", "search_type": "books"}),
+            "INVALID_ARGUMENT",
+        ),
+        (
+            json!({"query": "x", "not_a_param": true}),
+            "INVALID_ARGUMENT",
+        ),
+    ] {
+        let (is_error, payload) = session.call_tool(TOOL_BRAVE_WEB_SEARCH, arguments.clone())?;
+        ensure(is_error, || {
+            format!("error envelope: {arguments} did not fail: {payload}")
+        })?;
+        ensure(payload["error"]["code"] == code, || {
+            format!("error envelope: {arguments} returned {payload}, expected {code}")
+        })?;
+        ensure(payload["error"]["message"].is_string(), || {
+            format!("error envelope: {arguments} has no message: {payload}")
+        })?;
+    }
+    Ok(())
+}
+
+fn check_suggest(session: &mut Session) -> CheckResult {
+    let (is_error, payload) = session.call_tool(TOOL_BRAVE_SUGGEST, json!({"query": "rus"}))?;
+    ensure(!is_error, || format!("suggest: unexpected error {payload}"))?;
+    ensure(payload["suggestions"].is_array(), || {
+        format!("suggest: missing suggestions: {payload}")
+    })
+}
+
+async fn start_backend() -> MockServer {
+    let backend = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "query": {"original": "conformance", "more_results_available": false},
+            "web": {"results": [
+                {"title": "One", "url": "https://example.com/one", "description": "first"},
+                {"title": "Two", "url": "https://example.com/two", "description": "second"}
+            ]}
+        })))
+        .mount(&backend)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/suggest"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "type": "suggest",
+            "query": {"original": "rus"},
+            "results": [{"query": "rust"}, {"query": "rust language"}]
+        })))
+        .mount(&backend)
+        .await;
+    backend
+}
+
+fn server_binary() -> Result<PathBuf, String> {
+    if let Some(path) = std::env::args_os().nth(1) {
+        return Ok(PathBuf::from(path));
+    }
+    // target/<profile>/examples/conformance -> target/<profile>/codex-brave-web-search
+    let exe = std::env::current_exe().map_err(|error| error.to_string())?;
+    let binary = exe
+        .parent()
+        .and_then(|examples| examples.parent())
+        .map(|profile| {
+            profile.join(format!(
+                "codex-brave-web-search{}",
+                std::env::consts::EXE_SUFFIX
+            ))
+        })
+        .ok_or("cannot locate the target directory")?;
+    ensure(binary.exists(), || {
+        format!(
+            "{} not found; run `cargo build` first or pass the server binary path",
+            binary.display()
+        )
+    })?;
+    Ok(binary)
+}
+
+#[tokio::main]
+async fn main() -> std::process::ExitCode {
+    let backend = start_backend().await;
+    let outcome = server_binary().and_then(|binary| {
+        let mut session = Session::spawn(&binary, &backend.uri())?;
+        let checks: [(&str, Check); 7] = [
+            ("initialize", check_initialize),
+            ("tools/list", check_list_tools),
+            ("help", check_help),
+            ("status", check_status),
+            ("search", check_search),
+            ("suggest", check_suggest),
+            ("error envelopes", check_error_envelopes),
+        ];
+        for (name, check) in checks {
+            check(&mut session)?;
+            println!("ok   {name}");
+        }
+        Ok(())
+    });
+
+    match outcome {
+        Ok(()) => {
+            println!("conformance: all checks passed");
+            std::process::ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("FAIL {error}");
+            std::process::ExitCode::FAILURE
+        }
+    }
+}
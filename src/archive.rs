@@ -0,0 +1,77 @@
+//! On-disk archive of searches keyed by trace id, read back by `brave_web_search_replay`.
+
+use crate::constants::MAX_ARCHIVE_TRACE_ID_LENGTH;
+use crate::error::AppError;
+use crate::types::BraveWebSearchArgs;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedSearch {
+    pub trace_id: String,
+    pub archived_at_unix_secs: u64,
+    /// The request with every normalized and inferred value pinned, so replaying it repeats the
+    /// upstream call exactly.
+    pub request: BraveWebSearchArgs,
+    pub response: Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchArchive {
+    dir: PathBuf,
+}
+
+impl SearchArchive {
+    #[must_use]
+    pub const fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Writes `entry`; failures are logged rather than failing the search they belong to.
+    pub fn store(&self, entry: &ArchivedSearch) {
+        if !is_valid_trace_id(&entry.trace_id) {
+            tracing::warn!("not archiving search with unusable trace id");
+            return;
+        }
+        let path = entry_path(&self.dir, &entry.trace_id);
+        let temp_path = path.with_extension("json.tmp");
+        let result = std::fs::create_dir_all(&self.dir)
+            .and_then(|()| serde_json::to_vec(entry).map_err(std::io::Error::other))
+            .and_then(|bytes| std::fs::write(&temp_path, bytes))
+            .and_then(|()| std::fs::rename(&temp_path, &path));
+        if let Err(error) = result {
+            tracing::warn!("failed to archive search {}: {error}", entry.trace_id);
+        }
+    }
+
+    pub fn load(&self, trace_id: &str) -> Result<ArchivedSearch, AppError> {
+        let not_found = || {
+            AppError::invalid_argument_with_details(
+                "no archived search exists for this trace_id",
+                serde_json::json!({"field": "trace_id"}),
+            )
+        };
+        if !is_valid_trace_id(trace_id) {
+            return Err(not_found());
+        }
+        let bytes = std::fs::read(entry_path(&self.dir, trace_id)).map_err(|_| not_found())?;
+        serde_json::from_slice(&bytes).map_err(|error| {
+            AppError::Internal(format!("archived search {trace_id} is unreadable: {error}"))
+        })
+    }
+}
+
+/// Trace ids become file names, so only characters that cannot escape the archive directory
+/// are accepted.
+fn is_valid_trace_id(trace_id: &str) -> bool {
+    !trace_id.is_empty()
+        && trace_id.len() <= MAX_ARCHIVE_TRACE_ID_LENGTH
+        && trace_id
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || matches!(character, '-' | '_'))
+}
+
+fn entry_path(dir: &Path, trace_id: &str) -> PathBuf {
+    dir.join(format!("{trace_id}.json"))
+}
@@ -0,0 +1,426 @@
+use crate::constants::{
+    API_VERSION, BATCH_JOB_RETENTION_SECS, BATCH_PERSIST_INTERVAL_MS, DEFAULT_BATCH_DEADLINE_SECS,
+    MAX_BATCH_DEADLINE_SECS, MAX_BATCH_QUERIES, NOTIFY_EVENT_BATCH_COMPLETED,
+};
+use crate::error::AppError;
+use crate::formatting::build_response_payload;
+use crate::notify::Notifier;
+use crate::service::SearchService;
+use crate::types::{
+    BatchJobStatus, BatchQueryResult, BatchQueryStatus, BatchScheduleArgs, BatchScheduleResponse,
+    BatchStatusResponse, BraveWebSearchArgs, ToolErrorInfo, WarningCode, WarningEntry,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BatchJob {
+    job_id: String,
+    status: BatchJobStatus,
+    interval_ms: u64,
+    template: BatchScheduleArgs,
+    results: Vec<BatchQueryResult>,
+    finished_at_unix_secs: Option<u64>,
+    /// When this process last wrote the job to the state dir.
+    #[serde(skip)]
+    persisted_at: Option<Instant>,
+}
+
+impl BatchJob {
+    const fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            BatchJobStatus::Done | BatchJobStatus::Partial | BatchJobStatus::Failed
+        )
+    }
+
+    fn is_expired(&self, now: u64) -> bool {
+        self.finished_at_unix_secs
+            .is_some_and(|finished| now.saturating_sub(finished) >= BATCH_JOB_RETENTION_SECS)
+    }
+}
+
+#[derive(Debug)]
+struct JobStore {
+    jobs: Mutex<HashMap<String, BatchJob>>,
+    state_dir: Option<PathBuf>,
+    notifier: Option<Notifier>,
+}
+
+impl JobStore {
+    /// Applies `update`, then writes a snapshot of the job to the state dir after releasing the
+    /// lock. A running job is written at most every [`BATCH_PERSIST_INTERVAL_MS`]; results
+    /// newer than the last write are re-run after a restart.
+    async fn update<U>(&self, job_id: &str, update: U)
+    where
+        U: FnOnce(&mut BatchJob),
+    {
+        let snapshot = {
+            let mut jobs = self.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+            let Some(job) = jobs.get_mut(job_id) else {
+                return;
+            };
+            update(job);
+            let due = job.is_finished()
+                || job.persisted_at.is_none_or(|at| {
+                    at.elapsed() >= Duration::from_millis(BATCH_PERSIST_INTERVAL_MS)
+                });
+            (due && self.state_dir.is_some()).then(|| {
+                job.persisted_at = Some(Instant::now());
+                job.clone()
+            })
+        };
+        if let Some(job) = snapshot {
+            self.persist(job).await;
+        }
+    }
+
+    async fn persist(&self, job: BatchJob) {
+        let Some(dir) = self.state_dir.clone() else {
+            return;
+        };
+        let job_id = job.job_id.clone();
+        let result = tokio::task::spawn_blocking(move || write_job(&dir, &job))
+            .await
+            .unwrap_or_else(|error| Err(std::io::Error::other(error)));
+        if let Err(error) = result {
+            tracing::warn!("failed to persist batch job {job_id}: {error}");
+        }
+    }
+
+    fn remove_files(&self, job_ids: Vec<String>) {
+        let Some(dir) = self.state_dir.clone() else {
+            return;
+        };
+        tokio::task::spawn_blocking(move || {
+            for job_id in job_ids {
+                std::fs::remove_file(job_path(&dir, &job_id)).ok();
+            }
+        });
+    }
+}
+
+fn write_job(dir: &Path, job: &BatchJob) -> std::io::Result<()> {
+    let path = job_path(dir, &job.job_id);
+    let temp_path = path.with_extension("json.tmp");
+    let bytes = serde_json::to_vec(job).map_err(std::io::Error::other)?;
+    std::fs::write(&temp_path, bytes)?;
+    std::fs::rename(&temp_path, &path)
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchScheduler {
+    service: Arc<SearchService>,
+    store: Arc<JobStore>,
+}
+
+impl BatchScheduler {
+    #[must_use]
+    pub fn new(service: Arc<SearchService>) -> Self {
+        let state_dir = service.batch_state_dir().map(Path::to_path_buf);
+        let jobs = state_dir.as_deref().map(load_jobs).unwrap_or_default();
+        let notifier = Notifier::from_config(service.config());
+        Self {
+            service,
+            store: Arc::new(JobStore {
+                jobs: Mutex::new(jobs),
+                state_dir,
+                notifier,
+            }),
+        }
+    }
+
+    /// Restarts persisted jobs that were still outstanding when the server last stopped.
+    pub fn resume(&self) -> usize {
+        let outstanding = {
+            let jobs = self
+                .store
+                .jobs
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            jobs.values()
+                .filter(|job| !job.is_finished())
+                .map(|job| job.job_id.clone())
+                .collect::<Vec<String>>()
+        };
+        for job_id in &outstanding {
+            tokio::spawn(run_job(
+                Arc::clone(&self.service),
+                Arc::clone(&self.store),
+                job_id.clone(),
+            ));
+        }
+        outstanding.len()
+    }
+
+    pub fn schedule(&self, args: BatchScheduleArgs) -> Result<BatchScheduleResponse, AppError> {
+        let queries = args
+            .queries
+            .iter()
+            .map(|query| query.trim().to_string())
+            .filter(|query| !query.is_empty())
+            .collect::<Vec<String>>();
+        if queries.is_empty() {
+            return Err(AppError::invalid_argument_with_details(
+                "queries must contain at least one non-empty query",
+                serde_json::json!({"field": "queries"}),
+            ));
+        }
+        if queries.len() > MAX_BATCH_QUERIES {
+            return Err(AppError::invalid_argument_with_details(
+                format!("queries must contain at most {MAX_BATCH_QUERIES} entries"),
+                serde_json::json!({"field": "queries", "count": queries.len()}),
+            ));
+        }
+
+        let deadline_secs = args
+            .deadline_secs
+            .unwrap_or(DEFAULT_BATCH_DEADLINE_SECS)
+            .clamp(1, MAX_BATCH_DEADLINE_SECS);
+        let slots = queries.len() as u64;
+        let min_interval_ms = 1_000_u64.div_ceil(u64::from(self.service.throttle_rate_per_sec()));
+        let interval_ms = (deadline_secs * 1_000 / slots).max(min_interval_ms);
+        let estimated_completion_secs = (interval_ms * (slots - 1)).div_ceil(1_000);
+
+        let mut warnings = Vec::<WarningEntry>::new();
+        if estimated_completion_secs > deadline_secs {
+            warnings.push(WarningEntry {
+                code: WarningCode::BatchDeadlineExceeded,
+                message: format!(
+                    "Throttle limits need about {estimated_completion_secs}s for {slots} queries; the {deadline_secs}s deadline cannot be met."
+                ),
+            });
+        }
+
+        let job_id = Uuid::new_v4().to_string();
+        let job = BatchJob {
+            job_id: job_id.clone(),
+            status: BatchJobStatus::Pending,
+            interval_ms,
+            results: queries
+                .into_iter()
+                .map(|query| BatchQueryResult {
+                    query,
+                    status: BatchQueryStatus::Pending,
+                    response: None,
+                    error: None,
+                })
+                .collect(),
+            template: BatchScheduleArgs {
+                queries: Vec::new(),
+                ..args
+            },
+            finished_at_unix_secs: None,
+            persisted_at: None,
+        };
+        // The job is first written to disk by `run_job`, so its writes stay in order.
+        let expired = {
+            let mut jobs = self
+                .store
+                .jobs
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let now = unix_now();
+            let expired = jobs
+                .iter()
+                .filter(|(_, job)| job.is_expired(now))
+                .map(|(job_id, _)| job_id.clone())
+                .collect::<Vec<String>>();
+            for job_id in &expired {
+                jobs.remove(job_id);
+            }
+            jobs.insert(job_id.clone(), job);
+            expired
+        };
+        self.store.remove_files(expired);
+
+        tokio::spawn(run_job(
+            Arc::clone(&self.service),
+            Arc::clone(&self.store),
+            job_id.clone(),
+        ));
+
+        Ok(BatchScheduleResponse {
+            api_version: API_VERSION.to_string(),
+            job_id,
+            status: BatchJobStatus::Pending,
+            total: slots as usize,
+            interval_ms,
+            estimated_completion_secs,
+            warnings,
+        })
+    }
+
+    pub fn status(&self, job_id: &str) -> Result<BatchStatusResponse, AppError> {
+        let jobs = self
+            .store
+            .jobs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        jobs.get(job_id.trim()).map(status_snapshot).ok_or_else(|| {
+            AppError::invalid_argument_with_details(
+                "unknown or expired job_id",
+                serde_json::json!({"field": "job_id"}),
+            )
+        })
+    }
+}
+
+fn status_snapshot(job: &BatchJob) -> BatchStatusResponse {
+    BatchStatusResponse {
+        api_version: API_VERSION.to_string(),
+        job_id: job.job_id.clone(),
+        status: job.status,
+        total: job.results.len(),
+        completed: count_status(&job.results, BatchQueryStatus::Done),
+        failed: count_status(&job.results, BatchQueryStatus::Failed),
+        results: job.results.clone(),
+    }
+}
+
+fn count_status(results: &[BatchQueryResult], status: BatchQueryStatus) -> usize {
+    results
+        .iter()
+        .filter(|result| result.status == status)
+        .count()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+fn job_path(dir: &Path, job_id: &str) -> PathBuf {
+    dir.join(format!("{job_id}.json"))
+}
+
+fn load_jobs(dir: &Path) -> HashMap<String, BatchJob> {
+    if let Err(error) = std::fs::create_dir_all(dir) {
+        tracing::warn!(
+            "failed to create batch state dir {}: {error}",
+            dir.display()
+        );
+        return HashMap::new();
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return HashMap::new();
+    };
+
+    let now = unix_now();
+    let mut jobs = HashMap::new();
+    for path in entries.filter_map(Result::ok).map(|entry| entry.path()) {
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let job = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<BatchJob>(&bytes).ok());
+        match job {
+            Some(job) if !job.is_expired(now) => {
+                jobs.insert(job.job_id.clone(), job);
+            }
+            Some(_) => {
+                std::fs::remove_file(&path).ok();
+            }
+            None => tracing::warn!("ignoring unreadable batch job file {}", path.display()),
+        }
+    }
+    jobs
+}
+
+async fn run_job(service: Arc<SearchService>, store: Arc<JobStore>, job_id: String) {
+    let Some((pending, template, interval)) = ({
+        let jobs = store.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+        jobs.get(&job_id).map(|job| {
+            let pending = job
+                .results
+                .iter()
+                .enumerate()
+                .filter(|(_, result)| result.status == BatchQueryStatus::Pending)
+                .map(|(index, result)| (index, result.query.clone()))
+                .collect::<Vec<(usize, String)>>();
+            (
+                pending,
+                job.template.clone(),
+                Duration::from_millis(job.interval_ms),
+            )
+        })
+    }) else {
+        return;
+    };
+
+    for (position, (index, query)) in pending.into_iter().enumerate() {
+        if position > 0 {
+            tokio::time::sleep(interval).await;
+        }
+        store
+            .update(&job_id, |job| job.status = BatchJobStatus::Running)
+            .await;
+
+        let args = BraveWebSearchArgs {
+            query,
+            search_type: template.search_type.clone(),
+            max_results: template.max_results,
+            country: template.country.clone(),
+            search_language: template.search_language.clone(),
+            ..Default::default()
+        };
+        let trace_id = format!("{job_id}-{index}");
+        let outcome = match service.execute_web_search(args, &trace_id, || false).await {
+            Ok(response) => build_response_payload(&response)
+                .map_err(|error| AppError::Internal(format!("Failed to build response: {error}"))),
+            Err(error) => Err(error),
+        };
+
+        store
+            .update(&job_id, |job| {
+                let result = &mut job.results[index];
+                match outcome {
+                    Ok(payload) => {
+                        result.status = BatchQueryStatus::Done;
+                        result.response = Some(payload);
+                    }
+                    Err(error) => {
+                        result.status = BatchQueryStatus::Failed;
+                        result.error = Some(ToolErrorInfo {
+                            code: error.code().to_string(),
+                            message: error.message(),
+                            details: error.details(),
+                        });
+                    }
+                }
+            })
+            .await;
+    }
+
+    store
+        .update(&job_id, |job| {
+            let failed = count_status(&job.results, BatchQueryStatus::Failed);
+            job.status = if failed == 0 {
+                BatchJobStatus::Done
+            } else if failed == job.results.len() {
+                BatchJobStatus::Failed
+            } else {
+                BatchJobStatus::Partial
+            };
+            job.finished_at_unix_secs = Some(unix_now());
+        })
+        .await;
+
+    if let Some(notifier) = &store.notifier {
+        let snapshot = {
+            let jobs = store.jobs.lock().unwrap_or_else(PoisonError::into_inner);
+            jobs.get(&job_id).map(status_snapshot)
+        };
+        if let Some(snapshot) = snapshot {
+            notifier
+                .deliver(NOTIFY_EVENT_BATCH_COMPLETED, &job_id, &snapshot)
+                .await;
+        }
+    }
+}
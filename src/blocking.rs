@@ -0,0 +1,73 @@
+use uuid::Uuid;
+
+use crate::client::BraveClient;
+use crate::config::RuntimeConfig;
+use crate::error::AppError;
+use crate::provider::SearchProvider;
+use crate::service::SearchService;
+use crate::types::{
+    BraveWebSearchArgs, SearchResponse, StatusArgs, StatusResponse, SuggestArgs, SuggestResponse,
+};
+
+/// Synchronous wrapper around [`SearchService`] for callers without a tokio runtime.
+///
+/// Owns a current-thread runtime and blocks on it for every call, so it must not be used from
+/// inside an async context.
+#[derive(Debug)]
+pub struct SearchServiceBlocking<P = BraveClient> {
+    runtime: tokio::runtime::Runtime,
+    service: SearchService<P>,
+}
+
+impl SearchServiceBlocking {
+    pub fn new(config: RuntimeConfig) -> Result<Self, AppError> {
+        let runtime = build_runtime()?;
+        let service = {
+            let _guard = runtime.enter();
+            SearchService::new(config)?
+        };
+        Ok(Self { runtime, service })
+    }
+
+    pub fn from_env() -> Result<Self, AppError> {
+        Self::new(RuntimeConfig::from_env())
+    }
+
+    pub fn suggest(&self, args: SuggestArgs) -> Result<SuggestResponse, AppError> {
+        let trace_id = Uuid::new_v4().to_string();
+        self.runtime
+            .block_on(self.service.execute_suggest(args, &trace_id, || false))
+    }
+}
+
+impl<P: SearchProvider> SearchServiceBlocking<P> {
+    pub fn with_provider(config: RuntimeConfig, provider: P) -> Result<Self, AppError> {
+        let runtime = build_runtime()?;
+        let service = {
+            let _guard = runtime.enter();
+            SearchService::with_provider(config, provider)
+        };
+        Ok(Self { runtime, service })
+    }
+
+    pub fn search(&self, args: BraveWebSearchArgs) -> Result<SearchResponse, AppError> {
+        let trace_id = Uuid::new_v4().to_string();
+        self.runtime
+            .block_on(self.service.execute_web_search(args, &trace_id, || false))
+    }
+
+    pub fn status(&self, args: StatusArgs) -> StatusResponse {
+        self.runtime.block_on(self.service.status(args, || false))
+    }
+
+    pub const fn service(&self) -> &SearchService<P> {
+        &self.service
+    }
+}
+
+fn build_runtime() -> Result<tokio::runtime::Runtime, AppError> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|err| AppError::Internal(format!("failed to start blocking runtime: {err}")))
+}
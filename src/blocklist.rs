@@ -0,0 +1,128 @@
+//! Server-side domain blocklist: results on a blocked host are removed before they reach the
+//! agent.
+//!
+//! A plain pattern (`example.com`) blocks the host and all of its subdomains; `*` matches any run
+//! of characters, so `*.example.com` blocks only subdomains and `bad-*.net` blocks a family of
+//! hosts.
+
+use crate::types::ParsedSection;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DomainBlocklist {
+    patterns: Vec<String>,
+}
+
+impl DomainBlocklist {
+    /// Builds the list from comma- or whitespace-separated patterns; `#` starts a comment that
+    /// runs to the end of the line. Invalid patterns are skipped.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let mut patterns = pattern_tokens(text)
+            .filter_map(|token| normalize_pattern(token).ok())
+            .collect::<Vec<String>>();
+        patterns.sort_unstable();
+        patterns.dedup();
+        Self { patterns }
+    }
+
+    #[must_use]
+    pub fn extend(mut self, other: Self) -> Self {
+        self.patterns.extend(other.patterns);
+        self.patterns.sort_unstable();
+        self.patterns.dedup();
+        self
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Whether the URL's host matches a blocked pattern; URLs without a host are never blocked.
+    #[must_use]
+    pub fn blocks_url(&self, url: &str) -> bool {
+        let Some(host) = url::Url::parse(url).ok().and_then(|url| {
+            url.host_str()
+                .map(|host| host.trim_end_matches('.').to_lowercase())
+        }) else {
+            return false;
+        };
+        self.patterns.iter().any(|pattern| {
+            if pattern.contains('*') {
+                wildcard_match(pattern, &host)
+            } else {
+                host == *pattern
+                    || host
+                        .strip_suffix(pattern.as_str())
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            }
+        })
+    }
+
+    /// Removes blocked results, dropping sections this leaves empty. Returns how many were
+    /// removed.
+    pub fn retain_allowed(&self, sections: &mut Vec<ParsedSection>) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let mut removed = 0usize;
+        sections.retain_mut(|section| {
+            let count = section.results.len();
+            section
+                .results
+                .retain(|result| !self.blocks_url(&result.url));
+            removed += count - section.results.len();
+            count == section.results.len() || !section.results.is_empty()
+        });
+        removed
+    }
+}
+
+/// Checks every pattern in `text`, reporting the first invalid one.
+pub fn validate_patterns(text: &str) -> Result<(), String> {
+    pattern_tokens(text).try_for_each(|token| normalize_pattern(token).map(|_| ()))
+}
+
+fn pattern_tokens(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(|line| line.split_once('#').map_or(line, |(before, _)| before))
+        .flat_map(|line| line.split(|c: char| c == ',' || c.is_whitespace()))
+        .filter(|token| !token.is_empty())
+}
+
+fn normalize_pattern(token: &str) -> Result<String, String> {
+    let pattern = token.trim_end_matches('.').to_lowercase();
+    let valid = !pattern.is_empty()
+        && pattern != "*"
+        && pattern
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '*'));
+    if valid {
+        Ok(pattern)
+    } else {
+        Err(format!("invalid domain pattern '{token}'"))
+    }
+}
+
+/// Glob match where `*` stands for any run of characters, including dots.
+fn wildcard_match(pattern: &str, host: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = host.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<&str>>();
+    let last = parts.pop().unwrap_or_default();
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
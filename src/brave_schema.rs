@@ -0,0 +1,354 @@
+//! Typed views of the Brave Search API payloads consumed by [`crate::parsing`].
+//!
+//! Every field is optional or defaulted and tolerates unexpected shapes: a field with the wrong
+//! JSON type reads as missing and malformed array entries are skipped, so a partially broken
+//! payload still yields whatever results it does contain.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Response of the web, news, images and videos search endpoints.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SearchPayload {
+    #[serde(rename = "type", deserialize_with = "lenient")]
+    pub payload_type: Option<String>,
+    #[serde(deserialize_with = "object")]
+    pub query: Option<QueryInfo>,
+    #[serde(deserialize_with = "object")]
+    pub web: Option<ResultList>,
+    #[serde(deserialize_with = "object")]
+    pub discussions: Option<ResultList>,
+    #[serde(deserialize_with = "object")]
+    pub infobox: Option<ResultList>,
+    #[serde(deserialize_with = "object")]
+    pub locations: Option<ResultList>,
+    #[serde(deserialize_with = "object")]
+    pub faq: Option<ResultList>,
+    #[serde(deserialize_with = "object")]
+    pub news: Option<ResultList>,
+    #[serde(deserialize_with = "object")]
+    pub images: Option<ResultList>,
+    #[serde(deserialize_with = "object")]
+    pub videos: Option<ResultList>,
+    /// Brave's relevance ordering across the sections of a web payload.
+    #[serde(deserialize_with = "object")]
+    pub mixed: Option<MixedOrder>,
+    /// Top-level results of the dedicated news/images/videos endpoints.
+    #[serde(deserialize_with = "objects")]
+    pub results: Vec<SearchResult>,
+}
+
+impl SearchPayload {
+    /// Reads a raw payload; anything that is not a JSON object yields an empty payload.
+    #[must_use]
+    pub fn from_value(payload: &Value) -> Self {
+        if payload.is_object() {
+            Self::deserialize(payload).unwrap_or_default()
+        } else {
+            Self::default()
+        }
+    }
+
+    #[must_use]
+    pub fn more_results_available(&self) -> bool {
+        self.query
+            .as_ref()
+            .is_some_and(|query| query.more_results_available)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct QueryInfo {
+    #[serde(deserialize_with = "lenient")]
+    pub original: Option<String>,
+    #[serde(deserialize_with = "lenient")]
+    pub more_results_available: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MixedOrder {
+    #[serde(deserialize_with = "objects")]
+    pub main: Vec<MixedEntry>,
+}
+
+/// One slot of the mixed ordering: result `index` of section `type`, or the whole section when
+/// `all` is set.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MixedEntry {
+    #[serde(rename = "type", deserialize_with = "text")]
+    pub section: Option<String>,
+    #[serde(deserialize_with = "lenient")]
+    pub index: Option<usize>,
+    #[serde(deserialize_with = "lenient")]
+    pub all: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ResultList {
+    #[serde(deserialize_with = "objects")]
+    pub results: Vec<SearchResult>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SearchResult {
+    #[serde(deserialize_with = "lenient")]
+    pub title: Option<String>,
+    #[serde(deserialize_with = "lenient")]
+    pub url: Option<String>,
+    #[serde(deserialize_with = "lenient")]
+    pub provider_url: Option<String>,
+    #[serde(deserialize_with = "lenient")]
+    pub description: Option<String>,
+    #[serde(deserialize_with = "lenient")]
+    pub snippet: Option<String>,
+    /// FAQ entries carry a question/answer pair alongside the page title.
+    #[serde(deserialize_with = "lenient")]
+    pub question: Option<String>,
+    #[serde(deserialize_with = "lenient")]
+    pub answer: Option<String>,
+    #[serde(deserialize_with = "lenient_vec")]
+    pub extra_snippets: Vec<String>,
+    #[serde(deserialize_with = "object")]
+    pub profile: Option<Profile>,
+    #[serde(deserialize_with = "text")]
+    pub source: Option<String>,
+    #[serde(deserialize_with = "text")]
+    pub source_name: Option<String>,
+    #[serde(deserialize_with = "text")]
+    pub age: Option<String>,
+    #[serde(deserialize_with = "text")]
+    pub page_age: Option<String>,
+    #[serde(rename = "type", deserialize_with = "text")]
+    pub result_type: Option<String>,
+    #[serde(deserialize_with = "text")]
+    pub subtype: Option<String>,
+    #[serde(deserialize_with = "object")]
+    pub video: Option<VideoInfo>,
+    #[serde(deserialize_with = "object")]
+    pub postal_address: Option<PostalAddress>,
+    #[serde(deserialize_with = "text")]
+    pub id: Option<String>,
+    #[serde(deserialize_with = "text")]
+    pub location: Option<String>,
+    #[serde(deserialize_with = "object")]
+    pub thumbnail: Option<Thumbnail>,
+    #[serde(deserialize_with = "object")]
+    pub properties: Option<ImageProperties>,
+    #[serde(deserialize_with = "lenient")]
+    pub is_live: bool,
+    #[serde(deserialize_with = "lenient")]
+    pub breaking: bool,
+    #[serde(deserialize_with = "object")]
+    pub meta_url: Option<MetaUrl>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    #[serde(deserialize_with = "text")]
+    pub name: Option<String>,
+    #[serde(deserialize_with = "text")]
+    pub long_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct VideoInfo {
+    #[serde(deserialize_with = "text")]
+    pub duration: Option<String>,
+    #[serde(deserialize_with = "text")]
+    pub creator: Option<String>,
+    #[serde(deserialize_with = "text")]
+    pub publisher: Option<String>,
+    /// View count; Brave sends it as a number or a numeric string.
+    #[serde(deserialize_with = "text")]
+    pub views: Option<String>,
+    #[serde(deserialize_with = "lenient")]
+    pub embeddable: Option<bool>,
+    #[serde(deserialize_with = "object")]
+    pub thumbnail: Option<Thumbnail>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PostalAddress {
+    #[serde(rename = "displayAddress", deserialize_with = "text")]
+    pub display_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MetaUrl {
+    #[serde(deserialize_with = "text")]
+    pub hostname: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Thumbnail {
+    #[serde(deserialize_with = "lenient")]
+    pub src: Option<String>,
+}
+
+/// Full-size image of an image search result.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ImageProperties {
+    #[serde(deserialize_with = "lenient")]
+    pub url: Option<String>,
+    #[serde(deserialize_with = "lenient")]
+    pub width: Option<u32>,
+    #[serde(deserialize_with = "lenient")]
+    pub height: Option<u32>,
+    #[serde(deserialize_with = "text")]
+    pub format: Option<String>,
+}
+
+/// Response of the local POI endpoint.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LocalPoisPayload {
+    #[serde(deserialize_with = "objects")]
+    pub results: Vec<LocalPoi>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LocalPoi {
+    #[serde(deserialize_with = "text")]
+    pub id: Option<String>,
+    #[serde(deserialize_with = "object")]
+    pub postal_address: Option<PostalAddress>,
+    #[serde(deserialize_with = "object")]
+    pub contact: Option<Contact>,
+    #[serde(deserialize_with = "object")]
+    pub rating: Option<Rating>,
+    #[serde(deserialize_with = "object")]
+    pub opening_hours: Option<OpeningHours>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Contact {
+    #[serde(deserialize_with = "text")]
+    pub telephone: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Rating {
+    #[serde(rename = "ratingValue", deserialize_with = "lenient")]
+    pub rating_value: Option<f64>,
+    #[serde(rename = "reviewCount", deserialize_with = "lenient")]
+    pub review_count: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct OpeningHours {
+    #[serde(deserialize_with = "lenient_vec")]
+    pub days: Vec<Vec<OpeningSlot>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct OpeningSlot {
+    #[serde(deserialize_with = "text")]
+    pub abbr_name: Option<String>,
+    #[serde(deserialize_with = "text")]
+    pub full_name: Option<String>,
+    #[serde(deserialize_with = "text")]
+    pub opens: Option<String>,
+    #[serde(deserialize_with = "text")]
+    pub closes: Option<String>,
+}
+
+/// Response of the local descriptions endpoint.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LocalDescriptionsPayload {
+    #[serde(deserialize_with = "objects")]
+    pub results: Vec<LocalDescription>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LocalDescription {
+    #[serde(deserialize_with = "text")]
+    pub id: Option<String>,
+    #[serde(deserialize_with = "text")]
+    pub description: Option<String>,
+}
+
+/// `T`, or its default when the value has another shape.
+fn lenient<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(T::deserialize(value).unwrap_or_default())
+}
+
+/// Array entries that read as `T`; a non-array reads as empty.
+fn lenient_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Array(items) => items
+            .into_iter()
+            .filter_map(|item| T::deserialize(item).ok())
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+/// Like [`lenient_vec`], but only JSON objects count as entries.
+fn objects<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Array(items) => items
+            .into_iter()
+            .filter(Value::is_object)
+            .filter_map(|item| T::deserialize(item).ok())
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+/// A nested struct, present only when the value is a JSON object.
+fn object<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value = Value::deserialize(deserializer)?;
+    Ok(value
+        .is_object()
+        .then(|| T::deserialize(value).ok())
+        .flatten())
+}
+
+/// Strings, and numbers rendered as text; other values read as missing.
+fn text<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(text) => Some(text),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    })
+}
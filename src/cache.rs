@@ -1,40 +1,322 @@
 use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone)]
+use crate::types::{CacheStats, FetchSearchParams, SearchType};
+use sha2::{Digest, Sha256};
+
+/// Hex SHA-256 of everything that shapes an upstream search, shared by the search cache and
+/// recorded payloads.
+#[must_use]
+pub fn search_cache_key(
+    query: &str,
+    search_type: SearchType,
+    params: &FetchSearchParams,
+) -> String {
+    let material = serde_json::json!({
+        "query": query,
+        "search_type": search_type.as_str(),
+        "count": params.count,
+        "offset": params.offset,
+        "country": params.country,
+        "search_language": params.search_language,
+        "ui_language": params.ui_language,
+        "safe_search": params.safe_search,
+        "freshness": params.freshness,
+        "goggles": params.goggles,
+        "result_filter_values": params
+            .result_filter_values
+            .iter()
+            .map(|v| v.as_str())
+            .collect::<Vec<&str>>(),
+        "units": params.units,
+        "spellcheck": params.spellcheck,
+        "extra_snippets": params.extra_snippets,
+        "text_decorations": params.text_decorations,
+        "order": params.order.as_str(),
+        "dedup": params.dedup.as_str(),
+        "dedupe_snippets": params.dedupe_snippets,
+    });
+
+    let bytes = serde_json::to_vec(&material).unwrap_or_default();
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    hex::encode(hasher.finalize())
+}
+
+const SHARD_COUNT: usize = 16;
+const INSERTS_PER_SHARD_SWEEP: usize = 64;
+const MIN_BOUNDED_ENTRIES_PER_SHARD: usize = 64;
+
+#[derive(Debug)]
 struct CacheEntry<T> {
     inserted_at: Instant,
     value: T,
 }
 
+#[derive(Debug)]
+struct Node<T> {
+    key: String,
+    entry: CacheEntry<T>,
+    newer: Option<usize>,
+    older: Option<usize>,
+}
+
+/// One shard's entries: a slab of nodes linked from most to least recently used and indexed by
+/// key, so lookups, touches and evictions are O(1).
+#[derive(Debug)]
+struct LruMap<T> {
+    index: HashMap<String, usize>,
+    slots: Vec<Option<Node<T>>>,
+    free: Vec<usize>,
+    newest: Option<usize>,
+    oldest: Option<usize>,
+    limit: Option<usize>,
+}
+
+impl<T> LruMap<T> {
+    fn new(limit: Option<usize>) -> Self {
+        Self {
+            index: HashMap::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            newest: None,
+            oldest: None,
+            limit,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    fn node(&self, slot: usize) -> &Node<T> {
+        self.slots[slot].as_ref().expect("linked slot is occupied")
+    }
+
+    fn node_mut(&mut self, slot: usize) -> &mut Node<T> {
+        self.slots[slot].as_mut().expect("linked slot is occupied")
+    }
+
+    fn get(&self, key: &str) -> Option<&CacheEntry<T>> {
+        self.index.get(key).map(|&slot| &self.node(slot).entry)
+    }
+
+    fn unlink(&mut self, slot: usize) {
+        let (newer, older) = {
+            let node = self.node(slot);
+            (node.newer, node.older)
+        };
+        match newer {
+            Some(newer) => self.node_mut(newer).older = older,
+            None => self.newest = older,
+        }
+        match older {
+            Some(older) => self.node_mut(older).newer = newer,
+            None => self.oldest = newer,
+        }
+    }
+
+    fn link_newest(&mut self, slot: usize) {
+        let previous = self.newest;
+        {
+            let node = self.node_mut(slot);
+            node.newer = None;
+            node.older = previous;
+        }
+        match previous {
+            Some(previous) => self.node_mut(previous).newer = Some(slot),
+            None => self.oldest = Some(slot),
+        }
+        self.newest = Some(slot);
+    }
+
+    /// Marks `key` as the most recently used entry.
+    fn touch(&mut self, key: &str) {
+        if let Some(&slot) = self.index.get(key) {
+            self.unlink(slot);
+            self.link_newest(slot);
+        }
+    }
+
+    fn insert(&mut self, key: String, entry: CacheEntry<T>) {
+        if let Some(&slot) = self.index.get(&key) {
+            self.node_mut(slot).entry = entry;
+            self.unlink(slot);
+            self.link_newest(slot);
+            return;
+        }
+        let node = Node {
+            key: key.clone(),
+            entry,
+            newer: None,
+            older: None,
+        };
+        let slot = if let Some(slot) = self.free.pop() {
+            self.slots[slot] = Some(node);
+            slot
+        } else {
+            self.slots.push(Some(node));
+            self.slots.len() - 1
+        };
+        self.index.insert(key, slot);
+        self.link_newest(slot);
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(slot) = self.index.remove(key) {
+            self.unlink(slot);
+            self.slots[slot] = None;
+            self.free.push(slot);
+        }
+    }
+
+    fn evict_oldest(&mut self) -> bool {
+        let Some(slot) = self.oldest else {
+            return false;
+        };
+        let key = self.node(slot).key.clone();
+        self.remove(&key);
+        true
+    }
+
+    fn purge_expired(&mut self, now: Instant, ttl: Duration) {
+        let expired = self
+            .index
+            .iter()
+            .filter(|&(_, &slot)| now.duration_since(self.node(slot).entry.inserted_at) >= ttl)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in expired {
+            self.remove(&key);
+        }
+    }
+
+    fn live(&self, now: Instant, ttl: Duration) -> usize {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|node| now.duration_since(node.entry.inserted_at) < ttl)
+            .count()
+    }
+}
+
+type Shard<T> = tokio::sync::RwLock<LruMap<T>>;
+
 #[derive(Debug)]
 pub struct SearchCache<T> {
     ttl: Duration,
-    entries: tokio::sync::RwLock<HashMap<String, CacheEntry<T>>>,
+    hasher: RandomState,
+    shards: Box<[Shard<T>]>,
+    inserts: AtomicUsize,
+    max_entries: Option<usize>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
 }
 
 impl<T: Clone> SearchCache<T> {
     #[must_use]
     pub fn new(ttl: Duration) -> Self {
+        Self::build(ttl, SHARD_COUNT, None)
+    }
+
+    /// Bounds the cache to exactly `max_entries` entries split across its shards. Recency is
+    /// tracked per shard, so eviction is approximate LRU: a full shard drops its own least
+    /// recently used entry even when another shard holds an older one. Small bounds use fewer
+    /// shards so eviction stays close to a global LRU.
+    #[must_use]
+    pub fn with_max_entries(ttl: Duration, max_entries: usize) -> Self {
+        let max_entries = max_entries.max(1);
+        let shard_count = (max_entries / MIN_BOUNDED_ENTRIES_PER_SHARD).clamp(1, SHARD_COUNT);
+        Self::build(ttl, shard_count, Some(max_entries))
+    }
+
+    fn build(ttl: Duration, shard_count: usize, max_entries: Option<usize>) -> Self {
+        // The remainder goes one entry each to the first shards, so the limits sum to the bound.
+        let shard_limit = |index: usize| {
+            max_entries.map(|max| max / shard_count + usize::from(index < max % shard_count))
+        };
         Self {
             ttl,
-            entries: tokio::sync::RwLock::new(HashMap::new()),
+            hasher: RandomState::new(),
+            shards: (0..shard_count)
+                .map(|index| tokio::sync::RwLock::new(LruMap::new(shard_limit(index))))
+                .collect(),
+            inserts: AtomicUsize::new(0),
+            max_entries,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
         }
     }
 
+    fn shard_for(&self, key: &str) -> &Shard<T> {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        &self.shards[index]
+    }
+
     pub async fn get(&self, key: &str) -> Option<T> {
+        self.get_max_age(key, None).await
+    }
+
+    /// Like [`Self::get`], but also treats live entries older than `max_age` as misses.
+    pub async fn get_max_age(&self, key: &str, max_age: Option<Duration>) -> Option<T> {
+        self.get_with_age(key, max_age)
+            .await
+            .map(|(value, _)| value)
+    }
+
+    /// Like [`Self::get_max_age`], but also returns how long ago the entry was inserted.
+    pub async fn get_with_age(
+        &self,
+        key: &str,
+        max_age: Option<Duration>,
+    ) -> Option<(T, Duration)> {
+        let value = self.lookup(key, max_age).await;
+        let counter = if value.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        value
+    }
+
+    async fn lookup(&self, key: &str, max_age: Option<Duration>) -> Option<(T, Duration)> {
         let now = Instant::now();
-        {
-            let entries = self.entries.read().await;
+        let shard = self.shard_for(key);
+        if self.max_entries.is_none() {
+            // Unbounded caches never evict, so recency is not tracked and reads can share.
+            let entries = shard.read().await;
+            let entry = entries.get(key)?;
+            let age = now.duration_since(entry.inserted_at);
+            if age < self.ttl {
+                return max_age
+                    .is_none_or(|max_age| age <= max_age)
+                    .then(|| (entry.value.clone(), age));
+            }
+        } else {
+            let mut entries = shard.write().await;
             let entry = entries.get(key)?;
-            if now.duration_since(entry.inserted_at) < self.ttl {
-                return Some(entry.value.clone());
+            let age = now.duration_since(entry.inserted_at);
+            if age < self.ttl {
+                if max_age.is_some_and(|max_age| age > max_age) {
+                    return None;
+                }
+                let value = entry.value.clone();
+                entries.touch(key);
+                return Some((value, age));
             }
+            entries.remove(key);
+            return None;
         }
 
-        let mut entries = self.entries.write().await;
-        if let Some(entry) = entries.get(key)
-            && now.duration_since(entry.inserted_at) >= self.ttl
+        let mut entries = shard.write().await;
+        if entries
+            .get(key)
+            .is_some_and(|entry| now.duration_since(entry.inserted_at) >= self.ttl)
         {
             entries.remove(key);
         }
@@ -43,36 +325,64 @@ impl<T: Clone> SearchCache<T> {
 
     pub async fn insert(&self, key: String, value: T) {
         let now = Instant::now();
-        let mut entries = self.entries.write().await;
-        purge_expired_entries(&mut entries, now, self.ttl);
-        entries.insert(
-            key,
-            CacheEntry {
-                inserted_at: now,
-                value,
-            },
-        );
+        {
+            let mut entries = self.shard_for(&key).write().await;
+            if let Some(limit) = entries.limit
+                && entries.get(&key).is_none()
+                && entries.len() >= limit
+            {
+                entries.purge_expired(now, self.ttl);
+                while entries.len() >= limit && entries.evict_oldest() {
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            entries.insert(
+                key,
+                CacheEntry {
+                    inserted_at: now,
+                    value,
+                },
+            );
+        }
+
+        // Sweep one shard round-robin every few inserts instead of purging the whole map on
+        // every write, so no single insert pays for a full scan.
+        let count = self.inserts.fetch_add(1, Ordering::Relaxed) + 1;
+        if count % INSERTS_PER_SHARD_SWEEP == 0 {
+            let index = (count / INSERTS_PER_SHARD_SWEEP) % self.shards.len();
+            if let Ok(mut entries) = self.shards[index].try_write() {
+                entries.purge_expired(now, self.ttl);
+            }
+        }
     }
 
     pub async fn purge_expired(&self) {
         let now = Instant::now();
-        let mut entries = self.entries.write().await;
-        purge_expired_entries(&mut entries, now, self.ttl);
+        for shard in &self.shards {
+            shard.write().await.purge_expired(now, self.ttl);
+        }
     }
 
     pub async fn len(&self) -> usize {
-        self.entries.read().await.len()
+        let now = Instant::now();
+        let mut live = 0usize;
+        for shard in &self.shards {
+            live += shard.read().await.live(now, self.ttl);
+        }
+        live
     }
 
     pub async fn is_empty(&self) -> bool {
         self.len().await == 0
     }
-}
 
-fn purge_expired_entries<T>(
-    entries: &mut HashMap<String, CacheEntry<T>>,
-    now: Instant,
-    ttl: Duration,
-) {
-    entries.retain(|_, entry| now.duration_since(entry.inserted_at) < ttl);
+    pub async fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.len().await,
+            max_entries: self.max_entries,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
 }
@@ -0,0 +1,154 @@
+//! Command line: the MCP server by default, or one-shot `search`, `status` and `schema` commands
+//! that bypass MCP for sanity-checking a key and the output.
+
+use crate::config::TransportMode;
+use crate::error::AppError;
+use crate::mcp_server::BraveSearchMcpServer;
+use crate::normalization::redact_secret;
+use crate::service::SearchService;
+use crate::types::{BraveWebSearchArgs, StatusArgs};
+use clap::{Args, Parser, Subcommand};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Parser)]
+#[command(name = "codex-brave-web-search", version, about)]
+pub struct Cli {
+    /// TOML config file (default: `CODEX_BRAVE_CONFIG`); environment variables override it.
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// MCP transport when serving: stdio or http.
+    #[arg(long, value_parser = parse_transport)]
+    pub transport: Option<TransportMode>,
+    /// Address the HTTP transport listens on.
+    #[arg(long)]
+    pub listen: Option<SocketAddr>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run one search and print the formatted response.
+    Search(SearchCommand),
+    /// Print the `brave_web_search_status` payload.
+    Status {
+        /// Probe each Brave endpoint with a live request.
+        #[arg(long)]
+        probe: bool,
+        /// Include the output limit settings.
+        #[arg(long)]
+        verbose: bool,
+    },
+    /// Print the MCP tool schemas, or the schema of one tool.
+    Schema {
+        /// Tool name, e.g. `brave_web_search`.
+        tool: Option<String>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct SearchCommand {
+    pub query: String,
+    /// web, news, images or videos.
+    #[arg(long = "type")]
+    pub search_type: Option<String>,
+    #[arg(long)]
+    pub max_results: Option<usize>,
+    #[arg(long)]
+    pub offset: Option<usize>,
+    #[arg(long)]
+    pub country: Option<String>,
+    #[arg(long)]
+    pub search_language: Option<String>,
+    #[arg(long)]
+    pub safe_search: Option<String>,
+    #[arg(long)]
+    pub freshness: Option<String>,
+    /// Output format, as the tool's `format` argument (e.g. text, json, markdown).
+    #[arg(long)]
+    pub format: Option<String>,
+}
+
+impl From<SearchCommand> for BraveWebSearchArgs {
+    fn from(command: SearchCommand) -> Self {
+        Self {
+            query: command.query,
+            search_type: command.search_type,
+            max_results: command.max_results,
+            offset: command.offset,
+            country: command.country,
+            search_language: command.search_language,
+            safe_search: command.safe_search,
+            freshness: command.freshness,
+            format: command.format,
+            ..Self::default()
+        }
+    }
+}
+
+fn parse_transport(value: &str) -> Result<TransportMode, String> {
+    TransportMode::from_env_value(value).ok_or_else(|| format!("unsupported transport: {value}"))
+}
+
+/// Runs a one-shot command and returns what it prints on stdout, with the API key masked.
+pub async fn run(command: Command, service: SearchService) -> Result<String, AppError> {
+    let key = service.provider().key_config().key.unwrap_or_default();
+    let redact = |text: &str| redact_secret(text, &key).into_owned();
+    Box::pin(run_command(command, service))
+        .await
+        .map(|output| redact(&output))
+        .map_err(|error| error.map_message(redact))
+}
+
+async fn run_command(command: Command, service: SearchService) -> Result<String, AppError> {
+    match command {
+        Command::Search(search) => {
+            let args = BraveWebSearchArgs::from(search);
+            let format = args.format.clone();
+            let trace_id = Uuid::new_v4().to_string();
+            let response = Box::pin(service.execute_web_search(args, &trace_id, || false)).await?;
+            service.render_response(&response, format.as_deref(), None)
+        }
+        Command::Status { probe, verbose } => {
+            let status = service
+                .status(
+                    StatusArgs {
+                        probe_connectivity: Some(probe),
+                        verbose: Some(verbose),
+                        include_limits: None,
+                    },
+                    || false,
+                )
+                .await;
+            to_pretty_json(&status)
+        }
+        Command::Schema { tool } => {
+            let schemas = BraveSearchMcpServer::new(service)
+                .tools()
+                .into_iter()
+                .filter(|schema| tool.as_ref().is_none_or(|name| schema.name == *name))
+                .map(|schema| {
+                    serde_json::json!({
+                        "name": schema.name,
+                        "description": schema.description,
+                        "inputSchema": schema.input_schema,
+                    })
+                })
+                .collect::<Vec<serde_json::Value>>();
+            match (tool, schemas.as_slice()) {
+                (Some(name), []) => {
+                    Err(AppError::invalid_argument(format!("unknown tool: {name}")))
+                }
+                (Some(_), [schema]) => to_pretty_json(schema),
+                _ => to_pretty_json(&schemas),
+            }
+        }
+    }
+}
+
+fn to_pretty_json<T: serde::Serialize>(value: &T) -> Result<String, AppError> {
+    serde_json::to_string_pretty(value)
+        .map_err(|error| AppError::Internal(format!("failed to serialize output: {error}")))
+}
@@ -1,43 +1,172 @@
-use crate::config::{ApiKeyConfig, RuntimeConfig};
-use crate::constants::{ERROR_CANCELLED, RETRYABLE_HTTP_STATUS, WARNING_RAW_PAYLOAD_TRUNCATED};
+use crate::cache::search_cache_key;
+use crate::config::{ApiKeyConfig, BraveEndpoints, RecordMode, RuntimeConfig};
+use crate::constants::{
+    CANCEL_POLL_INTERVAL_MS, ENV_RECORD_MODE, ERROR_CANCELLED, MAX_LOCAL_POI_IDS,
+    NOTICE_MISCONFIGURED_ENDPOINT, RAW_PAYLOAD_QUERY_ECHO_FIELDS, RETRY_BUDGET_EXHAUSTED,
+    RETRYABLE_HTTP_STATUS, SUMMARIZER_POLL_DELAY_MS,
+};
+use crate::endpoint_health::OverrideHealth;
 use crate::error::AppError;
-use crate::parsing::{parse_brave_error_message, parse_sections, query_echo_or_original};
-use crate::types::{FetchSearchParams, FetchSearchResult, SearchType, WarningEntry};
+use crate::parsing::{
+    parse_brave_error_code, parse_brave_error_message, parse_local_descriptions, parse_local_pois,
+    parse_sections_with_options, parse_spellcheck, parse_suggestions, parse_summarizer,
+    parse_summarizer_key, query_echo_or_original,
+};
+use crate::throttle::RetryBudget;
+use crate::types::{
+    BraveSectionName, DedupStrategy, FetchSearchParams, FetchSearchResult, KeyState,
+    LocalResultItem, MirrorStats, ParseOptions, ParsedSection, ParsedSummary, QuotaInfo,
+    RateLimitInfo, ResultOrder, SearchType, TraceContext, UpstreamTimings, WarningCode,
+    WarningEntry,
+};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
 use futures_util::StreamExt;
 use rand::Rng;
-use reqwest::header::{ACCEPT, HeaderMap, HeaderValue};
+use reqwest::header::{ACCEPT, ACCEPT_LANGUAGE, CONTENT_TYPE, HeaderMap, HeaderValue};
 use serde_json::Value;
-use std::time::{Duration, SystemTime};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant, SystemTime};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BraveClient {
     http: reqwest::Client,
+    /// Thumbnail fetches: no redirects and, for Brave's CDN, public addresses only.
+    thumbnail_http: reqwest::Client,
     config: RuntimeConfig,
-    api_key: ApiKeyConfig,
+    api_key: Arc<RwLock<ApiKeyConfig>>,
+    mirror: Arc<MirrorCounters>,
+    key_health: Arc<KeyHealth>,
+    override_health: Arc<OverrideHealth>,
+    retry_budget: Arc<RetryBudget>,
+}
+
+#[derive(Debug, Default)]
+struct KeyHealth {
+    verified: AtomicBool,
+    revoked: AtomicBool,
+}
+
+/// A successful JSON answer from Brave and what it took to get it.
+#[derive(Debug)]
+struct JsonReply {
+    payload: Value,
+    bytes: usize,
+    rate_limit: Option<RateLimitInfo>,
+    quota: Option<QuotaInfo>,
+    retries: usize,
+    timings: UpstreamTimings,
+    http_version: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct MirrorCounters {
+    mirrored: AtomicU64,
+    matched: AtomicU64,
+    mismatched: AtomicU64,
+    failed: AtomicU64,
 }
 
 impl BraveClient {
     pub fn new(config: RuntimeConfig) -> Result<Self, AppError> {
-        let http = reqwest::Client::builder()
+        let pool = &config.http_pool;
+        let mut builder = reqwest::Client::builder()
             .user_agent(format!(
                 "codex-brave-web-search/{}",
                 env!("CARGO_PKG_VERSION")
             ))
-            .build()
-            .map_err(|error| {
-                AppError::Internal(format!("Failed to create HTTP client: {error}"))
-            })?;
+            .tcp_keepalive(pool.tcp_keepalive_secs.map(Duration::from_secs));
+        if let Some(max_idle) = pool.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(idle_timeout_secs) = pool.pool_idle_timeout_secs {
+            builder = builder.pool_idle_timeout(Duration::from_secs(idle_timeout_secs));
+        }
+        if let Some(connect_timeout_ms) = pool.connect_timeout_ms {
+            builder = builder.connect_timeout(Duration::from_millis(connect_timeout_ms));
+        }
+        if pool.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        let http = builder.build().map_err(|error| {
+            AppError::Internal(format!("Failed to create HTTP client: {error}"))
+        })?;
+        let mut thumbnail_builder = reqwest::Client::builder()
+            .user_agent(format!(
+                "codex-brave-web-search/{}",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .redirect(reqwest::redirect::Policy::none());
+        if !config.endpoints.thumbnails_overridden() {
+            thumbnail_builder = thumbnail_builder.dns_resolver(Arc::new(PublicOnlyResolver));
+        }
+        let thumbnail_http = thumbnail_builder.build().map_err(|error| {
+            AppError::Internal(format!("Failed to create thumbnail HTTP client: {error}"))
+        })?;
 
+        let retry_budget = Arc::new(RetryBudget::new(config.retry_budget_per_min));
         Ok(Self {
             http,
+            thumbnail_http,
             config,
-            api_key: ApiKeyConfig::from_env(),
+            api_key: Arc::new(RwLock::new(ApiKeyConfig::from_env())),
+            mirror: Arc::default(),
+            key_health: Arc::default(),
+            override_health: Arc::default(),
+            retry_budget,
         })
     }
 
     #[must_use]
-    pub fn key_config(&self) -> &ApiKeyConfig {
-        &self.api_key
+    pub fn key_state(&self) -> KeyState {
+        if self.key_health.revoked.load(Ordering::Relaxed) {
+            KeyState::Invalid
+        } else if self.key_health.verified.load(Ordering::Relaxed) {
+            KeyState::Valid
+        } else {
+            KeyState::Unverified
+        }
+    }
+
+    /// Overridden search endpoints flagged `MISCONFIGURED_ENDPOINT`.
+    #[must_use]
+    pub fn misconfigured_endpoints(&self) -> Vec<SearchType> {
+        self.override_health.misconfigured()
+    }
+
+    #[must_use]
+    pub fn mirror_stats(&self) -> Option<MirrorStats> {
+        let settings = self.config.mirror.as_ref()?;
+        Some(MirrorStats {
+            percent: settings.percent,
+            mirrored: self.mirror.mirrored.load(Ordering::Relaxed),
+            matched: self.mirror.matched.load(Ordering::Relaxed),
+            mismatched: self.mirror.mismatched.load(Ordering::Relaxed),
+            failed: self.mirror.failed.load(Ordering::Relaxed),
+        })
+    }
+
+    #[must_use]
+    pub fn key_config(&self) -> ApiKeyConfig {
+        self.api_key
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Re-reads the API key and forgets whether the previous key was verified or revoked, so the
+    /// next request (or probe) judges the new key on its own.
+    pub fn reload_key(&self) -> ApiKeyConfig {
+        let key_config = ApiKeyConfig::from_env();
+        *self.api_key.write().unwrap_or_else(PoisonError::into_inner) = key_config.clone();
+        self.key_health.verified.store(false, Ordering::Relaxed);
+        self.key_health.revoked.store(false, Ordering::Relaxed);
+        key_config
     }
 
     #[must_use]
@@ -45,6 +174,14 @@ impl BraveClient {
         &self.config
     }
 
+    /// The fixture directory, when offline mode applies: configured and no key to search with.
+    fn offline_fixture_dir(&self) -> Option<PathBuf> {
+        self.config
+            .offline_fixture_dir
+            .clone()
+            .filter(|_| !self.key_config().has_key())
+    }
+
     pub async fn fetch_search<F>(
         &self,
         query: &str,
@@ -55,30 +192,419 @@ impl BraveClient {
     where
         F: Fn() -> bool,
     {
-        let api_key = self.api_key.key.as_deref().ok_or(AppError::MissingApiKey)?;
-
         let request_url = self.build_request_url(query, search_type, params)?;
+        let record = self
+            .config
+            .record
+            .as_ref()
+            .map(|record| (record, search_cache_key(query, search_type, params)));
+        let offline_dir = self.offline_fixture_dir();
+        let replaying = record
+            .as_ref()
+            .is_some_and(|(record, _)| record.mode == RecordMode::Replay);
+        let fetched = match (&record, &offline_dir) {
+            (Some((record, key)), _) if replaying => read_recorded_payload(&record.dir, key),
+            (_, Some(dir)) => read_offline_fixture(dir, query, search_type),
+            _ => {
+                let fetched = self
+                    .request_json_with_rate_limit(&request_url, Some(params), &is_cancelled)
+                    .await;
+                self.track_override_health(
+                    search_type,
+                    fetched.as_ref().map(|reply| &reply.payload),
+                );
+                #[cfg(feature = "archive")]
+                if let (Some((record, key)), Ok(reply)) = (&record, &fetched) {
+                    write_recorded_payload(&record.dir, key, &reply.payload);
+                }
+                fetched
+            }
+        };
+        let JsonReply {
+            payload: parsed_payload,
+            bytes: raw_payload_bytes,
+            rate_limit,
+            quota,
+            retries,
+            mut timings,
+            http_version,
+        } = fetched?;
+
+        let parse_started = Instant::now();
+        let mut parsed_sections = parse_sections_with_options(
+            &parsed_payload,
+            search_type,
+            &params.result_filter_values,
+            params.count,
+            params.text_decorations,
+            ParseOptions {
+                order: params.order,
+                dedup: params.dedup,
+                dedupe_snippets: params.dedupe_snippets,
+            },
+        );
+        timings.parse += parse_started.elapsed();
+        if let Some(dir) = &offline_dir {
+            parsed_sections.warnings.push(WarningEntry {
+                code: WarningCode::OfflineMode,
+                message: format!(
+                    "No API key is configured; served a canned {} response from {} instead of Brave.",
+                    search_type.as_str(),
+                    dir.display()
+                ),
+            });
+        } else if !replaying {
+            if self.override_health.is_misconfigured(search_type) {
+                parsed_sections.warnings.push(WarningEntry {
+                    code: WarningCode::MisconfiguredEndpoint,
+                    message: format!(
+                        "{} ({}) keeps failing or answering without a Brave payload, so these results may be incomplete; check the override or proxy configuration.",
+                        BraveEndpoints::env_var_for(search_type),
+                        self.config.endpoints.host_for(search_type)
+                    ),
+                });
+            }
+            self.enrich_locations(
+                &mut parsed_sections.sections,
+                &mut parsed_sections.warnings,
+                params,
+                &is_cancelled,
+            )
+            .await?;
+            self.maybe_mirror(&request_url, search_type, params, &parsed_sections.sections);
+        }
+
+        Ok(FetchSearchResult {
+            sections: parsed_sections.sections,
+            has_more: parsed_sections.has_more,
+            warnings: parsed_sections.warnings,
+            duplicates: parsed_sections.duplicates,
+            query_echo: query_echo_or_original(&parsed_payload, query),
+            request_url,
+            raw_payload: parsed_payload,
+            raw_payload_bytes,
+            rate_limit,
+            quota,
+            retries,
+            timings,
+            http_version,
+            trace_id: params
+                .trace_context
+                .as_ref()
+                .map(|trace_context| trace_context.trace_id.clone()),
+        })
+    }
+
+    /// Replays a sample of searches against the configured mirror in the background. The mirror
+    /// never affects the primary response; differences are only logged and counted.
+    fn maybe_mirror(
+        &self,
+        request_url: &str,
+        search_type: SearchType,
+        params: &FetchSearchParams,
+        primary: &[ParsedSection],
+    ) {
+        let Some(settings) = &self.config.mirror else {
+            return;
+        };
+        if rand::rng().random_range(0..100) >= settings.percent {
+            return;
+        }
+        self.mirror.mirrored.fetch_add(1, Ordering::Relaxed);
+        let Some(mirror_url) = mirror_request_url(&settings.url, request_url) else {
+            self.mirror.failed.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!("mirror URL {} is invalid", settings.url);
+            return;
+        };
+
+        let client = self.clone();
+        let params = params.clone();
+        let primary = section_urls(primary);
+        let send_api_key = settings.send_api_key;
+        tokio::spawn(async move {
+            let mirrored = client
+                .request_mirror(&mirror_url, &params, send_api_key)
+                .await
+                .map(|payload| {
+                    parse_sections_with_options(
+                        &payload,
+                        search_type,
+                        &params.result_filter_values,
+                        params.count,
+                        params.text_decorations,
+                        ParseOptions {
+                            order: params.order,
+                            dedup: params.dedup,
+                            dedupe_snippets: params.dedupe_snippets,
+                        },
+                    )
+                });
+            match mirrored {
+                Ok(parsed) => match describe_mirror_diff(&primary, &section_urls(&parsed.sections))
+                {
+                    None => {
+                        client.mirror.matched.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Some(diff) => {
+                        client.mirror.mismatched.fetch_add(1, Ordering::Relaxed);
+                        tracing::info!("mirror results differ for {mirror_url}: {diff}");
+                    }
+                },
+                Err(error) => {
+                    client.mirror.failed.fetch_add(1, Ordering::Relaxed);
+                    tracing::warn!("mirror request to {mirror_url} failed: {error}");
+                }
+            }
+        });
+    }
+
+    /// One attempt against the mirror. Unlike [`Self::request_json`] it never retries, so it
+    /// cannot spend the shared retry budget, and its status codes never mark the API key as
+    /// revoked. The subscription token is only sent when `send_api_key` is set.
+    async fn request_mirror(
+        &self,
+        mirror_url: &str,
+        params: &FetchSearchParams,
+        send_api_key: bool,
+    ) -> Result<Value, AppError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        if send_api_key {
+            let api_key = self.key_config().key.ok_or(AppError::MissingApiKey)?;
+            let subscription = HeaderValue::from_str(&api_key)
+                .map_err(|error| AppError::Internal(format!("Invalid API key header: {error}")))?;
+            headers.insert("X-Subscription-Token", subscription);
+        }
+        if let Some(accept_language) = params
+            .accept_language
+            .as_deref()
+            .and_then(|value| HeaderValue::from_str(value).ok())
+        {
+            headers.insert(ACCEPT_LANGUAGE, accept_language);
+        }
+
+        let timeout = Duration::from_millis(self.config.per_attempt_timeout_ms);
+        let response =
+            tokio::time::timeout(timeout, self.http.get(mirror_url).headers(headers).send())
+                .await
+                .map_err(|_| {
+                    AppError::Upstream("Per-attempt timeout waiting for mirror".to_string())
+                })?
+                .map_err(|error| AppError::Upstream(format!("Failed to call mirror: {error}")))?;
+        let status = response.status().as_u16();
+        let body = tokio::time::timeout(timeout, self.read_response_body(response, &|| false))
+            .await
+            .map_err(|_| {
+                AppError::Upstream("Per-attempt timeout reading mirror response".to_string())
+            })??;
+        if !(200..300).contains(&status) {
+            return Err(brave_api_error(status, &body, None));
+        }
+        serde_json::from_str::<Value>(&body)
+            .map_err(|error| AppError::Parse(format!("Invalid JSON response: {error}")))
+    }
+
+    /// Watches an overridden search endpoint by its own answers only: after several errors or
+    /// non-Brave payloads in a row the override is flagged `MISCONFIGURED_ENDPOINT` until it
+    /// returns a Brave payload again. The default endpoint is never contacted.
+    fn track_override_health(&self, search_type: SearchType, outcome: Result<&Value, &AppError>) {
+        if !self.config.endpoints.is_override(search_type) {
+            return;
+        }
+        let env_var = BraveEndpoints::env_var_for(search_type);
+        let endpoint_failure = match outcome {
+            Ok(payload) => !is_brave_search_payload(payload),
+            Err(AppError::Upstream(_) | AppError::Parse(_)) => true,
+            // Key problems and rate limiting say nothing about the endpoint itself.
+            Err(AppError::BraveApi { status, .. }) => !matches!(status, 401 | 403 | 422 | 429),
+            Err(_) => return,
+        };
+        if !endpoint_failure {
+            if self.override_health.record_success(search_type) {
+                tracing::info!("{env_var} override is answering again");
+            }
+            return;
+        }
+        if let Some(failures) = self.override_health.record_failure(search_type) {
+            tracing::warn!(
+                "{NOTICE_MISCONFIGURED_ENDPOINT}: {env_var} ({}) failed or answered without a Brave payload on {failures} consecutive {} searches; check the override or proxy configuration",
+                self.config.endpoints.host_for(search_type),
+                search_type.as_str()
+            );
+        }
+    }
+
+    async fn enrich_locations<F>(
+        &self,
+        sections: &mut [ParsedSection],
+        warnings: &mut Vec<WarningEntry>,
+        params: &FetchSearchParams,
+        is_cancelled: &F,
+    ) -> Result<(), AppError>
+    where
+        F: Fn() -> bool,
+    {
+        // A ranked `mixed` section carries the location results itself.
+        let Some(section) = sections.iter_mut().find(|section| {
+            matches!(
+                section.key,
+                BraveSectionName::Locations | BraveSectionName::Mixed
+            )
+        }) else {
+            return Ok(());
+        };
+        let ids = section
+            .results
+            .iter()
+            .filter_map(|result| result.poi_id.clone())
+            .take(MAX_LOCAL_POI_IDS)
+            .collect::<Vec<String>>();
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        match self
+            .fetch_local_details_with(&ids, Some(params), is_cancelled)
+            .await
+        {
+            Ok(mut details) => {
+                for result in &mut section.results {
+                    if let Some(id) = &result.poi_id {
+                        result.local = details.remove(id);
+                    }
+                }
+                Ok(())
+            }
+            Err(AppError::Cancelled) => Err(AppError::Cancelled),
+            Err(error) => {
+                warnings.push(WarningEntry {
+                    code: WarningCode::LocalDetailsUnavailable,
+                    message: format!("Location details could not be fetched: {error}"),
+                });
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn fetch_local_details<F>(
+        &self,
+        ids: &[String],
+        is_cancelled: &F,
+    ) -> Result<HashMap<String, LocalResultItem>, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        self.fetch_local_details_with(ids, None, is_cancelled).await
+    }
+
+    async fn fetch_local_details_with<F>(
+        &self,
+        ids: &[String],
+        params: Option<&FetchSearchParams>,
+        is_cancelled: &F,
+    ) -> Result<HashMap<String, LocalResultItem>, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        let pois_url = build_ids_url(&self.config.endpoints.local_pois, ids)?;
+        let (pois_payload, _) = self.request_json(&pois_url, params, is_cancelled).await?;
+        let mut details = parse_local_pois(&pois_payload);
+
+        let descriptions_url = build_ids_url(&self.config.endpoints.local_descriptions, ids)?;
+        let (descriptions_payload, _) = self
+            .request_json(&descriptions_url, params, is_cancelled)
+            .await?;
+        for (id, description) in parse_local_descriptions(&descriptions_payload) {
+            details
+                .entry(id.clone())
+                .or_insert_with(|| LocalResultItem {
+                    id,
+                    ..LocalResultItem::default()
+                })
+                .description = Some(description);
+        }
+
+        Ok(details)
+    }
+
+    async fn request_json<F>(
+        &self,
+        request_url: &str,
+        params: Option<&FetchSearchParams>,
+        is_cancelled: &F,
+    ) -> Result<(Value, usize), AppError>
+    where
+        F: Fn() -> bool,
+    {
+        let reply = self
+            .request_json_with_rate_limit(request_url, params, is_cancelled)
+            .await?;
+        Ok((reply.payload, reply.bytes))
+    }
+
+    /// Like [`Self::request_json`], also returning the rate-limit headers, how many retries the
+    /// call needed and how long its attempts took.
+    async fn request_json_with_rate_limit<F>(
+        &self,
+        request_url: &str,
+        params: Option<&FetchSearchParams>,
+        is_cancelled: &F,
+    ) -> Result<JsonReply, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        let api_key = self.key_config().key.ok_or(AppError::MissingApiKey)?;
+        if self.key_health.revoked.load(Ordering::Relaxed) {
+            return Err(AppError::ApiKeyRevoked);
+        }
 
         let mut last_error: Option<AppError> = None;
         let mut last_status: Option<u16> = None;
         let mut last_body = String::new();
+        let mut timings = UpstreamTimings::default();
+        let mut budget_exhausted = false;
 
         for attempt in 0..=self.config.retry_count {
             if is_cancelled() {
                 return Err(AppError::Cancelled);
             }
+            let attempt_started = Instant::now();
 
             let mut headers = HeaderMap::new();
             headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
-            let subscription = HeaderValue::from_str(api_key)
+            let subscription = HeaderValue::from_str(&api_key)
                 .map_err(|error| AppError::Internal(format!("Invalid API key header: {error}")))?;
             headers.insert("X-Subscription-Token", subscription);
+            if let Some(accept_language) = params
+                .and_then(|params| params.accept_language.as_deref())
+                .and_then(|value| HeaderValue::from_str(value).ok())
+            {
+                headers.insert(ACCEPT_LANGUAGE, accept_language);
+            }
+            if let Some(trace_context) = params.and_then(|params| params.trace_context.as_ref()) {
+                let traceparent = HeaderValue::from_str(&trace_context.traceparent())
+                    .map_err(|error| AppError::Internal(format!("Invalid traceparent: {error}")))?;
+                headers.insert("traceparent", traceparent);
+                if let Some(baggage) = trace_context
+                    .baggage
+                    .as_deref()
+                    .and_then(|baggage| HeaderValue::from_str(baggage).ok())
+                {
+                    headers.insert("baggage", baggage);
+                }
+            }
 
-            let send_result = tokio::time::timeout(
-                Duration::from_millis(self.config.per_attempt_timeout_ms),
-                self.http.get(request_url.clone()).headers(headers).send(),
-            )
-            .await;
+            let send_result = tokio::select! {
+                biased;
+                () = wait_for_cancellation(is_cancelled) => return Err(AppError::Cancelled),
+                send_result = tokio::time::timeout(
+                    Duration::from_millis(self.config.per_attempt_timeout_ms),
+                    self.http.get(request_url).headers(headers).send(),
+                ) => send_result,
+            };
+            if !matches!(send_result, Ok(Ok(_))) {
+                timings.attempts.push(attempt_started.elapsed());
+            }
 
             let response = match send_result {
                 Ok(Ok(response)) => response,
@@ -86,8 +612,8 @@ impl BraveClient {
                     last_error = Some(AppError::Upstream(format!(
                         "Failed to call Brave API: {error}"
                     )));
-                    if attempt < self.config.retry_count {
-                        self.wait_for_retry(None, attempt, &is_cancelled).await?;
+                    if self.retry_allowed(attempt, &mut budget_exhausted) {
+                        self.wait_for_retry(None, attempt, is_cancelled).await?;
                         continue;
                     }
                     break;
@@ -96,8 +622,8 @@ impl BraveClient {
                     last_error = Some(AppError::Upstream(
                         "Per-attempt timeout waiting for Brave API response".to_string(),
                     ));
-                    if attempt < self.config.retry_count {
-                        self.wait_for_retry(None, attempt, &is_cancelled).await?;
+                    if self.retry_allowed(attempt, &mut budget_exhausted) {
+                        self.wait_for_retry(None, attempt, is_cancelled).await?;
                         continue;
                     }
                     break;
@@ -105,24 +631,43 @@ impl BraveClient {
             };
 
             let status = response.status().as_u16();
+            let http_version = format!("{:?}", response.version());
             let retry_after_header = response
                 .headers()
                 .get("retry-after")
                 .and_then(|value| value.to_str().ok())
                 .map(str::to_string);
+            let header_str = |name: &str| {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+            };
+            let rate_limit = parse_rate_limit_headers(
+                header_str("x-ratelimit-limit"),
+                header_str("x-ratelimit-remaining"),
+                header_str("x-ratelimit-reset"),
+            );
+            let quota = parse_quota_headers(
+                header_str("x-ratelimit-limit"),
+                header_str("x-ratelimit-remaining"),
+            );
 
+            let body_started = Instant::now();
             let read_body = tokio::time::timeout(
                 Duration::from_millis(self.config.per_attempt_timeout_ms),
-                self.read_response_body(response, &is_cancelled),
+                self.read_response_body(response, is_cancelled),
             )
             .await;
+            timings.body_read = body_started.elapsed();
+            timings.attempts.push(attempt_started.elapsed());
 
             let raw_body = match read_body {
                 Ok(Ok(body)) => body,
                 Ok(Err(error)) => {
                     last_error = Some(error);
-                    if attempt < self.config.retry_count {
-                        self.wait_for_retry(None, attempt, &is_cancelled).await?;
+                    if self.retry_allowed(attempt, &mut budget_exhausted) {
+                        self.wait_for_retry(None, attempt, is_cancelled).await?;
                         continue;
                     }
                     break;
@@ -131,8 +676,8 @@ impl BraveClient {
                     last_error = Some(AppError::Upstream(
                         "Per-attempt timeout reading Brave API response".to_string(),
                     ));
-                    if attempt < self.config.retry_count {
-                        self.wait_for_retry(None, attempt, &is_cancelled).await?;
+                    if self.retry_allowed(attempt, &mut budget_exhausted) {
+                        self.wait_for_retry(None, attempt, is_cancelled).await?;
                         continue;
                     }
                     break;
@@ -143,51 +688,56 @@ impl BraveClient {
             last_body = raw_body.clone();
 
             if (200..300).contains(&status) {
-                let parsed_payload = serde_json::from_str::<Value>(&raw_body)
+                self.key_health.verified.store(true, Ordering::Relaxed);
+                let parse_started = Instant::now();
+                let payload = serde_json::from_str::<Value>(&raw_body)
                     .map_err(|error| AppError::Parse(format!("Invalid JSON response: {error}")))?;
-
-                let parsed_sections = parse_sections(
-                    &parsed_payload,
-                    search_type,
-                    &params.result_filter_values,
-                    params.count,
-                    params.text_decorations,
-                );
-
-                return Ok(FetchSearchResult {
-                    sections: parsed_sections.sections,
-                    has_more: parsed_sections.has_more,
-                    warnings: parsed_sections.warnings,
-                    query_echo: query_echo_or_original(&parsed_payload, query),
-                    request_url,
-                    raw_payload: parsed_payload,
-                    raw_payload_bytes: raw_body.len(),
+                timings.parse = parse_started.elapsed();
+                return Ok(JsonReply {
+                    payload,
+                    bytes: raw_body.len(),
+                    rate_limit,
+                    quota,
+                    retries: attempt,
+                    timings,
+                    http_version: Some(http_version),
                 });
             }
 
-            if RETRYABLE_HTTP_STATUS.contains(&status) && attempt < self.config.retry_count {
-                self.wait_for_retry(retry_after_header.as_deref(), attempt, &is_cancelled)
+            if status == 401 && self.key_health.verified.load(Ordering::Relaxed) {
+                self.key_health.revoked.store(true, Ordering::Relaxed);
+                tracing::warn!("Brave rejected the previously working API key; failing fast");
+                return Err(AppError::ApiKeyRevoked);
+            }
+
+            if RETRYABLE_HTTP_STATUS.contains(&status)
+                && self.retry_allowed(attempt, &mut budget_exhausted)
+            {
+                self.wait_for_retry(retry_after_header.as_deref(), attempt, is_cancelled)
                     .await?;
                 continue;
             }
 
-            let fallback = format!("Request failed ({status}).");
-            let detail = parse_brave_error_message(&raw_body, &fallback);
-            return Err(AppError::Upstream(format!(
-                "Brave Search API returned HTTP {status}: {detail}"
-            )));
+            let retry_after_ms = retry_after_header
+                .as_deref()
+                .and_then(parse_retry_after_delay_ms)
+                .or_else(|| {
+                    rate_limit
+                        .filter(|window| window.remaining == 0)
+                        .map(|window| window.reset_secs.saturating_mul(1_000))
+                });
+            return Err(note_skipped_retry(
+                brave_api_error(status, &raw_body, retry_after_ms),
+                budget_exhausted,
+            ));
         }
 
         if let Some(error) = last_error {
-            return Err(error);
+            return Err(note_skipped_retry(error, budget_exhausted));
         }
 
         if let Some(status) = last_status {
-            let fallback = format!("Request failed ({status}).");
-            let detail = parse_brave_error_message(&last_body, &fallback);
-            return Err(AppError::Upstream(format!(
-                "Brave Search API returned HTTP {status}: {detail}"
-            )));
+            return Err(brave_api_error(status, &last_body, None));
         }
 
         Err(AppError::Internal(
@@ -195,6 +745,20 @@ impl BraveClient {
         ))
     }
 
+    /// Whether `attempt` may be retried, spending one retry from the shared budget.
+    /// `budget_exhausted` records a retry the budget refused.
+    fn retry_allowed(&self, attempt: usize, budget_exhausted: &mut bool) -> bool {
+        if attempt >= self.config.retry_count {
+            return false;
+        }
+        if self.retry_budget.try_take() {
+            return true;
+        }
+        tracing::warn!("retry budget exhausted; not retrying the Brave request");
+        *budget_exhausted = true;
+        false
+    }
+
     async fn wait_for_retry<F>(
         &self,
         retry_after_header: Option<&str>,
@@ -211,19 +775,7 @@ impl BraveClient {
             self.config.retry_max_delay_ms,
         );
 
-        let total_wait = Duration::from_millis(delay_ms);
-        let step = Duration::from_millis(100);
-        let start = std::time::Instant::now();
-
-        while start.elapsed() < total_wait {
-            if is_cancelled() {
-                return Err(AppError::Cancelled);
-            }
-            let remaining = total_wait.saturating_sub(start.elapsed());
-            tokio::time::sleep(remaining.min(step)).await;
-        }
-
-        Ok(())
+        sleep_cancellable(Duration::from_millis(delay_ms), is_cancelled).await
     }
 
     async fn read_response_body<F>(
@@ -237,10 +789,17 @@ impl BraveClient {
         let mut stream = response.bytes_stream();
         let mut bytes = Vec::<u8>::new();
 
-        while let Some(chunk_result) = stream.next().await {
-            if is_cancelled() {
-                return Err(AppError::Cancelled);
-            }
+        loop {
+            // Racing the next chunk against cancellation drops the stream (and the connection)
+            // immediately instead of waiting on a stalled server.
+            let next_chunk = tokio::select! {
+                biased;
+                () = wait_for_cancellation(is_cancelled) => return Err(AppError::Cancelled),
+                next_chunk = stream.next() => next_chunk,
+            };
+            let Some(chunk_result) = next_chunk else {
+                break;
+            };
 
             let chunk = chunk_result.map_err(|error| {
                 AppError::Upstream(format!("Failed while reading response body: {error}"))
@@ -305,6 +864,13 @@ impl BraveClient {
                 if params.spellcheck { "true" } else { "false" },
             );
 
+            if let Some(goggles) = &params.goggles {
+                search_params.append_pair("goggles", goggles);
+            }
+            if params.summary {
+                search_params.append_pair("summary", "1");
+            }
+
             if search_type == SearchType::Web && !params.result_filter_values.is_empty() {
                 let filter = params
                     .result_filter_values
@@ -319,6 +885,199 @@ impl BraveClient {
         Ok(url.to_string())
     }
 
+    pub async fn fetch_thumbnail<F>(
+        &self,
+        thumbnail_url: &str,
+        max_bytes: usize,
+        is_cancelled: F,
+    ) -> Result<String, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        let url = thumbnail_fetch_url(
+            thumbnail_url,
+            &self.config.endpoints.thumbnails,
+            !self.config.endpoints.thumbnails_overridden(),
+        )?;
+        let response = tokio::time::timeout(
+            Duration::from_millis(self.config.per_attempt_timeout_ms),
+            self.thumbnail_http.get(url).send(),
+        )
+        .await
+        .map_err(|_| AppError::Upstream("Per-attempt timeout fetching thumbnail".to_string()))?
+        .map_err(|error| AppError::Upstream(format!("Failed to fetch thumbnail: {error}")))?;
+
+        let status = response.status().as_u16();
+        if !(200..300).contains(&status) {
+            return Err(AppError::Upstream(format!(
+                "Thumbnail request returned HTTP {status}"
+            )));
+        }
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| {
+                value
+                    .split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_lowercase()
+            })
+            .unwrap_or_default();
+        if !content_type.starts_with("image/") {
+            return Err(AppError::Upstream(format!(
+                "Thumbnail content type '{content_type}' is not an image"
+            )));
+        }
+        if response
+            .content_length()
+            .is_some_and(|length| length > max_bytes as u64)
+        {
+            return Err(AppError::Upstream(format!(
+                "Thumbnail exceeded {max_bytes} byte limit"
+            )));
+        }
+
+        let mut stream = response.bytes_stream();
+        let mut bytes = Vec::<u8>::new();
+        while let Some(chunk_result) = stream.next().await {
+            if is_cancelled() {
+                return Err(AppError::Cancelled);
+            }
+            let chunk = chunk_result.map_err(|error| {
+                AppError::Upstream(format!("Failed while reading thumbnail: {error}"))
+            })?;
+            if bytes.len() + chunk.len() > max_bytes {
+                return Err(AppError::Upstream(format!(
+                    "Thumbnail exceeded {max_bytes} byte limit"
+                )));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        Ok(format!(
+            "data:{content_type};base64,{}",
+            BASE64_STANDARD.encode(&bytes)
+        ))
+    }
+
+    /// Runs the summarizer-enabled web search and returns the summarizer URL to poll with
+    /// [`Self::poll_summary`].
+    pub async fn fetch_summary_url<F>(
+        &self,
+        query: &str,
+        params: &FetchSearchParams,
+        is_cancelled: F,
+    ) -> Result<String, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        let search_url = self.build_request_url(query, SearchType::Web, params)?;
+        let (search_payload, _) = self
+            .request_json(&search_url, Some(params), &is_cancelled)
+            .await?;
+        let summary_key = parse_summarizer_key(&search_payload).ok_or_else(|| {
+            AppError::Upstream(
+                "Brave returned no summarizer key; the query may not be summarizable or the API plan lacks summarizer access".to_string(),
+            )
+        })?;
+
+        let endpoint = &self.config.endpoints.summarizer;
+        let mut summary_url = url::Url::parse(endpoint).map_err(|error| {
+            AppError::Internal(format!("Invalid endpoint URL '{endpoint}': {error}"))
+        })?;
+        summary_url
+            .query_pairs_mut()
+            .append_pair("key", &summary_key);
+        Ok(summary_url.to_string())
+    }
+
+    /// Polls the summarizer once. `None` means Brave is still writing the summary; the poll
+    /// delay has then already been waited out.
+    pub async fn poll_summary<F>(
+        &self,
+        summary_url: &str,
+        params: &FetchSearchParams,
+        is_cancelled: F,
+    ) -> Result<Option<ParsedSummary>, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        let (payload, _) = self
+            .request_json(summary_url, Some(params), &is_cancelled)
+            .await?;
+        let parsed = parse_summarizer(&payload);
+        match parsed.status.as_str() {
+            "complete" => Ok(Some(parsed)),
+            "failed" => Err(AppError::Upstream(
+                "Brave summarizer failed to produce a summary".to_string(),
+            )),
+            _ => {
+                sleep_cancellable(
+                    Duration::from_millis(SUMMARIZER_POLL_DELAY_MS),
+                    &is_cancelled,
+                )
+                .await?;
+                Ok(None)
+            }
+        }
+    }
+
+    pub async fn fetch_suggestions<F>(
+        &self,
+        query: &str,
+        country: Option<&str>,
+        count: usize,
+        is_cancelled: F,
+    ) -> Result<Vec<String>, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        let endpoint = &self.config.endpoints.suggest;
+        let mut url = url::Url::parse(endpoint).map_err(|error| {
+            AppError::Internal(format!("Invalid endpoint URL '{endpoint}': {error}"))
+        })?;
+        {
+            let mut search_params = url.query_pairs_mut();
+            search_params.append_pair("q", query);
+            search_params.append_pair("count", &count.to_string());
+            if let Some(country) = country {
+                search_params.append_pair("country", country);
+            }
+        }
+
+        let (payload, _) = self.request_json(url.as_str(), None, &is_cancelled).await?;
+        Ok(parse_suggestions(&payload, count))
+    }
+
+    pub async fn fetch_spellcheck<F>(
+        &self,
+        query: &str,
+        country: Option<&str>,
+        is_cancelled: F,
+    ) -> Result<Option<String>, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        let endpoint = &self.config.endpoints.spellcheck;
+        let mut url = url::Url::parse(endpoint).map_err(|error| {
+            AppError::Internal(format!("Invalid endpoint URL '{endpoint}': {error}"))
+        })?;
+        {
+            let mut search_params = url.query_pairs_mut();
+            search_params.append_pair("q", query);
+            if let Some(country) = country {
+                search_params.append_pair("country", country);
+            }
+        }
+
+        let (payload, _) = self.request_json(url.as_str(), None, &is_cancelled).await?;
+        Ok(parse_spellcheck(&payload).filter(|corrected| corrected != query))
+    }
+
     pub async fn probe_endpoint<F>(
         &self,
         search_type: SearchType,
@@ -340,6 +1099,13 @@ impl BraveClient {
             spellcheck: true,
             extra_snippets: false,
             text_decorations: matches!(search_type, SearchType::News),
+            order: ResultOrder::Grouped,
+            dedup: DedupStrategy::Url,
+            dedupe_snippets: true,
+            summary: false,
+            goggles: None,
+            accept_language: None,
+            trace_context: None,
         };
 
         self.fetch_search("mcp healthcheck", search_type, &params, is_cancelled)
@@ -348,6 +1114,161 @@ impl BraveClient {
     }
 }
 
+fn build_ids_url(endpoint: &str, ids: &[String]) -> Result<String, AppError> {
+    let mut url = url::Url::parse(endpoint).map_err(|error| {
+        AppError::Internal(format!("Invalid endpoint URL '{endpoint}': {error}"))
+    })?;
+    {
+        let mut search_params = url.query_pairs_mut();
+        for id in ids {
+            search_params.append_pair("ids", id);
+        }
+    }
+    Ok(url.to_string())
+}
+
+async fn wait_for_cancellation<F>(is_cancelled: &F)
+where
+    F: Fn() -> bool,
+{
+    let interval = Duration::from_millis(CANCEL_POLL_INTERVAL_MS);
+    while !is_cancelled() {
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn sleep_cancellable<F>(total_wait: Duration, is_cancelled: &F) -> Result<(), AppError>
+where
+    F: Fn() -> bool,
+{
+    let step = Duration::from_millis(100);
+    let start = std::time::Instant::now();
+
+    while start.elapsed() < total_wait {
+        if is_cancelled() {
+            return Err(AppError::Cancelled);
+        }
+        let remaining = total_wait.saturating_sub(start.elapsed());
+        tokio::time::sleep(remaining.min(step)).await;
+    }
+
+    Ok(())
+}
+
+/// Writes a raw upstream payload for later replay; failures are logged, never fatal.
+#[cfg(feature = "archive")]
+fn write_recorded_payload(dir: &Path, key: &str, payload: &Value) {
+    let path = dir.join(format!("{key}.json"));
+    let temp_path = path.with_extension("json.tmp");
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|()| serde_json::to_vec_pretty(payload).map_err(std::io::Error::other))
+        .and_then(|bytes| std::fs::write(&temp_path, bytes))
+        .and_then(|()| std::fs::rename(&temp_path, &path));
+    if let Err(error) = result {
+        tracing::warn!("failed to record payload {}: {error}", path.display());
+    }
+}
+
+fn read_recorded_payload(dir: &Path, key: &str) -> Result<JsonReply, AppError> {
+    let path = dir.join(format!("{key}.json"));
+    let body = std::fs::read(&path).map_err(|_| {
+        AppError::Upstream(format!(
+            "Replay mode has no recorded payload for this search ({}); record it first with {ENV_RECORD_MODE}=record",
+            path.display()
+        ))
+    })?;
+    let payload = serde_json::from_slice::<Value>(&body)
+        .map_err(|error| AppError::Parse(format!("Invalid recorded payload: {error}")))?;
+    Ok(JsonReply {
+        payload,
+        bytes: body.len(),
+        rate_limit: None,
+        quota: None,
+        retries: 0,
+        timings: UpstreamTimings::default(),
+        http_version: None,
+    })
+}
+
+/// Reads the canned payload for an offline search: `<dir>/<search_type>/<query slug>.json` when it
+/// exists, otherwise `<dir>/<search_type>.json`.
+fn read_offline_fixture(
+    dir: &Path,
+    query: &str,
+    search_type: SearchType,
+) -> Result<JsonReply, AppError> {
+    let slug = query
+        .to_lowercase()
+        .split(|character: char| !character.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+    let per_query =
+        (!slug.is_empty()).then(|| dir.join(search_type.as_str()).join(format!("{slug}.json")));
+    let fallback = dir.join(format!("{}.json", search_type.as_str()));
+    let Some(body) = per_query
+        .iter()
+        .chain([&fallback])
+        .find_map(|path| std::fs::read(path).ok())
+    else {
+        return Err(AppError::Upstream(format!(
+            "Offline mode has no fixture for this query; add {}",
+            fallback.display()
+        )));
+    };
+    let payload = serde_json::from_slice::<Value>(&body)
+        .map_err(|error| AppError::Parse(format!("Invalid offline fixture: {error}")))?;
+    Ok(JsonReply {
+        payload,
+        bytes: body.len(),
+        rate_limit: None,
+        quota: None,
+        retries: 0,
+        timings: UpstreamTimings::default(),
+        http_version: None,
+    })
+}
+
+/// Appends a `RETRY_BUDGET_EXHAUSTED` note to an upstream error whose retry was refused.
+fn note_skipped_retry(error: AppError, budget_exhausted: bool) -> AppError {
+    let note = |message: String| {
+        format!(
+            "{message} ({RETRY_BUDGET_EXHAUSTED}: retry skipped because the shared retry budget is used up.)"
+        )
+    };
+    match error {
+        AppError::Upstream(message) if budget_exhausted => AppError::Upstream(note(message)),
+        AppError::BraveApi {
+            status,
+            message,
+            brave_code,
+            params,
+            retry_after_ms,
+        } if budget_exhausted => AppError::BraveApi {
+            status,
+            message: note(message),
+            brave_code,
+            params,
+            retry_after_ms,
+        },
+        error => error,
+    }
+}
+
+#[must_use]
+fn brave_api_error(status: u16, body: &str, retry_after_ms: Option<u64>) -> AppError {
+    let fallback = format!("Request failed ({status}).");
+    let detail = parse_brave_error_message(body, &fallback);
+    let (brave_code, params) = parse_brave_error_code(body);
+    AppError::BraveApi {
+        status,
+        message: format!("Brave Search API returned HTTP {status}: {detail}"),
+        brave_code,
+        params,
+        retry_after_ms,
+    }
+}
+
 #[must_use]
 pub fn compute_retry_delay_ms(
     attempt: usize,
@@ -368,6 +1289,220 @@ pub fn compute_retry_delay_ms(
     delay_ms.clamp(1, max_delay_ms)
 }
 
+/// Parses a thumbnail URL and checks it may be fetched: same scheme, host and port as `origin`,
+/// and, when `public_only`, https with no IP literal outside the public address space.
+pub fn thumbnail_fetch_url(
+    thumbnail_url: &str,
+    origin: &str,
+    public_only: bool,
+) -> Result<url::Url, AppError> {
+    let refused = |reason: &str| AppError::Upstream(format!("Thumbnail URL refused: {reason}"));
+    let url = url::Url::parse(thumbnail_url).map_err(|_| refused("not a valid URL"))?;
+    let origin = url::Url::parse(origin).map_err(|_| refused("thumbnail origin is invalid"))?;
+    if url.origin() != origin.origin() {
+        return Err(refused("not served by the thumbnail origin"));
+    }
+    if public_only {
+        if url.scheme() != "https" {
+            return Err(refused("not https"));
+        }
+        let literal = match url.host() {
+            Some(url::Host::Ipv4(ip)) => Some(IpAddr::V4(ip)),
+            Some(url::Host::Ipv6(ip)) => Some(IpAddr::V6(ip)),
+            _ => None,
+        };
+        if literal.is_some_and(|ip| !is_public_address(ip)) {
+            return Err(refused("private or loopback address"));
+        }
+    }
+    Ok(url)
+}
+
+/// Whether `ip` is routable on the public internet: not loopback, private, link-local,
+/// carrier-grade NAT, unspecified, broadcast, documentation or unique-local.
+#[must_use]
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || (first == 100 && (64..128).contains(&second)))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(mapped));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Resolver for thumbnail fetches that drops private and loopback answers, so a CDN hostname
+/// that resolves inward is unreachable rather than fetched.
+struct PublicOnlyResolver;
+
+impl reqwest::dns::Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = tokio::task::spawn_blocking(move || (host.as_str(), 0).to_socket_addrs())
+                .await??
+                .filter(|addr| is_public_address(addr.ip()))
+                .collect::<Vec<SocketAddr>>();
+            if addrs.is_empty() {
+                return Err("thumbnail host resolved to no public address".into());
+            }
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Whether an overridden endpoint answered like Brave: a JSON object echoing the query or
+/// naming its result `type`.
+fn is_brave_search_payload(payload: &Value) -> bool {
+    payload
+        .as_object()
+        .is_some_and(|object| object.contains_key("query") || object.contains_key("type"))
+}
+
+/// Re-targets `request_url` at the mirror base URL, keeping its path (appended to any mirror path
+/// prefix) and query string.
+fn mirror_request_url(mirror_base: &str, request_url: &str) -> Option<String> {
+    let primary = url::Url::parse(request_url).ok()?;
+    let mut mirrored = url::Url::parse(mirror_base).ok()?;
+    let path = format!(
+        "{}{}",
+        mirrored.path().trim_end_matches('/'),
+        primary.path()
+    );
+    mirrored.set_path(&path);
+    mirrored.set_query(primary.query());
+    Some(mirrored.to_string())
+}
+
+fn section_urls(sections: &[ParsedSection]) -> Vec<(BraveSectionName, Vec<String>)> {
+    sections
+        .iter()
+        .map(|section| {
+            let urls = section
+                .results
+                .iter()
+                .map(|result| result.url.clone())
+                .collect();
+            (section.key, urls)
+        })
+        .collect()
+}
+
+fn describe_mirror_diff(
+    primary: &[(BraveSectionName, Vec<String>)],
+    mirror: &[(BraveSectionName, Vec<String>)],
+) -> Option<String> {
+    let mut sections = primary.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+    for (key, _) in mirror {
+        if !sections.contains(key) {
+            sections.push(*key);
+        }
+    }
+
+    let urls_for = |side: &[(BraveSectionName, Vec<String>)], key: BraveSectionName| {
+        side.iter()
+            .find(|(section, _)| *section == key)
+            .map(|(_, urls)| urls.clone())
+            .unwrap_or_default()
+    };
+    let diffs = sections
+        .into_iter()
+        .filter_map(|key| {
+            let primary_urls = urls_for(primary, key);
+            let mirror_urls = urls_for(mirror, key);
+            if primary_urls == mirror_urls {
+                return None;
+            }
+            let only_primary = primary_urls
+                .iter()
+                .filter(|url| !mirror_urls.contains(url))
+                .count();
+            let only_mirror = mirror_urls
+                .iter()
+                .filter(|url| !primary_urls.contains(url))
+                .count();
+            Some(format!(
+                "{}: {} vs {} results, {only_primary} only in primary, {only_mirror} only in mirror",
+                key.as_str(),
+                primary_urls.len(),
+                mirror_urls.len()
+            ))
+        })
+        .collect::<Vec<_>>();
+    (!diffs.is_empty()).then(|| diffs.join("; "))
+}
+
+/// Builds the W3C trace context for one upstream call. UUID trace ids are reused as-is so Brave-side
+/// requests correlate with the server's own `trace_id`; anything else is hashed down to 16 bytes.
+#[must_use]
+pub fn trace_context_for(trace_id: &str, baggage: Option<&str>) -> TraceContext {
+    let compact = trace_id.replace('-', "").to_ascii_lowercase();
+    let trace_id = if compact.len() == 32
+        && compact.bytes().all(|byte| byte.is_ascii_hexdigit())
+        && compact.bytes().any(|byte| byte != b'0')
+    {
+        compact
+    } else {
+        hex::encode(&Sha256::digest(trace_id.as_bytes())[..16])
+    };
+    TraceContext {
+        trace_id,
+        span_id: format!("{:016x}", rand::rng().random::<u64>().max(1)),
+        baggage: baggage.map(str::to_string),
+    }
+}
+
+/// Reads the first (per-second) window of Brave's comma-separated `X-RateLimit-*` headers.
+#[must_use]
+pub fn parse_rate_limit_headers(
+    limit: Option<&str>,
+    remaining: Option<&str>,
+    reset: Option<&str>,
+) -> Option<RateLimitInfo> {
+    fn first_window(value: Option<&str>) -> Option<u64> {
+        value?.split(',').next()?.trim().parse::<u64>().ok()
+    }
+
+    let limit = first_window(limit)?;
+    let remaining = first_window(remaining)?;
+    (limit > 0).then(|| RateLimitInfo {
+        limit: u32::try_from(limit).unwrap_or(u32::MAX),
+        remaining: u32::try_from(remaining).unwrap_or(u32::MAX),
+        reset_secs: first_window(reset).unwrap_or(1),
+    })
+}
+
+/// Reads the last (monthly quota) window of Brave's `X-RateLimit-*` headers; `None` unless the
+/// headers carry more than one window.
+#[must_use]
+pub fn parse_quota_headers(limit: Option<&str>, remaining: Option<&str>) -> Option<QuotaInfo> {
+    fn last_window(value: Option<&str>) -> Option<u64> {
+        let (_, last) = value?.rsplit_once(',')?;
+        last.trim().parse::<u64>().ok()
+    }
+
+    let limit = last_window(limit)?;
+    (limit > 0).then_some(QuotaInfo {
+        limit,
+        remaining: last_window(remaining)?,
+    })
+}
+
 fn parse_retry_after_delay_ms(retry_after_header: &str) -> Option<u64> {
     if let Ok(seconds) = retry_after_header.trim().parse::<u64>()
         && seconds > 0
@@ -381,6 +1516,28 @@ fn parse_retry_after_delay_ms(retry_after_header: &str) -> Option<u64> {
     Some(diff.as_millis().min(u128::from(u64::MAX)) as u64)
 }
 
+/// The debug raw payload with Brave's `query` echo trimmed to [`RAW_PAYLOAD_QUERY_ECHO_FIELDS`].
+///
+/// When `fields` is non-empty the payload is reduced to an object keyed by those JSON pointers;
+/// pointers that resolve to nothing are left out.
+#[must_use]
+pub fn filter_debug_raw_payload(payload: &Value, fields: &[String]) -> Value {
+    let mut payload = payload.clone();
+    if let Some(Value::Object(query)) = payload.get_mut("query") {
+        query.retain(|key, _| RAW_PAYLOAD_QUERY_ECHO_FIELDS.contains(&key.as_str()));
+    }
+    if fields.is_empty() {
+        return payload;
+    }
+    Value::Object(
+        fields
+            .iter()
+            .filter_map(|field| Some((field.clone(), payload.pointer(field)?.clone())))
+            .collect(),
+    )
+}
+
+#[cfg(feature = "debug-tools")]
 pub fn maybe_cap_debug_raw_payload(
     payload: &Value,
     original_size: usize,
@@ -393,7 +1550,7 @@ pub fn maybe_cap_debug_raw_payload(
     }
 
     warnings.push(WarningEntry {
-        code: WARNING_RAW_PAYLOAD_TRUNCATED.to_string(),
+        code: WarningCode::RawPayloadTruncated,
         message: format!(
             "Raw payload exceeded debug cap ({} bytes > {} bytes); returning truncated preview object.",
             serialized.len(), cap_bytes
@@ -1,18 +1,50 @@
+use crate::blocklist::{DomainBlocklist, validate_patterns};
 use crate::constants::{
-    BRAVE_ENDPOINT_IMAGES, BRAVE_ENDPOINT_NEWS, BRAVE_ENDPOINT_VIDEOS, BRAVE_ENDPOINT_WEB,
-    DEFAULT_CACHE_TTL_SECS, DEFAULT_MAX_BYTES, DEFAULT_MAX_LINES, DEFAULT_MAX_MAX_BYTES,
-    DEFAULT_MAX_MAX_LINES, DEFAULT_MAX_RESPONSE_BYTES, DEFAULT_MAX_RETRY_DELAY_MS,
-    DEFAULT_MIN_MAX_BYTES, DEFAULT_MIN_MAX_LINES, DEFAULT_PER_ATTEMPT_TIMEOUT_MS,
-    DEFAULT_RAW_PAYLOAD_CAP_BYTES, DEFAULT_RETRY_BASE_DELAY_MS, DEFAULT_RETRY_COUNT,
-    DEFAULT_THROTTLE_BURST, DEFAULT_THROTTLE_RATE_PER_SEC, ENV_BRAVE_API_KEY,
-    ENV_BRAVE_SEARCH_API_KEY, ENV_CACHE_TTL_SECS, ENV_DEFAULT_MAX_BYTES, ENV_DEFAULT_MAX_LINES,
-    ENV_ENDPOINT_IMAGES, ENV_ENDPOINT_NEWS, ENV_ENDPOINT_VIDEOS, ENV_ENDPOINT_WEB, ENV_LOG,
-    ENV_MAX_MAX_BYTES, ENV_MAX_MAX_LINES, ENV_MAX_QUERY_LENGTH, ENV_MAX_RESPONSE_BYTES,
-    ENV_MIN_MAX_BYTES, ENV_MIN_MAX_LINES, ENV_PER_ATTEMPT_TIMEOUT_MS, ENV_RAW_PAYLOAD_CAP_BYTES,
-    ENV_RETRY_BASE_DELAY_MS, ENV_RETRY_COUNT, ENV_RETRY_MAX_DELAY_MS, ENV_THROTTLE_BURST,
-    ENV_THROTTLE_RATE, MAX_QUERY_LENGTH,
+    BRAVE_ENDPOINT_IMAGES, BRAVE_ENDPOINT_LOCAL_DESCRIPTIONS, BRAVE_ENDPOINT_LOCAL_POIS,
+    BRAVE_ENDPOINT_NEWS, BRAVE_ENDPOINT_SPELLCHECK, BRAVE_ENDPOINT_SUGGEST,
+    BRAVE_ENDPOINT_SUMMARIZER, BRAVE_ENDPOINT_VIDEOS, BRAVE_ENDPOINT_WEB, BRAVE_THUMBNAIL_ORIGIN,
+    COUNTRY_OPTIONS, DEFAULT_AUTO_PAGINATE_MAX_PAGES, DEFAULT_CACHE_MAX_ENTRIES,
+    DEFAULT_CACHE_TTL_SECS, DEFAULT_HISTORY_SIZE, DEFAULT_HTTP_LISTEN_ADDR, DEFAULT_MAX_BYTES,
+    DEFAULT_MAX_LINES, DEFAULT_MAX_MAX_BYTES, DEFAULT_MAX_MAX_LINES, DEFAULT_MAX_RESPONSE_BYTES,
+    DEFAULT_MAX_RETRY_DELAY_MS, DEFAULT_MAX_SNIPPET_CHARS, DEFAULT_MAX_TITLE_CHARS,
+    DEFAULT_MIN_MAX_BYTES, DEFAULT_MIN_MAX_LINES, DEFAULT_MIRROR_PERCENT,
+    DEFAULT_PER_ATTEMPT_TIMEOUT_MS, DEFAULT_RAW_PAYLOAD_CAP_BYTES, DEFAULT_REPEAT_QUERY_THRESHOLD,
+    DEFAULT_REPEAT_QUERY_WINDOW_SECS, DEFAULT_RETRY_BASE_DELAY_MS, DEFAULT_RETRY_BUDGET_PER_MIN,
+    DEFAULT_RETRY_COUNT, DEFAULT_SECTION_RESULT_FLOOR, DEFAULT_THROTTLE_BURST,
+    DEFAULT_THROTTLE_RATE_PER_SEC, DEFAULT_THUMBNAIL_MAX_BYTES, ENV_ACCEPT_LANGUAGE,
+    ENV_API_KEY_FILE, ENV_API_VERSION, ENV_ARCHIVE_DIR, ENV_AUTO_PAGINATE_MAX_PAGES,
+    ENV_BATCH_STATE_DIR, ENV_BLOCKED_DOMAINS, ENV_BLOCKED_DOMAINS_FILE, ENV_BRAVE_API_KEY,
+    ENV_BRAVE_SEARCH_API_KEY, ENV_BRAVE_SEARCH_API_KEY_CMD, ENV_BRAVE_SEARCH_API_KEY_FILE,
+    ENV_CACHE_MAX_ENTRIES, ENV_CACHE_TTL_SECS, ENV_COMPACT_JSON, ENV_CONNECT_TIMEOUT_MS,
+    ENV_DEFAULT_MAX_BYTES, ENV_DEFAULT_MAX_LINES, ENV_DETECT_QUERY_LANGUAGE, ENV_ENDPOINT_IMAGES,
+    ENV_ENDPOINT_LOCAL_DESCRIPTIONS, ENV_ENDPOINT_LOCAL_POIS, ENV_ENDPOINT_NEWS,
+    ENV_ENDPOINT_SPELLCHECK, ENV_ENDPOINT_SUGGEST, ENV_ENDPOINT_SUMMARIZER,
+    ENV_ENDPOINT_THUMBNAILS, ENV_ENDPOINT_VIDEOS, ENV_ENDPOINT_WEB, ENV_HISTORY_FILE,
+    ENV_HISTORY_QUERIES, ENV_HISTORY_SIZE, ENV_HTTP2_PRIOR_KNOWLEDGE, ENV_INFER_COUNTRY,
+    ENV_LISTEN, ENV_LOCALE_DATA_FILE, ENV_LOG, ENV_LOG_FORMAT, ENV_MAX_MAX_BYTES,
+    ENV_MAX_MAX_LINES, ENV_MAX_QUERY_LENGTH, ENV_MAX_RESPONSE_BYTES, ENV_MAX_SECTIONS,
+    ENV_MAX_SNIPPET_CHARS, ENV_MAX_TITLE_CHARS, ENV_MIN_MAX_BYTES, ENV_MIN_MAX_LINES,
+    ENV_MIRROR_PERCENT, ENV_MIRROR_SEND_API_KEY, ENV_MIRROR_URL, ENV_NOTIFY_DIR,
+    ENV_NOTIFY_WEBHOOK_SECRET, ENV_NOTIFY_WEBHOOK_URL, ENV_OFFLINE_FIXTURE_DIR,
+    ENV_PER_ATTEMPT_TIMEOUT_MS, ENV_POOL_IDLE_TIMEOUT_SECS, ENV_POOL_MAX_IDLE_PER_HOST,
+    ENV_RAW_PAYLOAD_CAP_BYTES, ENV_RECORD_DIR, ENV_RECORD_MODE, ENV_REPEAT_QUERY_THRESHOLD,
+    ENV_REPEAT_QUERY_WINDOW_SECS, ENV_RETRY_BASE_DELAY_MS, ENV_RETRY_BUDGET_PER_MIN,
+    ENV_RETRY_COUNT, ENV_RETRY_MAX_DELAY_MS, ENV_SAFE_SEARCH_FLOOR_IMAGES,
+    ENV_SAFE_SEARCH_FLOOR_NEWS, ENV_SAFE_SEARCH_FLOOR_VIDEOS, ENV_SAFE_SEARCH_FLOOR_WEB,
+    ENV_SAFE_SEARCH_MIN, ENV_SECTION_RESULT_FLOOR, ENV_SUPPRESS_NOTICES, ENV_TCP_KEEPALIVE_SECS,
+    ENV_THROTTLE_ADAPTIVE, ENV_THROTTLE_BURST, ENV_THROTTLE_RATE, ENV_THUMBNAIL_MAX_BYTES,
+    ENV_THUMBNAIL_MODE, ENV_TRACE_BAGGAGE, ENV_TRACE_PROPAGATION, ENV_TRANSPORT, MAX_QUERY_LENGTH,
+    MAX_RESULTS, MIN_FIELD_CAP_CHARS, SEARCH_LANGUAGE_OPTIONS, SEARCH_LOG_TARGET,
+    UI_LANGUAGE_OPTIONS,
 };
-use crate::types::OutputLimitSettings;
+use crate::normalization::{api_version_from_str, canonical_ui_language, normalize_safe_search};
+use crate::types::{ApiVersion, LocaleOptions, OutputLimitSettings};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+const BUNDLED_LOCALE_DATA: &str = include_str!("../data/locales.json");
 
 #[derive(Debug, Clone)]
 pub struct BraveEndpoints {
@@ -20,6 +52,13 @@ pub struct BraveEndpoints {
     pub news: String,
     pub images: String,
     pub videos: String,
+    pub summarizer: String,
+    pub suggest: String,
+    pub spellcheck: String,
+    pub local_pois: String,
+    pub local_descriptions: String,
+    /// Origin inline thumbnails may be fetched from; anything else is refused.
+    pub thumbnails: String,
 }
 
 impl BraveEndpoints {
@@ -32,23 +71,334 @@ impl BraveEndpoints {
             crate::types::SearchType::Videos => &self.videos,
         }
     }
+
+    #[must_use]
+    pub const fn default_for(search_type: crate::types::SearchType) -> &'static str {
+        match search_type {
+            crate::types::SearchType::Web => BRAVE_ENDPOINT_WEB,
+            crate::types::SearchType::News => BRAVE_ENDPOINT_NEWS,
+            crate::types::SearchType::Images => BRAVE_ENDPOINT_IMAGES,
+            crate::types::SearchType::Videos => BRAVE_ENDPOINT_VIDEOS,
+        }
+    }
+
+    /// Environment variable that overrides the endpoint for `search_type`.
+    #[must_use]
+    pub const fn env_var_for(search_type: crate::types::SearchType) -> &'static str {
+        match search_type {
+            crate::types::SearchType::Web => ENV_ENDPOINT_WEB,
+            crate::types::SearchType::News => ENV_ENDPOINT_NEWS,
+            crate::types::SearchType::Images => ENV_ENDPOINT_IMAGES,
+            crate::types::SearchType::Videos => ENV_ENDPOINT_VIDEOS,
+        }
+    }
+
+    #[must_use]
+    pub fn is_override(&self, search_type: crate::types::SearchType) -> bool {
+        self.endpoint_for(search_type) != Self::default_for(search_type)
+    }
+
+    /// Whether the thumbnail origin was replaced; an override is trusted as configured and
+    /// skips the https and public-address checks applied to Brave's CDN.
+    #[must_use]
+    pub fn thumbnails_overridden(&self) -> bool {
+        self.thumbnails != BRAVE_THUMBNAIL_ORIGIN
+    }
+
+    #[must_use]
+    pub fn host_for(&self, search_type: crate::types::SearchType) -> String {
+        let endpoint = self.endpoint_for(search_type);
+        url::Url::parse(endpoint)
+            .ok()
+            .and_then(|url| {
+                url.host_str().map(|host| match url.port() {
+                    Some(port) => format!("{host}:{port}"),
+                    None => host.to_string(),
+                })
+            })
+            .unwrap_or_else(|| endpoint.to_string())
+    }
+}
+
+/// Shadow-traffic target: a sample of upstream searches is replayed against `url` in the
+/// background and its parsed results compared with the primary response.
+#[derive(Debug, Clone)]
+pub struct MirrorSettings {
+    pub url: String,
+    pub percent: u32,
+    /// Send the real `X-Subscription-Token` to the mirror; off so a test mirror never sees it.
+    pub send_api_key: bool,
+}
+
+/// Tuning for the HTTP client's connection reuse; unset values keep reqwest's defaults.
+#[derive(Debug, Clone, Default)]
+pub struct HttpPoolSettings {
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout_secs: Option<u64>,
+    pub tcp_keepalive_secs: Option<u64>,
+    /// Speak HTTP/2 without negotiating it first; only for endpoints known to support it.
+    pub http2_prior_knowledge: bool,
+    pub connect_timeout_ms: Option<u64>,
+}
+
+/// Loop detection for identical searches: more than `threshold` within `window_secs` adds a
+/// `REPEATED_QUERY` warning. A `threshold` of 0 disables it.
+#[derive(Debug, Clone)]
+pub struct RepeatQuerySettings {
+    pub threshold: usize,
+    pub window_secs: u64,
+}
+
+/// How the request history records queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistoryQueryMode {
+    /// A short SHA-256 digest only.
+    #[default]
+    Hash,
+    /// The query with email addresses and long numbers masked.
+    Redacted,
+}
+
+impl HistoryQueryMode {
+    #[must_use]
+    pub fn from_env_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "hash" | "" => Some(Self::Hash),
+            "redacted" | "redact" => Some(Self::Redacted),
+            _ => None,
+        }
+    }
+}
+
+/// Whether upstream search payloads are written to the record directory or served from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RecordMode {
+    #[default]
+    Record,
+    /// Serve recorded payloads and never contact Brave; searches never recorded fail.
+    Replay,
+}
+
+impl RecordMode {
+    #[must_use]
+    pub fn from_env_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "record" | "" => Some(Self::Record),
+            "replay" => Some(Self::Replay),
+            _ => None,
+        }
+    }
+}
+
+/// Raw upstream search payloads on disk, one `<cache key>.json` file per search.
+#[derive(Debug, Clone)]
+pub struct RecordSettings {
+    pub dir: PathBuf,
+    pub mode: RecordMode,
+}
+
+/// Ring buffer of recent searches behind `brave_web_search_history`; a `size` of 0 disables it.
+#[derive(Debug, Clone)]
+pub struct HistorySettings {
+    pub size: usize,
+    /// JSON-lines file the buffer is appended to and reloaded from on startup.
+    pub file: Option<PathBuf>,
+    pub queries: HistoryQueryMode,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NotifySettings {
+    pub webhook_url: Option<String>,
+    pub webhook_secret: Option<String>,
+    pub drop_dir: Option<PathBuf>,
+}
+
+impl NotifySettings {
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.webhook_url.is_some() || self.drop_dir.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SafeSearchFloors {
+    pub web: Option<String>,
+    pub news: Option<String>,
+    pub images: Option<String>,
+    pub videos: Option<String>,
+    /// Server-wide minimum for every search type (`moderate` or `strict`), applied after the
+    /// per-type floor.
+    pub minimum: Option<String>,
+}
+
+impl SafeSearchFloors {
+    #[must_use]
+    pub fn floor_for(&self, search_type: crate::types::SearchType) -> Option<&str> {
+        match search_type {
+            crate::types::SearchType::Web => self.web.as_deref(),
+            crate::types::SearchType::News => self.news.as_deref(),
+            crate::types::SearchType::Images => self.images.as_deref(),
+            crate::types::SearchType::Videos => self.videos.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThumbnailMode {
+    #[default]
+    Off,
+    Url,
+    Inline,
+}
+
+impl ThumbnailMode {
+    #[must_use]
+    pub fn from_env_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "off" | "" => Some(Self::Off),
+            "url" => Some(Self::Url),
+            "inline" | "proxy" => Some(Self::Inline),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Url => "url",
+            Self::Inline => "inline",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QueryLanguageDetection {
+    #[default]
+    Off,
+    Language,
+    LanguageAndCountry,
+}
+
+impl QueryLanguageDetection {
+    #[must_use]
+    pub fn from_env_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "off" | "false" | "0" | "" => Some(Self::Off),
+            "language" | "on" | "true" | "1" => Some(Self::Language),
+            "language_and_country" | "language+country" => Some(Self::LanguageAndCountry),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Language => "language",
+            Self::LanguageAndCountry => "language_and_country",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransportMode {
+    #[default]
+    Stdio,
+    Http,
+}
+
+impl TransportMode {
+    #[must_use]
+    pub fn from_env_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "stdio" | "" => Some(Self::Stdio),
+            "http" | "sse" | "streamable-http" => Some(Self::Http),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Stdio => "stdio",
+            Self::Http => "http",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    /// One JSON object per line, for log shippers.
+    Json,
+}
+
+impl LogFormat {
+    #[must_use]
+    pub fn from_env_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "text" | "" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct RuntimeConfig {
     pub output_limits: OutputLimitSettings,
     pub cache_ttl_secs: u64,
+    pub cache_max_entries: usize,
     pub throttle_rate_per_sec: u32,
     pub throttle_burst: u32,
+    pub throttle_adaptive: bool,
     pub retry_count: usize,
     pub retry_base_delay_ms: u64,
     pub retry_max_delay_ms: u64,
+    /// Retries all requests may spend per minute; 0 leaves them unbudgeted.
+    pub retry_budget_per_min: u32,
     pub per_attempt_timeout_ms: u64,
+    pub http_pool: HttpPoolSettings,
     pub max_response_bytes: usize,
     pub raw_payload_cap_bytes: usize,
+    pub thumbnail_mode: ThumbnailMode,
+    pub thumbnail_max_bytes: usize,
     pub max_query_length: usize,
     pub endpoints: BraveEndpoints,
+    pub safe_search_floors: SafeSearchFloors,
+    /// Hosts whose results are removed from every response.
+    pub blocked_domains: DomainBlocklist,
+    pub locales: LocaleOptions,
+    pub locale_data_error: Option<String>,
+    /// Settings that were set but unusable, so a default or clamped value stands in for them.
+    pub config_warnings: Vec<String>,
+    pub infer_country_from_ui_language: bool,
+    pub detect_query_language: QueryLanguageDetection,
+    pub suppressed_notices: Vec<String>,
+    pub api_version: ApiVersion,
+    /// Default for the `compact` argument: single-line JSON instead of pretty-printed.
+    pub compact_json: bool,
     pub log_filter: String,
+    pub log_format: LogFormat,
+    pub accept_language: Option<String>,
+    pub trace_propagation: bool,
+    pub trace_baggage: Option<String>,
+    pub transport: TransportMode,
+    pub listen_addr: SocketAddr,
+    pub batch_state_dir: Option<PathBuf>,
+    /// Directory archiving each search by trace id for `brave_web_search_replay`.
+    pub archive_dir: Option<PathBuf>,
+    /// Canned Brave payloads served instead of failing when no API key is configured.
+    pub offline_fixture_dir: Option<PathBuf>,
+    pub record: Option<RecordSettings>,
+    pub notify: NotifySettings,
+    pub mirror: Option<MirrorSettings>,
+    pub repeat_query: RepeatQuerySettings,
+    pub history: HistorySettings,
+    /// Follow-up pages one `auto_paginate` request may fetch; `0` disables auto-pagination.
+    pub auto_paginate_max_pages: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -58,23 +408,28 @@ pub struct ApiKeyConfig {
 }
 
 impl ApiKeyConfig {
+    /// Looks up the key in the files named by `CODEX_BRAVE_API_KEY_FILE` and
+    /// `BRAVE_SEARCH_API_KEY_FILE`, then the output of `BRAVE_SEARCH_API_KEY_CMD`, then the
+    /// environment. Re-read on every key reload, so rotating the file contents (or whatever the
+    /// command reads) swaps the key in place.
     #[must_use]
     pub fn from_env() -> Self {
-        if let Ok(value) = std::env::var(ENV_BRAVE_SEARCH_API_KEY) {
-            let trimmed = value.trim();
-            if !trimmed.is_empty() {
-                return Self {
-                    key: Some(trimmed.to_string()),
-                    source: Some(ENV_BRAVE_SEARCH_API_KEY.to_string()),
-                };
-            }
-        }
-        if let Ok(value) = std::env::var(ENV_BRAVE_API_KEY) {
-            let trimmed = value.trim();
-            if !trimmed.is_empty() {
+        let lookups: [(&str, KeyLookup); 5] = [
+            (ENV_API_KEY_FILE, read_key_file),
+            (ENV_BRAVE_SEARCH_API_KEY_FILE, read_key_file),
+            (ENV_BRAVE_SEARCH_API_KEY_CMD, run_key_command),
+            (ENV_BRAVE_SEARCH_API_KEY, |value| Some(value.to_string())),
+            (ENV_BRAVE_API_KEY, |value| Some(value.to_string())),
+        ];
+        for (variable, lookup) in lookups {
+            let key = env_value(variable)
+                .and_then(|value| lookup(&value))
+                .map(|key| key.trim().to_string())
+                .filter(|key| !key.is_empty());
+            if let Some(key) = key {
                 return Self {
-                    key: Some(trimmed.to_string()),
-                    source: Some(ENV_BRAVE_API_KEY.to_string()),
+                    key: Some(key),
+                    source: Some(variable.to_string()),
                 };
             }
         }
@@ -93,69 +448,274 @@ impl ApiKeyConfig {
 impl RuntimeConfig {
     #[must_use]
     pub fn from_env() -> Self {
-        let min_max_lines = get_env_usize(ENV_MIN_MAX_LINES).unwrap_or(DEFAULT_MIN_MAX_LINES);
-        let min_max_bytes = get_env_usize(ENV_MIN_MAX_BYTES).unwrap_or(DEFAULT_MIN_MAX_BYTES);
-        let max_max_lines = get_env_usize(ENV_MAX_MAX_LINES).unwrap_or(DEFAULT_MAX_MAX_LINES);
-        let max_max_bytes = get_env_usize(ENV_MAX_MAX_BYTES).unwrap_or(DEFAULT_MAX_MAX_BYTES);
+        Self::from_source(&ConfigSource::default())
+    }
+
+    /// Builds the config from `source`: environment variables first, then config file values.
+    #[must_use]
+    pub fn from_source(source: &ConfigSource) -> Self {
+        let min_max_lines = source
+            .usize(ENV_MIN_MAX_LINES)
+            .unwrap_or(DEFAULT_MIN_MAX_LINES);
+        let min_max_bytes = source
+            .usize(ENV_MIN_MAX_BYTES)
+            .unwrap_or(DEFAULT_MIN_MAX_BYTES);
+        let max_max_lines = source
+            .usize(ENV_MAX_MAX_LINES)
+            .unwrap_or(DEFAULT_MAX_MAX_LINES);
+        let max_max_bytes = source
+            .usize(ENV_MAX_MAX_BYTES)
+            .unwrap_or(DEFAULT_MAX_MAX_BYTES);
 
         let clamped_min_lines = min_max_lines.min(max_max_lines);
         let clamped_min_bytes = min_max_bytes.min(max_max_bytes);
 
         let default_max_lines = clamp_usize(
-            get_env_usize(ENV_DEFAULT_MAX_LINES).unwrap_or(DEFAULT_MAX_LINES),
+            source
+                .usize(ENV_DEFAULT_MAX_LINES)
+                .unwrap_or(DEFAULT_MAX_LINES),
             clamped_min_lines,
             max_max_lines,
         );
         let default_max_bytes = clamp_usize(
-            get_env_usize(ENV_DEFAULT_MAX_BYTES).unwrap_or(DEFAULT_MAX_BYTES),
+            source
+                .usize(ENV_DEFAULT_MAX_BYTES)
+                .unwrap_or(DEFAULT_MAX_BYTES),
             clamped_min_bytes,
             max_max_bytes,
         );
 
-        let cache_ttl_secs = get_env_u64(ENV_CACHE_TTL_SECS).unwrap_or(DEFAULT_CACHE_TTL_SECS);
-        let throttle_rate_per_sec = get_env_u32(ENV_THROTTLE_RATE)
+        let section_result_floor = source
+            .usize(ENV_SECTION_RESULT_FLOOR)
+            .unwrap_or(DEFAULT_SECTION_RESULT_FLOOR)
+            .min(MAX_RESULTS);
+        let max_sections = source.usize(ENV_MAX_SECTIONS).filter(|value| *value > 0);
+        let max_title_chars = source
+            .usize(ENV_MAX_TITLE_CHARS)
+            .unwrap_or(DEFAULT_MAX_TITLE_CHARS)
+            .max(MIN_FIELD_CAP_CHARS);
+        let max_snippet_chars = source
+            .usize(ENV_MAX_SNIPPET_CHARS)
+            .unwrap_or(DEFAULT_MAX_SNIPPET_CHARS)
+            .max(MIN_FIELD_CAP_CHARS);
+
+        let cache_ttl_secs = source
+            .u64(ENV_CACHE_TTL_SECS)
+            .unwrap_or(DEFAULT_CACHE_TTL_SECS);
+        let cache_max_entries = source
+            .usize(ENV_CACHE_MAX_ENTRIES)
+            .unwrap_or(DEFAULT_CACHE_MAX_ENTRIES)
+            .max(1);
+        let throttle_rate_per_sec = source
+            .u32(ENV_THROTTLE_RATE)
             .unwrap_or(DEFAULT_THROTTLE_RATE_PER_SEC)
             .max(1);
-        let throttle_burst = get_env_u32(ENV_THROTTLE_BURST)
+        let throttle_adaptive = source.bool(ENV_THROTTLE_ADAPTIVE).unwrap_or(true);
+        let throttle_burst = source
+            .u32(ENV_THROTTLE_BURST)
             .unwrap_or(DEFAULT_THROTTLE_BURST)
             .max(throttle_rate_per_sec)
             .max(1);
 
-        let retry_count = get_env_usize(ENV_RETRY_COUNT)
+        let retry_count = source
+            .usize(ENV_RETRY_COUNT)
             .unwrap_or(DEFAULT_RETRY_COUNT)
             .clamp(0, 10);
-        let retry_base_delay_ms = get_env_u64(ENV_RETRY_BASE_DELAY_MS)
+        let retry_base_delay_ms = source
+            .u64(ENV_RETRY_BASE_DELAY_MS)
             .unwrap_or(DEFAULT_RETRY_BASE_DELAY_MS)
             .max(1);
-        let retry_max_delay_ms = get_env_u64(ENV_RETRY_MAX_DELAY_MS)
+        let retry_max_delay_ms = source
+            .u64(ENV_RETRY_MAX_DELAY_MS)
             .unwrap_or(DEFAULT_MAX_RETRY_DELAY_MS)
             .max(retry_base_delay_ms);
-        let per_attempt_timeout_ms = get_env_u64(ENV_PER_ATTEMPT_TIMEOUT_MS)
+        let retry_budget_per_min = source
+            .u32(ENV_RETRY_BUDGET_PER_MIN)
+            .unwrap_or(DEFAULT_RETRY_BUDGET_PER_MIN);
+        let per_attempt_timeout_ms = source
+            .u64(ENV_PER_ATTEMPT_TIMEOUT_MS)
             .unwrap_or(DEFAULT_PER_ATTEMPT_TIMEOUT_MS)
             .max(100);
 
-        let max_response_bytes = get_env_usize(ENV_MAX_RESPONSE_BYTES)
+        let http_pool = HttpPoolSettings {
+            pool_max_idle_per_host: source.usize(ENV_POOL_MAX_IDLE_PER_HOST),
+            pool_idle_timeout_secs: source.u64(ENV_POOL_IDLE_TIMEOUT_SECS),
+            tcp_keepalive_secs: source.u64(ENV_TCP_KEEPALIVE_SECS).filter(|secs| *secs > 0),
+            http2_prior_knowledge: source.bool(ENV_HTTP2_PRIOR_KNOWLEDGE).unwrap_or(false),
+            connect_timeout_ms: source.u64(ENV_CONNECT_TIMEOUT_MS).filter(|ms| *ms > 0),
+        };
+
+        let max_response_bytes = source
+            .usize(ENV_MAX_RESPONSE_BYTES)
             .unwrap_or(DEFAULT_MAX_RESPONSE_BYTES)
             .max(1_024);
-        let raw_payload_cap_bytes = get_env_usize(ENV_RAW_PAYLOAD_CAP_BYTES)
+        let raw_payload_cap_bytes = source
+            .usize(ENV_RAW_PAYLOAD_CAP_BYTES)
             .unwrap_or(DEFAULT_RAW_PAYLOAD_CAP_BYTES)
             .max(1_024);
-        let max_query_length = get_env_usize(ENV_MAX_QUERY_LENGTH)
+        let thumbnail_mode = source
+            .var(ENV_THUMBNAIL_MODE)
+            .and_then(|value| ThumbnailMode::from_env_value(&value))
+            .unwrap_or_default();
+        let thumbnail_max_bytes = source
+            .usize(ENV_THUMBNAIL_MAX_BYTES)
+            .unwrap_or(DEFAULT_THUMBNAIL_MAX_BYTES)
+            .max(1);
+
+        let max_query_length = source
+            .usize(ENV_MAX_QUERY_LENGTH)
             .unwrap_or(MAX_QUERY_LENGTH)
             .clamp(256, 10_000);
 
         let endpoints = BraveEndpoints {
-            web: std::env::var(ENV_ENDPOINT_WEB).unwrap_or_else(|_| BRAVE_ENDPOINT_WEB.to_string()),
-            news: std::env::var(ENV_ENDPOINT_NEWS)
-                .unwrap_or_else(|_| BRAVE_ENDPOINT_NEWS.to_string()),
-            images: std::env::var(ENV_ENDPOINT_IMAGES)
-                .unwrap_or_else(|_| BRAVE_ENDPOINT_IMAGES.to_string()),
-            videos: std::env::var(ENV_ENDPOINT_VIDEOS)
-                .unwrap_or_else(|_| BRAVE_ENDPOINT_VIDEOS.to_string()),
+            web: source
+                .var(ENV_ENDPOINT_WEB)
+                .unwrap_or_else(|| BRAVE_ENDPOINT_WEB.to_string()),
+            news: source
+                .var(ENV_ENDPOINT_NEWS)
+                .unwrap_or_else(|| BRAVE_ENDPOINT_NEWS.to_string()),
+            images: source
+                .var(ENV_ENDPOINT_IMAGES)
+                .unwrap_or_else(|| BRAVE_ENDPOINT_IMAGES.to_string()),
+            videos: source
+                .var(ENV_ENDPOINT_VIDEOS)
+                .unwrap_or_else(|| BRAVE_ENDPOINT_VIDEOS.to_string()),
+            summarizer: source
+                .var(ENV_ENDPOINT_SUMMARIZER)
+                .unwrap_or_else(|| BRAVE_ENDPOINT_SUMMARIZER.to_string()),
+            suggest: source
+                .var(ENV_ENDPOINT_SUGGEST)
+                .unwrap_or_else(|| BRAVE_ENDPOINT_SUGGEST.to_string()),
+            spellcheck: source
+                .var(ENV_ENDPOINT_SPELLCHECK)
+                .unwrap_or_else(|| BRAVE_ENDPOINT_SPELLCHECK.to_string()),
+            local_pois: source
+                .var(ENV_ENDPOINT_LOCAL_POIS)
+                .unwrap_or_else(|| BRAVE_ENDPOINT_LOCAL_POIS.to_string()),
+            local_descriptions: source
+                .var(ENV_ENDPOINT_LOCAL_DESCRIPTIONS)
+                .unwrap_or_else(|| BRAVE_ENDPOINT_LOCAL_DESCRIPTIONS.to_string()),
+            thumbnails: source
+                .var(ENV_ENDPOINT_THUMBNAILS)
+                .unwrap_or_else(|| BRAVE_THUMBNAIL_ORIGIN.to_string()),
+        };
+
+        let safe_search_floors = SafeSearchFloors {
+            web: source.safe_search(ENV_SAFE_SEARCH_FLOOR_WEB),
+            news: source.safe_search(ENV_SAFE_SEARCH_FLOOR_NEWS),
+            images: source.safe_search(ENV_SAFE_SEARCH_FLOOR_IMAGES),
+            videos: source.safe_search(ENV_SAFE_SEARCH_FLOOR_VIDEOS),
+            minimum: source
+                .safe_search(ENV_SAFE_SEARCH_MIN)
+                .filter(|minimum| minimum != "off"),
         };
 
-        let log_filter = std::env::var(ENV_LOG)
-            .unwrap_or_else(|_| "warn,codex_brave_web_search=warn".to_string());
+        let blocked_domains =
+            DomainBlocklist::parse(&source.var(ENV_BLOCKED_DOMAINS).unwrap_or_default()).extend(
+                source
+                    .var(ENV_BLOCKED_DOMAINS_FILE)
+                    .and_then(|path| std::fs::read_to_string(path).ok())
+                    .map(|text| DomainBlocklist::parse(&text))
+                    .unwrap_or_default(),
+            );
+
+        let (locales, locale_data_error) = load_locale_options(source);
+        let infer_country_from_ui_language = source.bool(ENV_INFER_COUNTRY).unwrap_or(true);
+        let detect_query_language = source
+            .var(ENV_DETECT_QUERY_LANGUAGE)
+            .and_then(|value| QueryLanguageDetection::from_env_value(&value))
+            .unwrap_or_default();
+
+        let suppressed_notices = source
+            .var(ENV_SUPPRESS_NOTICES)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|code| code.trim().to_uppercase())
+                    .filter(|code| !code.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let api_version = source
+            .var(ENV_API_VERSION)
+            .and_then(|value| api_version_from_str(&value))
+            .unwrap_or_default();
+        let compact_json = source.bool(ENV_COMPACT_JSON).unwrap_or(false);
+
+        let log_format = source
+            .var(ENV_LOG_FORMAT)
+            .and_then(|value| LogFormat::from_env_value(&value))
+            .unwrap_or_default();
+        // JSON logs exist to be shipped, so they include the per-search events by default.
+        let log_filter = source.var(ENV_LOG).unwrap_or_else(|| match log_format {
+            LogFormat::Text => "warn,codex_brave_web_search=warn".to_string(),
+            LogFormat::Json => format!("warn,codex_brave_web_search=warn,{SEARCH_LOG_TARGET}=info"),
+        });
+
+        let transport = source
+            .var(ENV_TRANSPORT)
+            .and_then(|value| TransportMode::from_env_value(&value))
+            .unwrap_or_default();
+        let listen_addr = source
+            .var(ENV_LISTEN)
+            .and_then(|value| value.trim().parse::<SocketAddr>().ok())
+            .unwrap_or_else(default_listen_addr);
+        let batch_state_dir = source.var(ENV_BATCH_STATE_DIR).map(PathBuf::from);
+        let archive_dir = source
+            .var(ENV_ARCHIVE_DIR)
+            .filter(|_| cfg!(feature = "archive"))
+            .map(PathBuf::from);
+        let offline_fixture_dir = source.var(ENV_OFFLINE_FIXTURE_DIR).map(PathBuf::from);
+        let record = source
+            .var(ENV_RECORD_DIR)
+            .filter(|_| cfg!(feature = "archive"))
+            .map(|dir| RecordSettings {
+                dir: PathBuf::from(dir),
+                mode: source
+                    .var(ENV_RECORD_MODE)
+                    .and_then(|value| RecordMode::from_env_value(&value))
+                    .unwrap_or_default(),
+            });
+        let accept_language = source.var(ENV_ACCEPT_LANGUAGE);
+        let mirror = source.var(ENV_MIRROR_URL).map(|url| MirrorSettings {
+            url,
+            percent: source
+                .u32(ENV_MIRROR_PERCENT)
+                .unwrap_or(DEFAULT_MIRROR_PERCENT)
+                .min(100),
+            send_api_key: source.bool(ENV_MIRROR_SEND_API_KEY).unwrap_or(false),
+        });
+        let repeat_query = RepeatQuerySettings {
+            threshold: source
+                .usize(ENV_REPEAT_QUERY_THRESHOLD)
+                .unwrap_or(DEFAULT_REPEAT_QUERY_THRESHOLD),
+            window_secs: source
+                .u64(ENV_REPEAT_QUERY_WINDOW_SECS)
+                .unwrap_or(DEFAULT_REPEAT_QUERY_WINDOW_SECS)
+                .max(1),
+        };
+        let history = HistorySettings {
+            size: source
+                .usize(ENV_HISTORY_SIZE)
+                .unwrap_or(DEFAULT_HISTORY_SIZE),
+            file: source.var(ENV_HISTORY_FILE).map(PathBuf::from),
+            queries: source
+                .var(ENV_HISTORY_QUERIES)
+                .and_then(|value| HistoryQueryMode::from_env_value(&value))
+                .unwrap_or_default(),
+        };
+        let auto_paginate_max_pages = source
+            .usize(ENV_AUTO_PAGINATE_MAX_PAGES)
+            .unwrap_or(DEFAULT_AUTO_PAGINATE_MAX_PAGES);
+        let trace_propagation = source.bool(ENV_TRACE_PROPAGATION).unwrap_or(false);
+        let trace_baggage = source.var(ENV_TRACE_BAGGAGE);
+
+        let notify = NotifySettings {
+            webhook_url: source.var(ENV_NOTIFY_WEBHOOK_URL),
+            webhook_secret: source.var(ENV_NOTIFY_WEBHOOK_SECRET),
+            drop_dir: source.var(ENV_NOTIFY_DIR).map(PathBuf::from),
+        };
 
         Self {
             output_limits: OutputLimitSettings {
@@ -165,19 +725,58 @@ impl RuntimeConfig {
                 min_max_bytes: clamped_min_bytes,
                 max_max_lines,
                 max_max_bytes,
+                section_result_floor,
+                max_sections,
+                max_title_chars,
+                max_snippet_chars,
             },
             cache_ttl_secs,
+            cache_max_entries,
             throttle_rate_per_sec,
             throttle_burst,
+            throttle_adaptive,
             retry_count,
             retry_base_delay_ms,
             retry_max_delay_ms,
+            retry_budget_per_min,
             per_attempt_timeout_ms,
+            http_pool,
             max_response_bytes,
             raw_payload_cap_bytes,
+            thumbnail_mode,
+            thumbnail_max_bytes,
             max_query_length,
             endpoints,
+            safe_search_floors,
+            blocked_domains,
+            locales,
+            locale_data_error,
+            config_warnings: source
+                .invalid_settings()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            infer_country_from_ui_language,
+            detect_query_language,
+            suppressed_notices,
+            api_version,
+            compact_json,
             log_filter,
+            log_format,
+            accept_language,
+            trace_propagation,
+            trace_baggage,
+            transport,
+            listen_addr,
+            batch_state_dir,
+            archive_dir,
+            offline_fixture_dir,
+            record,
+            notify,
+            mirror,
+            repeat_query,
+            history,
+            auto_paginate_max_pages,
         }
     }
 
@@ -201,20 +800,374 @@ impl RuntimeConfig {
     }
 }
 
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LocaleDataFile {
+    countries: Vec<String>,
+    search_languages: Vec<String>,
+    ui_languages: Vec<String>,
+}
+
+impl LocaleOptions {
+    #[must_use]
+    pub fn builtin() -> Self {
+        Self {
+            countries: COUNTRY_OPTIONS.iter().map(ToString::to_string).collect(),
+            search_languages: SEARCH_LANGUAGE_OPTIONS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            ui_languages: UI_LANGUAGE_OPTIONS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+
+    fn merge(&mut self, data: LocaleDataFile) {
+        merge_options(
+            &mut self.countries,
+            data.countries
+                .iter()
+                .map(|value| value.trim().to_uppercase()),
+        );
+        merge_options(
+            &mut self.search_languages,
+            data.search_languages
+                .iter()
+                .map(|value| value.trim().to_lowercase()),
+        );
+        merge_options(
+            &mut self.ui_languages,
+            data.ui_languages
+                .iter()
+                .map(|value| canonical_ui_language(value)),
+        );
+    }
+}
+
+fn merge_options(target: &mut Vec<String>, values: impl Iterator<Item = String>) {
+    for value in values {
+        if !value.is_empty() && !target.contains(&value) {
+            target.push(value);
+        }
+    }
+}
+
+fn load_locale_options(source: &ConfigSource) -> (LocaleOptions, Option<String>) {
+    let mut options = LocaleOptions::builtin();
+
+    if let Ok(bundled) = serde_json::from_str::<LocaleDataFile>(BUNDLED_LOCALE_DATA) {
+        options.merge(bundled);
+    }
+
+    let Some(path) = source.var(ENV_LOCALE_DATA_FILE) else {
+        return (options, None);
+    };
+
+    let loaded = std::fs::read_to_string(&path)
+        .map_err(|error| error.to_string())
+        .and_then(|text| {
+            serde_json::from_str::<LocaleDataFile>(&text).map_err(|error| error.to_string())
+        });
+
+    match loaded {
+        Ok(data) => {
+            options.merge(data);
+            (options, None)
+        }
+        Err(error) => (
+            options,
+            Some(format!(
+                "{ENV_LOCALE_DATA_FILE} '{path}' could not be loaded: {error}"
+            )),
+        ),
+    }
+}
+
+fn default_listen_addr() -> SocketAddr {
+    DEFAULT_HTTP_LISTEN_ADDR
+        .parse()
+        .expect("default listen address is valid")
+}
+
 fn clamp_usize(value: usize, min: usize, max: usize) -> usize {
     value.clamp(min, max)
 }
 
-fn get_env_usize(name: &str) -> Option<usize> {
+/// Turns the value of a key variable into the key.
+type KeyLookup = fn(&str) -> Option<String>;
+
+fn read_key_file(path: &str) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+}
+
+/// Runs `command` through the platform shell and returns its stdout when it exits successfully.
+fn run_key_command(command: &str) -> Option<String> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = std::process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = std::process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let output = shell
+        .arg(command)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+fn env_value(name: &str) -> Option<String> {
     std::env::var(name)
         .ok()
-        .and_then(|v| v.parse::<usize>().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Reads settings from the environment, falling back to values loaded from a config file
+/// (keyed by environment variable name) for variables that are unset or blank.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSource {
+    file: BTreeMap<String, String>,
+}
+
+impl ConfigSource {
+    #[must_use]
+    pub const fn with_file_values(file: BTreeMap<String, String>) -> Self {
+        Self { file }
+    }
+
+    /// The trimmed value of `name`, with the environment taking precedence over the file.
+    #[must_use]
+    pub fn var(&self, name: &str) -> Option<String> {
+        env_value(name).or_else(|| self.file_value(name))
+    }
+
+    fn file_value(&self, name: &str) -> Option<String> {
+        self.file
+            .get(name)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Every set value that fails its check, in declaration order.
+    #[must_use]
+    pub fn invalid_settings(&self) -> Vec<InvalidSetting> {
+        SETTING_CHECKS
+            .iter()
+            .filter_map(|&(variable, check)| {
+                let (value, from_file) = match env_value(variable) {
+                    Some(value) => (value, false),
+                    None => (self.file_value(variable)?, true),
+                };
+                check(&value).err().map(|reason| InvalidSetting {
+                    variable,
+                    value,
+                    from_file,
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    fn usize(&self, name: &str) -> Option<usize> {
+        self.var(name).and_then(|v| v.parse::<usize>().ok())
+    }
+
+    fn u64(&self, name: &str) -> Option<u64> {
+        self.var(name).and_then(|v| v.parse::<u64>().ok())
+    }
+
+    fn u32(&self, name: &str) -> Option<u32> {
+        self.var(name).and_then(|v| v.parse::<u32>().ok())
+    }
+
+    fn safe_search(&self, name: &str) -> Option<String> {
+        normalize_safe_search(self.var(name).as_deref())
+    }
+
+    fn bool(&self, name: &str) -> Option<bool> {
+        parse_bool(&self.var(name)?)
+    }
 }
 
-fn get_env_u64(name: &str) -> Option<u64> {
-    std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok())
+type Check = fn(&str) -> Result<(), String>;
+
+/// What each setting must satisfy; `from_source` ignores or clamps values that do not.
+const SETTING_CHECKS: &[(&str, Check)] = &[
+    (ENV_MIN_MAX_LINES, |value| at_least(value, 1)),
+    (ENV_MIN_MAX_BYTES, |value| at_least(value, 1)),
+    (ENV_MAX_MAX_LINES, |value| at_least(value, 1)),
+    (ENV_MAX_MAX_BYTES, |value| at_least(value, 1)),
+    (ENV_DEFAULT_MAX_LINES, |value| at_least(value, 1)),
+    (ENV_DEFAULT_MAX_BYTES, |value| at_least(value, 1)),
+    (ENV_SECTION_RESULT_FLOOR, |value| {
+        between(value, 0, MAX_RESULTS)
+    }),
+    (ENV_MAX_SECTIONS, |value| at_least(value, 1)),
+    (ENV_MAX_TITLE_CHARS, |value| {
+        at_least(value, MIN_FIELD_CAP_CHARS)
+    }),
+    (ENV_MAX_SNIPPET_CHARS, |value| {
+        at_least(value, MIN_FIELD_CAP_CHARS)
+    }),
+    (ENV_CACHE_TTL_SECS, integer),
+    (ENV_CACHE_MAX_ENTRIES, |value| at_least(value, 1)),
+    (ENV_THROTTLE_RATE, |value| {
+        between(value, 1, u32::MAX as usize)
+    }),
+    (ENV_THROTTLE_BURST, |value| {
+        between(value, 1, u32::MAX as usize)
+    }),
+    (ENV_THROTTLE_ADAPTIVE, boolean),
+    (ENV_RETRY_COUNT, |value| between(value, 0, 10)),
+    (ENV_RETRY_BASE_DELAY_MS, |value| at_least(value, 1)),
+    (ENV_RETRY_MAX_DELAY_MS, |value| at_least(value, 1)),
+    (ENV_RETRY_BUDGET_PER_MIN, |value| {
+        between(value, 0, u32::MAX as usize)
+    }),
+    (ENV_PER_ATTEMPT_TIMEOUT_MS, |value| at_least(value, 100)),
+    (ENV_POOL_MAX_IDLE_PER_HOST, integer),
+    (ENV_POOL_IDLE_TIMEOUT_SECS, integer),
+    (ENV_TCP_KEEPALIVE_SECS, integer),
+    (ENV_HTTP2_PRIOR_KNOWLEDGE, boolean),
+    (ENV_CONNECT_TIMEOUT_MS, integer),
+    (ENV_MAX_RESPONSE_BYTES, |value| at_least(value, 1_024)),
+    (ENV_RAW_PAYLOAD_CAP_BYTES, |value| at_least(value, 1_024)),
+    (ENV_THUMBNAIL_MODE, |value| {
+        one_of(value, ThumbnailMode::from_env_value)
+    }),
+    (ENV_THUMBNAIL_MAX_BYTES, |value| at_least(value, 1)),
+    (ENV_MAX_QUERY_LENGTH, |value| between(value, 256, 10_000)),
+    (ENV_SAFE_SEARCH_FLOOR_WEB, safe_search),
+    (ENV_SAFE_SEARCH_FLOOR_NEWS, safe_search),
+    (ENV_SAFE_SEARCH_FLOOR_IMAGES, safe_search),
+    (ENV_SAFE_SEARCH_FLOOR_VIDEOS, safe_search),
+    (ENV_SAFE_SEARCH_MIN, |value| {
+        match normalize_safe_search(Some(value)).as_deref() {
+            Some("moderate" | "strict") => Ok(()),
+            _ => Err("expected moderate or strict".to_string()),
+        }
+    }),
+    (ENV_BLOCKED_DOMAINS, validate_patterns),
+    (ENV_BLOCKED_DOMAINS_FILE, |value| {
+        std::fs::read_to_string(value)
+            .map_err(|error| error.to_string())
+            .and_then(|text| validate_patterns(&text))
+    }),
+    (ENV_INFER_COUNTRY, boolean),
+    (ENV_DETECT_QUERY_LANGUAGE, |value| {
+        one_of(value, QueryLanguageDetection::from_env_value)
+    }),
+    (ENV_API_VERSION, |value| one_of(value, api_version_from_str)),
+    (ENV_COMPACT_JSON, boolean),
+    (ENV_LOG_FORMAT, |value| {
+        one_of(value, LogFormat::from_env_value)
+    }),
+    (ENV_TRANSPORT, |value| {
+        one_of(value, TransportMode::from_env_value)
+    }),
+    (ENV_LISTEN, socket_addr),
+    (ENV_ARCHIVE_DIR, archive_feature),
+    (ENV_RECORD_DIR, archive_feature),
+    (ENV_RECORD_MODE, |value| {
+        one_of(value, RecordMode::from_env_value)
+    }),
+    (ENV_MIRROR_PERCENT, |value| between(value, 0, 100)),
+    (ENV_MIRROR_SEND_API_KEY, boolean),
+    (ENV_REPEAT_QUERY_THRESHOLD, integer),
+    (ENV_REPEAT_QUERY_WINDOW_SECS, |value| at_least(value, 1)),
+    (ENV_HISTORY_SIZE, integer),
+    (ENV_HISTORY_QUERIES, |value| {
+        one_of(value, HistoryQueryMode::from_env_value)
+    }),
+    (ENV_AUTO_PAGINATE_MAX_PAGES, integer),
+    (ENV_TRACE_PROPAGATION, boolean),
+];
+
+/// A setting whose value fails its check, from either the environment or a config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidSetting {
+    pub variable: &'static str,
+    pub value: String,
+    pub from_file: bool,
+    pub reason: String,
+}
+
+impl std::fmt::Display for InvalidSetting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={:?}: {}", self.variable, self.value, self.reason)
+    }
+}
+
+fn at_least(value: &str, min: usize) -> Result<(), String> {
+    between(value, min, usize::MAX)
+}
+
+fn integer(value: &str) -> Result<(), String> {
+    between(value, 0, usize::MAX)
+}
+
+fn between(value: &str, min: usize, max: usize) -> Result<(), String> {
+    let number = value
+        .parse::<usize>()
+        .map_err(|_| "expected a non-negative integer".to_string())?;
+    if number < min {
+        return Err(format!("must be at least {min}"));
+    }
+    if number > max {
+        return Err(format!("must be at most {max}"));
+    }
+    Ok(())
+}
+
+fn boolean(value: &str) -> Result<(), String> {
+    parse_bool(value)
+        .map(|_| ())
+        .ok_or_else(|| "expected true or false".to_string())
 }
 
-fn get_env_u32(name: &str) -> Option<u32> {
-    std::env::var(name).ok().and_then(|v| v.parse::<u32>().ok())
+fn one_of<T>(value: &str, parse: fn(&str) -> Option<T>) -> Result<(), String> {
+    parse(value)
+        .map(|_| ())
+        .ok_or_else(|| "unsupported value".to_string())
+}
+
+/// Settings that write searches to disk only exist in builds with the `archive` feature.
+fn archive_feature(_value: &str) -> Result<(), String> {
+    if cfg!(feature = "archive") {
+        Ok(())
+    } else {
+        Err("requires a build with the archive feature".to_string())
+    }
+}
+
+fn safe_search(value: &str) -> Result<(), String> {
+    normalize_safe_search(Some(value))
+        .map(|_| ())
+        .ok_or_else(|| "unsupported value".to_string())
+}
+
+fn socket_addr(value: &str) -> Result<(), String> {
+    value
+        .parse::<SocketAddr>()
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+/// Accepts `1/true/yes/on` and `0/false/no/off`, case-insensitively.
+#[must_use]
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
 }
@@ -0,0 +1,175 @@
+//! TOML config file layered under the environment: `--config path.toml` or `CODEX_BRAVE_CONFIG`.
+//!
+//! Keys are the `CODEX_BRAVE_*` variable names without the prefix, lowercased
+//! (`cache_ttl_secs = 600`), and take the same values. Environment variables override the file.
+//! Unknown keys, mistyped values and out-of-range values fail startup rather than falling back
+//! to defaults.
+
+use crate::config::{ConfigSource, RuntimeConfig};
+use crate::constants::{
+    ENV_ACCEPT_LANGUAGE, ENV_API_VERSION, ENV_ARCHIVE_DIR, ENV_AUTO_PAGINATE_MAX_PAGES,
+    ENV_BATCH_STATE_DIR, ENV_BLOCKED_DOMAINS, ENV_BLOCKED_DOMAINS_FILE, ENV_CACHE_MAX_ENTRIES,
+    ENV_CACHE_TTL_SECS, ENV_COMPACT_JSON, ENV_CONFIG, ENV_CONNECT_TIMEOUT_MS,
+    ENV_DEFAULT_MAX_BYTES, ENV_DEFAULT_MAX_LINES, ENV_DETECT_QUERY_LANGUAGE, ENV_ENDPOINT_IMAGES,
+    ENV_ENDPOINT_LOCAL_DESCRIPTIONS, ENV_ENDPOINT_LOCAL_POIS, ENV_ENDPOINT_NEWS,
+    ENV_ENDPOINT_SPELLCHECK, ENV_ENDPOINT_SUGGEST, ENV_ENDPOINT_SUMMARIZER,
+    ENV_ENDPOINT_THUMBNAILS, ENV_ENDPOINT_VIDEOS, ENV_ENDPOINT_WEB, ENV_HISTORY_FILE,
+    ENV_HISTORY_QUERIES, ENV_HISTORY_SIZE, ENV_HTTP2_PRIOR_KNOWLEDGE, ENV_INFER_COUNTRY,
+    ENV_LISTEN, ENV_LOCALE_DATA_FILE, ENV_LOG, ENV_LOG_FORMAT, ENV_MAX_MAX_BYTES,
+    ENV_MAX_MAX_LINES, ENV_MAX_QUERY_LENGTH, ENV_MAX_RESPONSE_BYTES, ENV_MAX_SECTIONS,
+    ENV_MAX_SNIPPET_CHARS, ENV_MAX_TITLE_CHARS, ENV_MIN_MAX_BYTES, ENV_MIN_MAX_LINES,
+    ENV_MIRROR_PERCENT, ENV_MIRROR_SEND_API_KEY, ENV_MIRROR_URL, ENV_NOTIFY_DIR,
+    ENV_NOTIFY_WEBHOOK_SECRET, ENV_NOTIFY_WEBHOOK_URL, ENV_OFFLINE_FIXTURE_DIR,
+    ENV_PER_ATTEMPT_TIMEOUT_MS, ENV_POOL_IDLE_TIMEOUT_SECS, ENV_POOL_MAX_IDLE_PER_HOST,
+    ENV_RAW_PAYLOAD_CAP_BYTES, ENV_RECORD_DIR, ENV_RECORD_MODE, ENV_REPEAT_QUERY_THRESHOLD,
+    ENV_REPEAT_QUERY_WINDOW_SECS, ENV_RETRY_BASE_DELAY_MS, ENV_RETRY_BUDGET_PER_MIN,
+    ENV_RETRY_COUNT, ENV_RETRY_MAX_DELAY_MS, ENV_SAFE_SEARCH_FLOOR_IMAGES,
+    ENV_SAFE_SEARCH_FLOOR_NEWS, ENV_SAFE_SEARCH_FLOOR_VIDEOS, ENV_SAFE_SEARCH_FLOOR_WEB,
+    ENV_SAFE_SEARCH_MIN, ENV_SECTION_RESULT_FLOOR, ENV_SUPPRESS_NOTICES, ENV_TCP_KEEPALIVE_SECS,
+    ENV_THROTTLE_ADAPTIVE, ENV_THROTTLE_BURST, ENV_THROTTLE_RATE, ENV_THUMBNAIL_MAX_BYTES,
+    ENV_THUMBNAIL_MODE, ENV_TRACE_BAGGAGE, ENV_TRACE_PROPAGATION, ENV_TRANSPORT,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Declares the file's keys and the environment variable each one stands in for.
+macro_rules! config_file_keys {
+    ($($key:ident: $ty:ty => $env:ident;)*) => {
+        #[derive(Debug, Default, Deserialize)]
+        #[serde(deny_unknown_fields)]
+        pub struct ConfigFile {
+            $(pub $key: Option<$ty>,)*
+        }
+
+        impl ConfigFile {
+            /// File values keyed by environment variable name.
+            #[must_use]
+            pub fn into_values(self) -> BTreeMap<String, String> {
+                let mut values = BTreeMap::new();
+                $(if let Some(value) = self.$key {
+                    values.insert($env.to_string(), value.to_string());
+                })*
+                values
+            }
+        }
+    };
+}
+
+config_file_keys! {
+    min_max_lines: usize => ENV_MIN_MAX_LINES;
+    min_max_bytes: usize => ENV_MIN_MAX_BYTES;
+    max_max_lines: usize => ENV_MAX_MAX_LINES;
+    max_max_bytes: usize => ENV_MAX_MAX_BYTES;
+    default_max_lines: usize => ENV_DEFAULT_MAX_LINES;
+    default_max_bytes: usize => ENV_DEFAULT_MAX_BYTES;
+    section_result_floor: usize => ENV_SECTION_RESULT_FLOOR;
+    max_sections: usize => ENV_MAX_SECTIONS;
+    max_title_chars: usize => ENV_MAX_TITLE_CHARS;
+    max_snippet_chars: usize => ENV_MAX_SNIPPET_CHARS;
+    cache_ttl_secs: u64 => ENV_CACHE_TTL_SECS;
+    cache_max_entries: usize => ENV_CACHE_MAX_ENTRIES;
+    throttle_rate_per_sec: u32 => ENV_THROTTLE_RATE;
+    throttle_burst: u32 => ENV_THROTTLE_BURST;
+    throttle_adaptive: bool => ENV_THROTTLE_ADAPTIVE;
+    retry_count: usize => ENV_RETRY_COUNT;
+    retry_base_delay_ms: u64 => ENV_RETRY_BASE_DELAY_MS;
+    retry_max_delay_ms: u64 => ENV_RETRY_MAX_DELAY_MS;
+    retry_budget_per_min: u32 => ENV_RETRY_BUDGET_PER_MIN;
+    per_attempt_timeout_ms: u64 => ENV_PER_ATTEMPT_TIMEOUT_MS;
+    pool_max_idle_per_host: usize => ENV_POOL_MAX_IDLE_PER_HOST;
+    pool_idle_timeout_secs: u64 => ENV_POOL_IDLE_TIMEOUT_SECS;
+    tcp_keepalive_secs: u64 => ENV_TCP_KEEPALIVE_SECS;
+    http2_prior_knowledge: bool => ENV_HTTP2_PRIOR_KNOWLEDGE;
+    connect_timeout_ms: u64 => ENV_CONNECT_TIMEOUT_MS;
+    max_response_bytes: usize => ENV_MAX_RESPONSE_BYTES;
+    raw_payload_cap_bytes: usize => ENV_RAW_PAYLOAD_CAP_BYTES;
+    thumbnail_mode: String => ENV_THUMBNAIL_MODE;
+    thumbnail_max_bytes: usize => ENV_THUMBNAIL_MAX_BYTES;
+    max_query_length: usize => ENV_MAX_QUERY_LENGTH;
+    endpoint_web: String => ENV_ENDPOINT_WEB;
+    endpoint_news: String => ENV_ENDPOINT_NEWS;
+    endpoint_images: String => ENV_ENDPOINT_IMAGES;
+    endpoint_videos: String => ENV_ENDPOINT_VIDEOS;
+    endpoint_summarizer: String => ENV_ENDPOINT_SUMMARIZER;
+    endpoint_suggest: String => ENV_ENDPOINT_SUGGEST;
+    endpoint_spellcheck: String => ENV_ENDPOINT_SPELLCHECK;
+    endpoint_local_pois: String => ENV_ENDPOINT_LOCAL_POIS;
+    endpoint_local_descriptions: String => ENV_ENDPOINT_LOCAL_DESCRIPTIONS;
+    endpoint_thumbnails: String => ENV_ENDPOINT_THUMBNAILS;
+    safe_search_floor_web: String => ENV_SAFE_SEARCH_FLOOR_WEB;
+    safe_search_floor_news: String => ENV_SAFE_SEARCH_FLOOR_NEWS;
+    safe_search_floor_images: String => ENV_SAFE_SEARCH_FLOOR_IMAGES;
+    safe_search_floor_videos: String => ENV_SAFE_SEARCH_FLOOR_VIDEOS;
+    safe_search_min: String => ENV_SAFE_SEARCH_MIN;
+    blocked_domains: String => ENV_BLOCKED_DOMAINS;
+    blocked_domains_file: String => ENV_BLOCKED_DOMAINS_FILE;
+    locale_data_file: String => ENV_LOCALE_DATA_FILE;
+    infer_country: bool => ENV_INFER_COUNTRY;
+    detect_query_language: String => ENV_DETECT_QUERY_LANGUAGE;
+    suppress_notices: String => ENV_SUPPRESS_NOTICES;
+    api_version: String => ENV_API_VERSION;
+    compact_json: bool => ENV_COMPACT_JSON;
+    log: String => ENV_LOG;
+    log_format: String => ENV_LOG_FORMAT;
+    transport: String => ENV_TRANSPORT;
+    listen: String => ENV_LISTEN;
+    batch_state_dir: String => ENV_BATCH_STATE_DIR;
+    archive_dir: String => ENV_ARCHIVE_DIR;
+    offline_fixture_dir: String => ENV_OFFLINE_FIXTURE_DIR;
+    record_dir: String => ENV_RECORD_DIR;
+    record_mode: String => ENV_RECORD_MODE;
+    accept_language: String => ENV_ACCEPT_LANGUAGE;
+    mirror_url: String => ENV_MIRROR_URL;
+    mirror_percent: u32 => ENV_MIRROR_PERCENT;
+    mirror_send_api_key: bool => ENV_MIRROR_SEND_API_KEY;
+    repeat_query_threshold: usize => ENV_REPEAT_QUERY_THRESHOLD;
+    repeat_query_window_secs: u64 => ENV_REPEAT_QUERY_WINDOW_SECS;
+    history_size: usize => ENV_HISTORY_SIZE;
+    history_file: String => ENV_HISTORY_FILE;
+    history_queries: String => ENV_HISTORY_QUERIES;
+    auto_paginate_max_pages: usize => ENV_AUTO_PAGINATE_MAX_PAGES;
+    trace_propagation: bool => ENV_TRACE_PROPAGATION;
+    trace_baggage: String => ENV_TRACE_BAGGAGE;
+    notify_webhook_url: String => ENV_NOTIFY_WEBHOOK_URL;
+    notify_webhook_secret: String => ENV_NOTIFY_WEBHOOK_SECRET;
+    notify_dir: String => ENV_NOTIFY_DIR;
+}
+
+/// Loads the config for startup: the file from `path` (or `CODEX_BRAVE_CONFIG`) under the
+/// environment, with every value validated.
+pub fn load_runtime_config(path: Option<&Path>) -> Result<RuntimeConfig, String> {
+    let path = path
+        .map(Path::to_path_buf)
+        .or_else(|| ConfigSource::default().var(ENV_CONFIG).map(PathBuf::from));
+    let source = match path {
+        Some(path) => ConfigSource::with_file_values(read_config_file(&path)?.into_values()),
+        None => ConfigSource::default(),
+    };
+    if let Some(invalid) = source
+        .invalid_settings()
+        .into_iter()
+        .find(|invalid| invalid.from_file)
+    {
+        return Err(format!(
+            "config file key {} = {:?}: {}",
+            file_key(invalid.variable),
+            invalid.value,
+            invalid.reason
+        ));
+    }
+    Ok(RuntimeConfig::from_source(&source))
+}
+
+pub fn read_config_file(path: &Path) -> Result<ConfigFile, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| format!("config file {} could not be read: {error}", path.display()))?;
+    toml::from_str(&text)
+        .map_err(|error| format!("config file {} is invalid: {error}", path.display()))
+}
+
+fn file_key(variable: &str) -> String {
+    variable
+        .trim_start_matches("CODEX_BRAVE_")
+        .to_ascii_lowercase()
+}
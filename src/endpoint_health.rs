@@ -0,0 +1,73 @@
+use crate::constants::ENDPOINT_OVERRIDE_FAILURE_THRESHOLD;
+use crate::types::SearchType;
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+/// Consecutive failures of overridden search endpoints, judged only by their own answers.
+///
+/// Transport errors, non-JSON bodies, endpoint-level HTTP errors and JSON that is not a Brave
+/// search payload all count.
+#[derive(Debug, Default)]
+pub struct OverrideHealth {
+    endpoints: Mutex<HashMap<SearchType, EndpointRecord>>,
+}
+
+#[derive(Debug, Default)]
+struct EndpointRecord {
+    consecutive_failures: u32,
+    misconfigured: bool,
+}
+
+impl OverrideHealth {
+    /// Records a successful request and returns whether the endpoint had been flagged.
+    pub fn record_success(&self, search_type: SearchType) -> bool {
+        let mut endpoints = self
+            .endpoints
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        endpoints
+            .remove(&search_type)
+            .is_some_and(|record| record.misconfigured)
+    }
+
+    /// Records a failed request. Returns the number of consecutive failures when this one
+    /// reaches the threshold and flags the endpoint; a later success clears the flag.
+    pub fn record_failure(&self, search_type: SearchType) -> Option<u32> {
+        let mut endpoints = self
+            .endpoints
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let record = endpoints.entry(search_type).or_default();
+        record.consecutive_failures = record.consecutive_failures.saturating_add(1);
+        if record.consecutive_failures < ENDPOINT_OVERRIDE_FAILURE_THRESHOLD || record.misconfigured
+        {
+            return None;
+        }
+        record.misconfigured = true;
+        Some(record.consecutive_failures)
+    }
+
+    #[must_use]
+    pub fn is_misconfigured(&self, search_type: SearchType) -> bool {
+        let endpoints = self
+            .endpoints
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        endpoints
+            .get(&search_type)
+            .is_some_and(|record| record.misconfigured)
+    }
+
+    #[must_use]
+    pub fn misconfigured(&self) -> Vec<SearchType> {
+        [
+            SearchType::Web,
+            SearchType::News,
+            SearchType::Images,
+            SearchType::Videos,
+        ]
+        .into_iter()
+        .filter(|&search_type| self.is_misconfigured(search_type))
+        .collect()
+    }
+}
@@ -1,10 +1,10 @@
 use crate::constants::{
-    API_VERSION, ERROR_CANCELLED, ERROR_INTERNAL, ERROR_INVALID_ARGUMENT, ERROR_MISSING_API_KEY,
-    ERROR_PARSE, ERROR_UPSTREAM, PROVIDER_NAME,
+    API_VERSION, ERROR_API_KEY_REVOKED, ERROR_CANCELLED, ERROR_INTERNAL, ERROR_INVALID_ARGUMENT,
+    ERROR_MISSING_API_KEY, ERROR_PARSE, ERROR_RATE_LIMITED, ERROR_UPSTREAM, PROVIDER_NAME,
 };
 use crate::types::{ErrorMeta, ToolErrorEnvelope, ToolErrorInfo};
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum AppError {
     #[error("invalid argument: {message}")]
     InvalidArgument {
@@ -13,10 +13,24 @@ pub enum AppError {
     },
     #[error("missing API key; set BRAVE_SEARCH_API_KEY or BRAVE_API_KEY")]
     MissingApiKey,
+    /// Brave answered 401 after earlier calls with the same key had succeeded.
+    #[error("API key rejected by Brave after previously working")]
+    ApiKeyRevoked,
     #[error("request cancelled")]
     Cancelled,
     #[error("upstream error: {0}")]
     Upstream(String),
+    /// Brave answered with an error status; `brave_code` and `params` come from its error body.
+    #[error("upstream error: {message}")]
+    BraveApi {
+        status: u16,
+        message: String,
+        brave_code: Option<String>,
+        params: Vec<String>,
+        /// How long Brave asked callers to back off, from `Retry-After` or an exhausted
+        /// `X-RateLimit-*` window.
+        retry_after_ms: Option<u64>,
+    },
     #[error("parse error: {0}")]
     Parse(String),
     #[error("internal error: {0}")]
@@ -24,13 +38,43 @@ pub enum AppError {
 }
 
 impl AppError {
+    /// The same error with its message text passed through `map`, e.g. to redact secrets.
+    #[must_use]
+    pub fn map_message(self, map: impl Fn(&str) -> String) -> Self {
+        match self {
+            Self::InvalidArgument { message, details } => Self::InvalidArgument {
+                message: map(&message),
+                details,
+            },
+            Self::Upstream(message) => Self::Upstream(map(&message)),
+            Self::BraveApi {
+                status,
+                message,
+                brave_code,
+                params,
+                retry_after_ms,
+            } => Self::BraveApi {
+                status,
+                message: map(&message),
+                brave_code,
+                params,
+                retry_after_ms,
+            },
+            Self::Parse(message) => Self::Parse(map(&message)),
+            Self::Internal(message) => Self::Internal(map(&message)),
+            other => other,
+        }
+    }
+
     #[must_use]
     pub fn code(&self) -> &'static str {
         match self {
             Self::InvalidArgument { .. } => ERROR_INVALID_ARGUMENT,
             Self::MissingApiKey => ERROR_MISSING_API_KEY,
+            Self::ApiKeyRevoked => ERROR_API_KEY_REVOKED,
             Self::Cancelled => ERROR_CANCELLED,
-            Self::Upstream(_) => ERROR_UPSTREAM,
+            Self::BraveApi { status: 429, .. } => ERROR_RATE_LIMITED,
+            Self::Upstream(_) | Self::BraveApi { .. } => ERROR_UPSTREAM,
             Self::Parse(_) => ERROR_PARSE,
             Self::Internal(_) => ERROR_INTERNAL,
         }
@@ -40,6 +84,38 @@ impl AppError {
     pub fn details(&self) -> Option<serde_json::Value> {
         match self {
             Self::InvalidArgument { details, .. } => details.clone(),
+            Self::ApiKeyRevoked => Some(serde_json::json!({
+                "status": 401,
+                "remediation": [
+                    "Check the key's subscription and status at https://api-dashboard.search.brave.com",
+                    "Set a valid key in BRAVE_SEARCH_API_KEY or the CODEX_BRAVE_API_KEY_FILE file",
+                    "Call brave_reload_key (or send SIGHUP) so the new key is loaded",
+                ],
+            })),
+            Self::BraveApi {
+                status,
+                brave_code,
+                params,
+                retry_after_ms,
+                ..
+            } => {
+                let hint = brave_error_hint(*status, brave_code.as_deref(), params);
+                let mut details = serde_json::json!({ "status": status });
+                if let Some(code) = brave_code {
+                    details["brave_code"] = code.as_str().into();
+                }
+                if !params.is_empty() {
+                    details["params"] = params.clone().into();
+                }
+                if let Some(retry_after_ms) = retry_after_ms {
+                    details["retry_after_ms"] = (*retry_after_ms).into();
+                }
+                if let Some(hint) = hint {
+                    details["likely_cause"] = hint.likely_cause.into();
+                    details["suggested_fix"] = hint.suggested_fix.into();
+                }
+                Some(details)
+            }
             _ => None,
         }
     }
@@ -52,8 +128,9 @@ impl AppError {
                 "Missing BRAVE_SEARCH_API_KEY/BRAVE_API_KEY. Configure env vars for MCP launch."
                     .to_string()
             }
+            Self::ApiKeyRevoked => "Brave rejected the API key (HTTP 401) after earlier requests with it succeeded; it was likely revoked, rotated or its subscription expired. Further searches fail immediately until a valid key is configured and reloaded.".to_string(),
             Self::Cancelled => "Search cancelled.".to_string(),
-            Self::Upstream(message) => message.clone(),
+            Self::Upstream(message) | Self::BraveApi { message, .. } => message.clone(),
             Self::Parse(message) => message.clone(),
             Self::Internal(message) => message.clone(),
         }
@@ -95,3 +172,154 @@ impl AppError {
         }
     }
 }
+
+/// Remediation for a known Brave API failure, surfaced in `ToolErrorInfo.details`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ErrorHint {
+    pub likely_cause: &'static str,
+    pub suggested_fix: &'static str,
+}
+
+/// Brave error `code`s (or top-level `type`s) with a known remediation.
+const BRAVE_ERROR_HINTS: &[(&str, ErrorHint)] = &[
+    (
+        "SUBSCRIPTION_TOKEN_INVALID",
+        ErrorHint {
+            likely_cause: "The API key is not a valid Brave Search subscription token (typo, wrong key type, or deleted key).",
+            suggested_fix: "Copy the Search API key from https://api-dashboard.search.brave.com into BRAVE_SEARCH_API_KEY (or the CODEX_BRAVE_API_KEY_FILE file) and call brave_reload_key.",
+        },
+    ),
+    (
+        "RATE_LIMITED",
+        ErrorHint {
+            likely_cause: "Requests exceeded the plan's per-second rate limit.",
+            suggested_fix: "Retry after a short pause; lower CODEX_BRAVE_THROTTLE_RATE_PER_SEC / CODEX_BRAVE_THROTTLE_BURST or keep CODEX_BRAVE_THROTTLE_ADAPTIVE enabled.",
+        },
+    ),
+    (
+        "QUOTA_LIMITED",
+        ErrorHint {
+            likely_cause: "The plan's monthly request quota is used up.",
+            suggested_fix: "Wait for the quota to reset or upgrade the plan at https://api-dashboard.search.brave.com; cached results keep working meanwhile.",
+        },
+    ),
+    (
+        "OPTION_NOT_IN_PLAN",
+        ErrorHint {
+            likely_cause: "The request uses a feature the plan does not include (e.g. summarizer, extra snippets, goggles, local results).",
+            suggested_fix: "Drop the parameter that needs the higher plan or upgrade the subscription.",
+        },
+    ),
+    (
+        "RESOURCE_NOT_ALLOWED",
+        ErrorHint {
+            likely_cause: "The key is not subscribed to this endpoint (e.g. a Data for AI key calling another product).",
+            suggested_fix: "Subscribe the key to the Web Search API plan or use a key issued for it.",
+        },
+    ),
+];
+
+/// Hints for `VALIDATION` failures, keyed by the rejected Brave query parameter.
+const BRAVE_PARAM_HINTS: &[(&str, ErrorHint)] = &[
+    (
+        "country",
+        ErrorHint {
+            likely_cause: "Brave does not support the requested country code.",
+            suggested_fix: "Use a two-letter country from the supported list in brave_web_search_help, or omit country (ALL).",
+        },
+    ),
+    (
+        "search_lang",
+        ErrorHint {
+            likely_cause: "Brave does not support the requested search_language.",
+            suggested_fix: "Use a search_language from the supported list in brave_web_search_help (e.g. en, de, pt-br).",
+        },
+    ),
+    (
+        "ui_lang",
+        ErrorHint {
+            likely_cause: "Brave expects ui_language as language-REGION (e.g. en-US).",
+            suggested_fix: "Pass ui_language in language-REGION form or omit it.",
+        },
+    ),
+    (
+        "count",
+        ErrorHint {
+            likely_cause: "max_results is outside the range the endpoint accepts.",
+            suggested_fix: "Use max_results between 1 and 20.",
+        },
+    ),
+    (
+        "offset",
+        ErrorHint {
+            likely_cause: "offset is beyond the last page Brave serves (9 for web).",
+            suggested_fix: "Use an offset of 9 or less, or refine the query instead of paging further.",
+        },
+    ),
+    (
+        "freshness",
+        ErrorHint {
+            likely_cause: "freshness is neither pd/pw/pm/py nor a YYYY-MM-DDtoYYYY-MM-DD range.",
+            suggested_fix: "Use pd, pw, pm, py or a date range such as 2024-01-01to2024-06-30.",
+        },
+    ),
+    (
+        "safesearch",
+        ErrorHint {
+            likely_cause: "safe_search is not a value this endpoint accepts.",
+            suggested_fix: "Use off, moderate or strict (images accept only off and strict).",
+        },
+    ),
+    (
+        "goggles",
+        ErrorHint {
+            likely_cause: "Brave could not load or parse the goggle.",
+            suggested_fix: "Check that the goggle URL is public and valid, or drop goggles.",
+        },
+    ),
+];
+
+const GENERIC_VALIDATION_HINT: ErrorHint = ErrorHint {
+    likely_cause: "Brave rejected one of the request parameters.",
+    suggested_fix: "Check the parameter named in the message against brave_web_search_help and retry without it if unsure.",
+};
+
+const SERVER_ERROR_HINT: ErrorHint = ErrorHint {
+    likely_cause: "Brave Search had a transient server-side failure.",
+    suggested_fix: "Retry in a few seconds; check https://status.brave.com if it persists.",
+};
+
+/// Looks up the remediation for a Brave failure: the error code first, then the first rejected
+/// parameter of a validation error, then the HTTP status alone.
+#[must_use]
+pub fn brave_error_hint(
+    status: u16,
+    brave_code: Option<&str>,
+    params: &[String],
+) -> Option<ErrorHint> {
+    let lookup = |table: &[(&str, ErrorHint)], key: &str| {
+        table
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(key))
+            .map(|(_, hint)| *hint)
+    };
+
+    if let Some(hint) = brave_code.and_then(|code| lookup(BRAVE_ERROR_HINTS, code)) {
+        return Some(hint);
+    }
+    if status == 422 || brave_code.is_some_and(|code| code.eq_ignore_ascii_case("VALIDATION")) {
+        return Some(
+            params
+                .iter()
+                .find_map(|param| lookup(BRAVE_PARAM_HINTS, param))
+                .unwrap_or(GENERIC_VALIDATION_HINT),
+        );
+    }
+
+    match status {
+        401 => lookup(BRAVE_ERROR_HINTS, "SUBSCRIPTION_TOKEN_INVALID"),
+        429 => lookup(BRAVE_ERROR_HINTS, "RATE_LIMITED"),
+        500..=599 => Some(SERVER_ERROR_HINT),
+        _ => None,
+    }
+}
@@ -0,0 +1,112 @@
+//! Operational events worth surfacing to the host application.
+//!
+//! A nearly used-up monthly quota, repeated rate limiting, and a revoked API key that makes every
+//! search fail fast queue here until the MCP server sends them as `logging` notifications.
+
+use crate::constants::{
+    ERROR_API_KEY_REVOKED, ERROR_RATE_LIMITED, EVENT_QUOTA_LOW, MAX_PENDING_EVENTS,
+    QUOTA_LOW_PERCENT, REPEATED_RATE_LIMIT_THRESHOLD,
+};
+use crate::error::AppError;
+use crate::types::QuotaInfo;
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventLevel {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationalEvent {
+    pub level: EventLevel,
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// Each condition is reported once when it starts, not on every search while it lasts.
+#[derive(Debug, Default)]
+pub struct OperationalEvents {
+    state: Mutex<EventState>,
+}
+
+#[derive(Debug, Default)]
+struct EventState {
+    pending: VecDeque<OperationalEvent>,
+    consecutive_rate_limited: u32,
+    quota_low: bool,
+    key_revoked: bool,
+}
+
+impl EventState {
+    fn push(&mut self, level: EventLevel, code: &'static str, message: String) {
+        if self.pending.len() == MAX_PENDING_EVENTS {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(OperationalEvent {
+            level,
+            code,
+            message,
+        });
+    }
+}
+
+impl OperationalEvents {
+    /// Records a search Brave answered, with the quota window its headers reported.
+    pub fn observe_success(&self, quota: Option<QuotaInfo>) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.consecutive_rate_limited = 0;
+        state.key_revoked = false;
+        let Some(quota) = quota else {
+            return;
+        };
+        let low =
+            quota.remaining.saturating_mul(100) <= quota.limit.saturating_mul(QUOTA_LOW_PERCENT);
+        if low && !state.quota_low {
+            state.push(
+                EventLevel::Warning,
+                EVENT_QUOTA_LOW,
+                format!(
+                    "Brave monthly quota is nearly used up: {} of {} requests remaining.",
+                    quota.remaining, quota.limit
+                ),
+            );
+        }
+        state.quota_low = low;
+    }
+
+    /// Records a search that failed upstream.
+    pub fn observe_error(&self, error: &AppError) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        match error {
+            AppError::BraveApi { status: 429, .. } => {
+                state.consecutive_rate_limited = state.consecutive_rate_limited.saturating_add(1);
+                if state.consecutive_rate_limited == REPEATED_RATE_LIMIT_THRESHOLD {
+                    state.push(
+                        EventLevel::Warning,
+                        ERROR_RATE_LIMITED,
+                        format!(
+                            "Brave rate-limited {REPEATED_RATE_LIMIT_THRESHOLD} consecutive searches (HTTP 429) despite retries."
+                        ),
+                    );
+                }
+            }
+            AppError::ApiKeyRevoked if !state.key_revoked => {
+                state.key_revoked = true;
+                state.push(
+                    EventLevel::Error,
+                    ERROR_API_KEY_REVOKED,
+                    "Brave rejected the previously working API key; searches fail fast until a valid key is reloaded.".to_string(),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// Takes the events recorded since the last call, oldest first.
+    pub fn drain(&self) -> Vec<OperationalEvent> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.pending.drain(..).collect()
+    }
+}
@@ -0,0 +1,495 @@
+//! Post-filter expressions evaluated against parsed results, e.g.
+//! `domain endswith '.gov' and age < 30d`.
+
+use crate::constants::{MAX_FILTER_EXPR_DEPTH, MAX_FILTER_EXPR_LENGTH};
+use crate::error::AppError;
+use crate::types::{BraveSectionName, NormalizedResult, ParsedSection};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Title,
+    Url,
+    Domain,
+    Snippet,
+    Text,
+    Source,
+    Type,
+    Subtype,
+    Creator,
+    Section,
+    Published,
+    Age,
+    Live,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "title" => Self::Title,
+            "url" => Self::Url,
+            "domain" => Self::Domain,
+            "snippet" => Self::Snippet,
+            "text" => Self::Text,
+            "source" => Self::Source,
+            "type" => Self::Type,
+            "subtype" => Self::Subtype,
+            "creator" => Self::Creator,
+            "section" => Self::Section,
+            "published" => Self::Published,
+            "age" => Self::Age,
+            "live" => Self::Live,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+impl Op {
+    const fn is_text_only(self) -> bool {
+        matches!(self, Self::Contains | Self::StartsWith | Self::EndsWith)
+    }
+
+    fn compare<T: PartialOrd + ?Sized>(self, left: &T, right: &T) -> bool {
+        match self {
+            Self::Eq => left == right,
+            Self::Ne => left != right,
+            Self::Lt => left < right,
+            Self::Le => left <= right,
+            Self::Gt => left > right,
+            Self::Ge => left >= right,
+            Self::Contains | Self::StartsWith | Self::EndsWith => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Text(String),
+    Seconds(u64),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    And(Box<Self>, Box<Self>),
+    Or(Box<Self>, Box<Self>),
+    Not(Box<Self>),
+    Compare { field: Field, op: Op, value: Value },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Text(String),
+    Number(u64, Option<char>),
+    Op(Op),
+    LParen,
+    RParen,
+}
+
+/// A parsed `filter` expression.
+#[derive(Debug, Clone)]
+pub struct FilterExpr {
+    root: Node,
+}
+
+impl FilterExpr {
+    pub fn parse(input: &str) -> Result<Self, AppError> {
+        if input.chars().count() > MAX_FILTER_EXPR_LENGTH {
+            return Err(filter_error(
+                format!("expression exceeds {MAX_FILTER_EXPR_LENGTH} characters"),
+                0,
+            ));
+        }
+
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err(filter_error("expression is empty", 0));
+        }
+
+        let mut parser = Parser {
+            tokens,
+            index: 0,
+            end: input.len(),
+        };
+        let root = parser.parse_or(0)?;
+        if let Some((_, position)) = parser.tokens.get(parser.index) {
+            return Err(filter_error("unexpected trailing input", *position));
+        }
+        Ok(Self { root })
+    }
+
+    /// `now_unix_secs` anchors `age` comparisons; results without a parseable `published`
+    /// date never satisfy them.
+    #[must_use]
+    pub fn matches(
+        &self,
+        section: BraveSectionName,
+        result: &NormalizedResult,
+        now_unix_secs: u64,
+    ) -> bool {
+        evaluate(&self.root, section, result, now_unix_secs)
+    }
+
+    /// Drops non-matching results (and sections left empty); returns how many were removed.
+    pub fn retain_matching(&self, sections: &mut Vec<ParsedSection>, now_unix_secs: u64) -> usize {
+        let mut removed = 0usize;
+        for section in sections.iter_mut() {
+            let before = section.results.len();
+            let key = section.key;
+            section.results.retain(|result| {
+                self.matches(result.origin_section.unwrap_or(key), result, now_unix_secs)
+            });
+            removed += before - section.results.len();
+        }
+        sections.retain(|section| !section.results.is_empty());
+        removed
+    }
+}
+
+fn filter_error(message: impl Into<String>, position: usize) -> AppError {
+    let message = message.into();
+    AppError::invalid_argument_with_details(
+        format!("filter: {message} (at position {position})"),
+        serde_json::json!({"field": "filter", "position": position}),
+    )
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, AppError> {
+    let chars = input.char_indices().collect::<Vec<(usize, char)>>();
+    let mut tokens = Vec::new();
+    let mut index = 0usize;
+
+    while let Some(&(position, ch)) = chars.get(index) {
+        if ch.is_whitespace() {
+            index += 1;
+            continue;
+        }
+
+        let next = chars.get(index + 1).map(|(_, next)| *next);
+        let (token, consumed) = match ch {
+            '(' => (Token::LParen, 1),
+            ')' => (Token::RParen, 1),
+            '=' if next == Some('=') => (Token::Op(Op::Eq), 2),
+            '=' => (Token::Op(Op::Eq), 1),
+            '!' if next == Some('=') => (Token::Op(Op::Ne), 2),
+            '<' if next == Some('=') => (Token::Op(Op::Le), 2),
+            '<' => (Token::Op(Op::Lt), 1),
+            '>' if next == Some('=') => (Token::Op(Op::Ge), 2),
+            '>' => (Token::Op(Op::Gt), 1),
+            '\'' | '"' => {
+                let mut value = String::new();
+                let mut cursor = index + 1;
+                loop {
+                    match chars.get(cursor) {
+                        Some(&(_, c)) if c == ch => break,
+                        Some(&(_, c)) => value.push(c),
+                        None => return Err(filter_error("unterminated string", position)),
+                    }
+                    cursor += 1;
+                }
+                (Token::Text(value), cursor + 1 - index)
+            }
+            c if c.is_ascii_digit() => {
+                let mut cursor = index;
+                let mut digits = String::new();
+                while let Some(&(_, c)) = chars.get(cursor)
+                    && c.is_ascii_digit()
+                {
+                    digits.push(c);
+                    cursor += 1;
+                }
+                let number = digits
+                    .parse::<u64>()
+                    .map_err(|_| filter_error("number is too large", position))?;
+                let unit = chars
+                    .get(cursor)
+                    .map(|(_, c)| *c)
+                    .filter(|c| c.is_ascii_alphabetic());
+                if unit.is_some() {
+                    cursor += 1;
+                }
+                (Token::Number(number, unit), cursor - index)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut cursor = index;
+                let mut word = String::new();
+                while let Some(&(_, c)) = chars.get(cursor)
+                    && (c.is_ascii_alphanumeric() || c == '_')
+                {
+                    word.push(c.to_ascii_lowercase());
+                    cursor += 1;
+                }
+                let token = match word.as_str() {
+                    "contains" => Token::Op(Op::Contains),
+                    "startswith" => Token::Op(Op::StartsWith),
+                    "endswith" => Token::Op(Op::EndsWith),
+                    _ => Token::Ident(word),
+                };
+                (token, cursor - index)
+            }
+            other => {
+                return Err(filter_error(
+                    format!("unexpected character '{other}'"),
+                    position,
+                ));
+            }
+        };
+
+        tokens.push((token, position));
+        index += consumed;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|(token, _)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .map_or(self.end, |(_, position)| *position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).map(|(token, _)| token.clone());
+        self.index += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(word)) if word == keyword)
+    }
+
+    fn parse_or(&mut self, depth: usize) -> Result<Node, AppError> {
+        let mut node = self.parse_and(depth)?;
+        while self.peek_keyword("or") {
+            self.index += 1;
+            node = Node::Or(Box::new(node), Box::new(self.parse_and(depth)?));
+        }
+        Ok(node)
+    }
+
+    fn parse_and(&mut self, depth: usize) -> Result<Node, AppError> {
+        let mut node = self.parse_unary(depth)?;
+        while self.peek_keyword("and") {
+            self.index += 1;
+            node = Node::And(Box::new(node), Box::new(self.parse_unary(depth)?));
+        }
+        Ok(node)
+    }
+
+    fn parse_unary(&mut self, depth: usize) -> Result<Node, AppError> {
+        if depth >= MAX_FILTER_EXPR_DEPTH {
+            return Err(filter_error(
+                "expression is nested too deeply",
+                self.position(),
+            ));
+        }
+        if self.peek_keyword("not") {
+            self.index += 1;
+            return Ok(Node::Not(Box::new(self.parse_unary(depth + 1)?)));
+        }
+        if self.peek() == Some(&Token::LParen) {
+            self.index += 1;
+            let node = self.parse_or(depth + 1)?;
+            if self.peek() != Some(&Token::RParen) {
+                return Err(filter_error("expected ')'", self.position()));
+            }
+            self.index += 1;
+            return Ok(node);
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> Result<Node, AppError> {
+        let field_position = self.position();
+        let field = match self.next() {
+            Some(Token::Ident(name)) => Field::parse(&name)
+                .ok_or_else(|| filter_error(format!("unknown field '{name}'"), field_position))?,
+            _ => return Err(filter_error("expected a field name", field_position)),
+        };
+
+        let Some(Token::Op(op)) = self.peek().cloned() else {
+            // A bare boolean field reads as `field == true`.
+            if field == Field::Live {
+                return Ok(Node::Compare {
+                    field,
+                    op: Op::Eq,
+                    value: Value::Bool(true),
+                });
+            }
+            return Err(filter_error("expected an operator", self.position()));
+        };
+        self.index += 1;
+
+        let value_position = self.position();
+        let value = match self.next() {
+            Some(Token::Text(text)) => Value::Text(text.to_lowercase()),
+            Some(Token::Number(number, unit)) => {
+                let Some(seconds) = unit.and_then(duration_unit_secs) else {
+                    return Err(filter_error(
+                        "numbers need a duration unit (s, m, h, d, w, y)",
+                        value_position,
+                    ));
+                };
+                Value::Seconds(number.saturating_mul(seconds))
+            }
+            Some(Token::Ident(word)) if word == "true" => Value::Bool(true),
+            Some(Token::Ident(word)) if word == "false" => Value::Bool(false),
+            _ => return Err(filter_error("expected a value", value_position)),
+        };
+
+        let valid = match (field, &value) {
+            (Field::Age, Value::Seconds(_)) => !op.is_text_only(),
+            (Field::Live, Value::Bool(_)) => matches!(op, Op::Eq | Op::Ne),
+            (Field::Age | Field::Live, _) | (_, Value::Seconds(_) | Value::Bool(_)) => false,
+            (_, Value::Text(_)) => true,
+        };
+        if !valid {
+            return Err(filter_error(
+                "operator or value does not fit the field",
+                value_position,
+            ));
+        }
+
+        Ok(Node::Compare { field, op, value })
+    }
+}
+
+fn duration_unit_secs(unit: char) -> Option<u64> {
+    Some(match unit.to_ascii_lowercase() {
+        's' => 1,
+        'm' => 60,
+        'h' => 3_600,
+        'd' => 86_400,
+        'w' => 7 * 86_400,
+        'y' => 365 * 86_400,
+        _ => return None,
+    })
+}
+
+fn evaluate(
+    node: &Node,
+    section: BraveSectionName,
+    result: &NormalizedResult,
+    now_unix_secs: u64,
+) -> bool {
+    match node {
+        Node::And(left, right) => {
+            evaluate(left, section, result, now_unix_secs)
+                && evaluate(right, section, result, now_unix_secs)
+        }
+        Node::Or(left, right) => {
+            evaluate(left, section, result, now_unix_secs)
+                || evaluate(right, section, result, now_unix_secs)
+        }
+        Node::Not(inner) => !evaluate(inner, section, result, now_unix_secs),
+        Node::Compare { field, op, value } => match value {
+            Value::Bool(expected) => op.compare(&result.is_live, expected),
+            Value::Seconds(limit) => result
+                .published
+                .as_deref()
+                .and_then(parse_unix_secs)
+                .is_some_and(|published| {
+                    op.compare(&now_unix_secs.saturating_sub(published), limit)
+                }),
+            Value::Text(expected) => text_field(*field, section, result)
+                .is_some_and(|actual| compare_text(*op, &actual.to_lowercase(), expected)),
+        },
+    }
+}
+
+fn compare_text(op: Op, actual: &str, expected: &str) -> bool {
+    match op {
+        Op::Contains => actual.contains(expected),
+        Op::StartsWith => actual.starts_with(expected),
+        Op::EndsWith => actual.ends_with(expected),
+        _ => op.compare(actual, expected),
+    }
+}
+
+fn text_field(
+    field: Field,
+    section: BraveSectionName,
+    result: &NormalizedResult,
+) -> Option<String> {
+    match field {
+        Field::Title => Some(result.title.clone()),
+        Field::Url => Some(result.url.clone()),
+        Field::Domain => url::Url::parse(&result.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string)),
+        Field::Snippet => Some(result.snippet.clone()),
+        Field::Text => Some(
+            std::iter::once(result.title.as_str())
+                .chain(std::iter::once(result.snippet.as_str()))
+                .chain(result.extra_snippets.iter().map(String::as_str))
+                .collect::<Vec<&str>>()
+                .join(" "),
+        ),
+        Field::Source => result.source.clone(),
+        Field::Type => result.item_type.clone(),
+        Field::Subtype => result.subtype.clone(),
+        Field::Creator => result.creator.clone(),
+        Field::Section => Some(section.as_str().to_string()),
+        Field::Published => result.published.clone(),
+        Field::Age | Field::Live => None,
+    }
+}
+
+/// Parses the `YYYY-MM-DD[THH:MM[:SS]]` prefix of a `page_age` timestamp as UTC.
+fn parse_unix_secs(published: &str) -> Option<u64> {
+    let date = published.get(..10)?;
+    let mut parts = date.split('-');
+    let year = parts.next()?.parse::<i64>().ok()?;
+    let month = parts.next()?.parse::<i64>().ok()?;
+    let day = parts.next()?.parse::<i64>().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut seconds = 0i64;
+    if let Some(time) = published.get(11..) {
+        let mut fields = time
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|part| !part.is_empty())
+            .take(3)
+            .map(|part| part.parse::<i64>().ok());
+        let hours = fields.next().flatten().unwrap_or(0);
+        let minutes = fields.next().flatten().unwrap_or(0);
+        let secs = fields.next().flatten().unwrap_or(0);
+        seconds = hours * 3_600 + minutes * 60 + secs;
+    }
+
+    // Days since the Unix epoch for a proleptic Gregorian date (H. Hinnant's algorithm).
+    let shifted_year = if month <= 2 { year - 1 } else { year };
+    let era = shifted_year.div_euclid(400);
+    let year_of_era = shifted_year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    u64::try_from(days * 86_400 + seconds).ok()
+}
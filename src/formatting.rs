@@ -1,5 +1,293 @@
-use crate::constants::WARNING_OUTPUT_TRUNCATED;
-use crate::types::{NormalizedResult, SearchResponse, SearchResultItem, WarningEntry};
+use crate::constants::{
+    APPROX_CHARS_PER_TOKEN, ESTIMATED_ENVELOPE_BYTES, ESTIMATED_ENVELOPE_LINES,
+    ESTIMATED_EXTRA_SNIPPETS_BYTES, ESTIMATED_EXTRA_SNIPPETS_LINES, ESTIMATED_RESULT_BYTES,
+    ESTIMATED_RESULT_LINES, INJECTION_ENCODED_MIN_CHARS, INJECTION_OVERRIDE_PHRASES,
+    INJECTION_TOOL_CALL_MARKERS, NEWS_TOPIC_KEYWORDS, NEWS_TOPIC_OTHER,
+    READING_SNIPPET_BASELINE_WORDS, READING_WORDS_PER_MINUTE, SECTION_SUMMARY_STOPWORDS,
+    TOKEN_BUDGET_SNIPPET_CHARS,
+};
+use crate::normalization::{
+    api_version_from_str, clamp_offset, dedup_key, normalize_host_for_dedup,
+    normalize_published_date, normalize_url_for_dedup, strip_tracking_params,
+};
+use crate::render::{JsonRenderer, ResponseRenderer};
+use crate::types::{
+    ApiVersion, BraveSectionName, CompareOverlapItem, CompareResultItem, DedupStrategy,
+    DuplicateDecision, FieldDiff, FlatResultItem, InjectionRisk, InjectionRiskLevel,
+    InjectionSignal, MergedResultItem, NewsTopic, NormalizedResult, OutputSize, PaginationV2,
+    ParsedSection, ResultDiversity, ResultExplanation, SearchMetaV2, SearchResponse,
+    SearchResponseV2, SearchResultItem, SearchSection, SearchType, TimingsV2, TopicFacet,
+    WarningCode, WarningEntry, WebResultFilter,
+};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+pub trait ResponseBuilder {
+    fn build(&self, response: &SearchResponse) -> Result<Value, serde_json::Error>;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct V1ResponseBuilder;
+
+#[derive(Debug, Clone, Copy)]
+pub struct V2ResponseBuilder;
+
+impl ResponseBuilder for V1ResponseBuilder {
+    fn build(&self, response: &SearchResponse) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(response)
+    }
+}
+
+impl ResponseBuilder for V2ResponseBuilder {
+    fn build(&self, response: &SearchResponse) -> Result<Value, serde_json::Error> {
+        let merged = response.results.iter().map(|result| FlatResultItem {
+            section: result.origin_section,
+            rank: result.section_rank,
+            item: result.item.clone(),
+        });
+        let results = response
+            .sections
+            .iter()
+            .flat_map(|section| {
+                section
+                    .results
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| FlatResultItem {
+                        section: section.key,
+                        rank: index + 1,
+                        item: item.clone(),
+                    })
+            })
+            .chain(merged)
+            .collect::<Vec<FlatResultItem>>();
+
+        let meta = &response.meta;
+        // Auto-pagination may already have consumed several pages past `offset`.
+        let next_offset = meta.offset + meta.pages_fetched.unwrap_or(1);
+        let next_offset = (meta.has_more
+            && clamp_offset(Some(next_offset), meta.search_type) == next_offset)
+            .then_some(next_offset);
+
+        serde_json::to_value(SearchResponseV2 {
+            api_version: ApiVersion::V2.as_str().to_string(),
+            summary: response.summary.clone(),
+            results,
+            section_summaries: response.section_summaries.clone(),
+            topics: response.topics.clone(),
+            pagination: PaginationV2 {
+                offset: meta.offset,
+                requested: meta.requested,
+                returned: meta.returned,
+                has_more: meta.has_more,
+                next_offset,
+                next_cursor: response.next_cursor.clone(),
+            },
+            timings: TimingsV2 {
+                total_ms: meta.duration_ms,
+                upstream_ms: meta.upstream_ms,
+            },
+            meta: SearchMetaV2 {
+                query: meta.query.clone(),
+                sent_query: meta.sent_query.clone(),
+                search_type: meta.search_type,
+                provider: meta.provider.clone(),
+                server_version: meta.server_version.clone(),
+                trace_id: meta.trace_id.clone(),
+                endpoint_host: meta.endpoint_host.clone(),
+                endpoint_override: meta.endpoint_override,
+                warnings_count: meta.warnings_count,
+                effective_params: meta.effective_params.clone(),
+                cache_hit: meta.cache_hit,
+                cache_age_secs: meta.cache_age_secs,
+                throttle_tokens: meta.throttle_tokens,
+                rate_limit: meta.rate_limit,
+                dedup: meta.dedup,
+                rerank: meta.rerank,
+                suppressed_repeats: meta.suppressed_repeats,
+                diversity: meta.diversity.clone(),
+                pages_fetched: meta.pages_fetched,
+                exceeds_limits: meta.exceeds_limits,
+                measured_size: meta.measured_size,
+                estimated_tokens: meta.estimated_tokens,
+            },
+            warnings: response.warnings.clone(),
+            notices: response.notices.clone(),
+            explanations: response.explanations.clone(),
+            debug_data: response.debug_data.clone(),
+        })
+    }
+}
+
+#[must_use]
+pub fn response_builder_for(api_version: ApiVersion) -> &'static dyn ResponseBuilder {
+    match api_version {
+        ApiVersion::V1 => &V1ResponseBuilder,
+        ApiVersion::V2 => &V2ResponseBuilder,
+    }
+}
+
+pub fn build_response_payload(response: &SearchResponse) -> Result<Value, serde_json::Error> {
+    let api_version = api_version_from_str(&response.api_version).unwrap_or_default();
+    response_builder_for(api_version).build(response)
+}
+
+/// Field-level differences between two JSON documents, at most `max_diffs` of them; the flag
+/// reports whether more were left out. Paths listed in `ignored` are skipped with their children.
+#[must_use]
+pub fn diff_json(
+    original: &Value,
+    replayed: &Value,
+    ignored: &[&str],
+    max_diffs: usize,
+) -> (Vec<FieldDiff>, bool) {
+    let mut differ = JsonDiff {
+        ignored,
+        max_diffs,
+        diffs: Vec::new(),
+        truncated: false,
+    };
+    differ.visit("", Some(original), Some(replayed));
+    (differ.diffs, differ.truncated)
+}
+
+struct JsonDiff<'a> {
+    ignored: &'a [&'a str],
+    max_diffs: usize,
+    diffs: Vec<FieldDiff>,
+    truncated: bool,
+}
+
+impl JsonDiff<'_> {
+    fn visit(&mut self, path: &str, original: Option<&Value>, replayed: Option<&Value>) {
+        if original == replayed || self.ignored.contains(&path) {
+            return;
+        }
+        match (original, replayed) {
+            (Some(Value::Object(original)), Some(Value::Object(replayed))) => {
+                let keys = original
+                    .keys()
+                    .chain(replayed.keys())
+                    .collect::<BTreeSet<&String>>();
+                for key in keys {
+                    let child = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{path}.{key}")
+                    };
+                    self.visit(&child, original.get(key), replayed.get(key));
+                }
+            }
+            (Some(Value::Array(original)), Some(Value::Array(replayed))) => {
+                for index in 0..original.len().max(replayed.len()) {
+                    self.visit(
+                        &format!("{path}[{index}]"),
+                        original.get(index),
+                        replayed.get(index),
+                    );
+                }
+            }
+            _ if self.diffs.len() >= self.max_diffs => self.truncated = true,
+            _ => self.diffs.push(FieldDiff {
+                path: path.to_string(),
+                original: original.cloned(),
+                replayed: replayed.cloned(),
+            }),
+        }
+    }
+}
+
+/// Groups two merged result lists by normalized URL into results found by both runs (ordered by
+/// their rank in `a`) and results only one run returned.
+#[must_use]
+pub fn compare_result_sets(
+    a: &[MergedResultItem],
+    b: &[MergedResultItem],
+) -> (
+    Vec<CompareOverlapItem>,
+    Vec<CompareResultItem>,
+    Vec<CompareResultItem>,
+) {
+    let rank_by_url = |results: &[MergedResultItem]| {
+        let mut ranks = HashMap::<String, usize>::new();
+        for result in results {
+            ranks
+                .entry(normalize_url_for_dedup(&result.item.url))
+                .or_insert(result.rank);
+        }
+        ranks
+    };
+    let a_ranks = rank_by_url(a);
+    let b_ranks = rank_by_url(b);
+
+    let mut seen = HashSet::new();
+    let mut overlap = Vec::new();
+    let mut only_in_a = Vec::new();
+    for result in a {
+        let key = normalize_url_for_dedup(&result.item.url);
+        if !seen.insert(key.clone()) {
+            continue;
+        }
+        match b_ranks.get(&key) {
+            Some(rank_b) => overlap.push(CompareOverlapItem {
+                title: result.item.title.clone(),
+                url: result.item.url.clone(),
+                rank_a: result.rank,
+                rank_b: *rank_b,
+            }),
+            None => only_in_a.push(compare_item(result)),
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let only_in_b = b
+        .iter()
+        .filter(|result| {
+            let key = normalize_url_for_dedup(&result.item.url);
+            !a_ranks.contains_key(&key) && seen.insert(key)
+        })
+        .map(compare_item)
+        .collect();
+
+    (overlap, only_in_a, only_in_b)
+}
+
+fn compare_item(result: &MergedResultItem) -> CompareResultItem {
+    CompareResultItem {
+        title: result.item.title.clone(),
+        url: result.item.url.clone(),
+        rank: result.rank,
+        section: result.origin_section,
+    }
+}
+
+#[must_use]
+pub fn merge_section_results(sections: Vec<SearchSection>) -> Vec<MergedResultItem> {
+    let mut queues = sections
+        .into_iter()
+        .map(|section| (section.key, section.results.into_iter().enumerate()))
+        .collect::<Vec<_>>();
+
+    let mut merged = Vec::<MergedResultItem>::new();
+    loop {
+        let before = merged.len();
+        for (key, results) in &mut queues {
+            if let Some((index, mut item)) = results.next() {
+                merged.push(MergedResultItem {
+                    rank: merged.len() + 1,
+                    origin_section: item.origin_section.take().unwrap_or(*key),
+                    section_rank: index + 1,
+                    item,
+                });
+            }
+        }
+        if merged.len() == before {
+            break;
+        }
+    }
+    merged
+}
 
 #[derive(Debug, Clone, Copy)]
 struct TruncationContext {
@@ -9,6 +297,12 @@ struct TruncationContext {
     max_bytes: usize,
     removed_results: usize,
     omitted_debug_data: bool,
+    omitted_section_summaries: bool,
+    omitted_topics: bool,
+    omitted_effective_params: bool,
+    omitted_diversity: bool,
+    omitted_notices: bool,
+    omitted_cache_info: bool,
     collapsed_warnings: bool,
     condensed_summary: bool,
     condensed_query: bool,
@@ -34,6 +328,195 @@ pub fn build_summary(
     summary
 }
 
+#[must_use]
+pub fn build_section_summary(section: &SearchSection) -> String {
+    let count = section.results.len();
+    let mut summary = format!(
+        "{}: {count} result{}",
+        section.label,
+        if count == 1 { "" } else { "s" }
+    );
+
+    let sources = section
+        .results
+        .iter()
+        .filter_map(|result| result.source.as_deref())
+        .collect::<HashSet<&str>>()
+        .len();
+    if sources > 0 {
+        let _ = write!(
+            summary,
+            " from {sources} source{}",
+            if sources == 1 { "" } else { "s" }
+        );
+    }
+
+    if let Some(latest) = section
+        .results
+        .iter()
+        .filter_map(|result| result.published.as_deref())
+        .max()
+    {
+        let _ = write!(
+            summary,
+            ", latest published {}",
+            latest.get(..10).unwrap_or(latest)
+        );
+    }
+
+    if let Some(term) = dominant_title_term(section) {
+        let _ = write!(summary, ", mostly about \"{term}\"");
+    }
+
+    summary.push('.');
+    summary
+}
+
+fn dominant_title_term(section: &SearchSection) -> Option<String> {
+    let mut counts = Vec::<(String, usize)>::new();
+    for result in &section.results {
+        let terms = result
+            .title
+            .split(|character: char| !character.is_alphanumeric())
+            .map(str::to_lowercase)
+            .filter(|term| {
+                term.chars().count() >= 4 && !SECTION_SUMMARY_STOPWORDS.contains(&term.as_str())
+            })
+            .collect::<HashSet<String>>();
+        for term in terms {
+            match counts.iter_mut().find(|(existing, _)| *existing == term) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((term, 1)),
+            }
+        }
+    }
+
+    let best = counts.iter().map(|(_, count)| *count).max()?;
+    if best < 2 {
+        return None;
+    }
+    counts
+        .into_iter()
+        .find(|(_, count)| *count == best)
+        .map(|(term, _)| term)
+}
+
+/// Topic whose keywords occur most often in the result's title, snippet, source and URL path.
+#[must_use]
+pub fn classify_news_topic(item: &SearchResultItem) -> Option<NewsTopic> {
+    let path = url::Url::parse(&item.url)
+        .map(|url| url.path().to_string())
+        .unwrap_or_default();
+    let words = [
+        item.title.as_str(),
+        item.snippet.as_str(),
+        item.source.as_deref().unwrap_or_default(),
+        path.as_str(),
+    ]
+    .iter()
+    .flat_map(|text| text.split(|character: char| !character.is_alphanumeric()))
+    .filter(|word| !word.is_empty())
+    .map(str::to_lowercase)
+    .collect::<Vec<String>>();
+
+    let mut best = None::<(NewsTopic, usize)>;
+    for (topic, keywords) in NEWS_TOPIC_KEYWORDS {
+        let hits = words
+            .iter()
+            .filter(|word| keywords.contains(&word.as_str()))
+            .count();
+        if hits > 0 && best.is_none_or(|(_, best_hits)| hits > best_hits) {
+            best = Some((*topic, hits));
+        }
+    }
+    best.map(|(topic, _)| topic)
+}
+
+/// Counts returned results per classified topic, most frequent first, with unmatched results
+/// under `other`.
+#[must_use]
+pub fn build_topic_facets(sections: &[SearchSection]) -> Vec<TopicFacet> {
+    let mut facets = Vec::<TopicFacet>::new();
+    for item in sections.iter().flat_map(|section| &section.results) {
+        let topic = classify_news_topic(item).map_or(NEWS_TOPIC_OTHER, NewsTopic::as_str);
+        match facets.iter_mut().find(|facet| facet.topic == topic) {
+            Some(facet) => facet.count += 1,
+            None => facets.push(TopicFacet {
+                topic: topic.to_string(),
+                count: 1,
+            }),
+        }
+    }
+    facets.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| (a.topic == NEWS_TOPIC_OTHER).cmp(&(b.topic == NEWS_TOPIC_OTHER)))
+    });
+    facets
+}
+
+#[must_use]
+pub fn build_explanations(
+    sections: &[ParsedSection],
+    duplicates: &[DuplicateDecision],
+    search_type: SearchType,
+    result_filter_values: &[WebResultFilter],
+) -> Vec<ResultExplanation> {
+    let mut explanations = Vec::<ResultExplanation>::new();
+    for section in sections {
+        let inclusion = if search_type != SearchType::Web {
+            format!(
+                "'{}' is the only section for search_type={}.",
+                section.key.as_str(),
+                search_type.as_str()
+            )
+        } else if result_filter_values.is_empty() {
+            "'web' is the default section when result_filter is not set.".to_string()
+        } else {
+            format!(
+                "Section '{}' requested via result_filter.",
+                section.key.as_str()
+            )
+        };
+
+        for (index, result) in section.results.iter().enumerate() {
+            let position = index + 1;
+            let mut reasons = vec![
+                format!(
+                    "Brave ranked this #{} in its {} results.",
+                    result.provider_rank,
+                    section.key.as_str()
+                ),
+                inclusion.clone(),
+            ];
+            if position != result.provider_rank {
+                reasons.push(format!(
+                    "Moved up from #{} to #{position} because earlier provider results were removed as duplicates or invalid.",
+                    result.provider_rank
+                ));
+            }
+            for duplicate in duplicates.iter().filter(|duplicate| {
+                duplicate.kept_section == section.key && duplicate.kept_rank == result.provider_rank
+            }) {
+                reasons.push(format!(
+                    "Kept over duplicate {} #{} ({}).",
+                    duplicate.removed_section.as_str(),
+                    duplicate.removed_rank,
+                    duplicate.removed_url
+                ));
+            }
+            explanations.push(ResultExplanation {
+                section: section.key,
+                position,
+                provider_rank: result.provider_rank,
+                url: result.url.clone(),
+                reasons,
+            });
+        }
+    }
+    explanations
+}
+
 #[must_use]
 pub fn to_result_item(result: NormalizedResult) -> SearchResultItem {
     let mut metadata_lines = Vec::<String>::new();
@@ -59,12 +542,21 @@ pub fn to_result_item(result: NormalizedResult) -> SearchResultItem {
     if let Some(creator) = &result.creator {
         metadata_lines.push(format!("Creator: {creator}"));
     }
+    if let Some(publisher) = &result.publisher {
+        metadata_lines.push(format!("Publisher: {publisher}"));
+    }
+    if let Some(views) = result.views {
+        metadata_lines.push(format!("Views: {views}"));
+    }
     if let Some(location) = &result.location {
         metadata_lines.push(format!("Location: {location}"));
     }
     if result.is_live {
         metadata_lines.push("Live".to_string());
     }
+    if result.breaking {
+        metadata_lines.push("Breaking".to_string());
+    }
 
     SearchResultItem {
         title: result.title,
@@ -79,58 +571,333 @@ pub fn to_result_item(result: NormalizedResult) -> SearchResultItem {
         subtype: result.subtype,
         duration: result.duration,
         creator: result.creator,
+        publisher: result.publisher,
+        views: result.views,
+        embeddable: result.embeddable,
         location: result.location,
         is_live: result.is_live.then_some(true),
+        breaking: result.breaking.then_some(true),
+        hostname: result.hostname,
+        thumbnail: result.thumbnail,
+        local: result.local,
+        image: result.image,
+        origin_section: result.origin_section,
+        word_count: None,
+        estimated_reading_minutes: None,
+        injection_risk: None,
     }
 }
 
-pub fn enforce_output_limits(response: &mut SearchResponse, max_lines: usize, max_bytes: usize) {
-    let (initial_lines, initial_bytes) = serialized_shape(response);
-
-    if initial_lines <= max_lines && initial_bytes <= max_bytes {
-        return;
+/// Cuts titles, snippets and extra snippets longer than the caps, marking the cut with `…`, so a
+/// single pathological result cannot take the whole output budget. Returns the number of fields cut.
+pub fn cap_result_fields(
+    sections: &mut [ParsedSection],
+    max_title_chars: usize,
+    max_snippet_chars: usize,
+) -> usize {
+    let mut capped = 0;
+    for result in sections.iter_mut().flat_map(|section| &mut section.results) {
+        capped += usize::from(cap_field(&mut result.title, max_title_chars));
+        capped += usize::from(cap_field(&mut result.snippet, max_snippet_chars));
+        for extra in &mut result.extra_snippets {
+            capped += usize::from(cap_field(extra, max_snippet_chars));
+        }
     }
+    capped
+}
 
-    let mut removed_results = 0usize;
-    while !within_limits(response, max_lines, max_bytes) {
-        let mut removed_any = false;
-        for section in response.sections.iter_mut().rev() {
-            if section.results.pop().is_some() {
-                removed_results += 1;
-                removed_any = true;
+/// Appends the results of a follow-up page to the matching sections, skipping results `dedup`
+/// considers already present and capping every section at `limit`. Returns the number added.
+pub fn append_page_results(
+    sections: &mut Vec<ParsedSection>,
+    page: Vec<ParsedSection>,
+    dedup: DedupStrategy,
+    limit: usize,
+) -> usize {
+    let mut seen = sections
+        .iter()
+        .flat_map(|section| &section.results)
+        .filter_map(|result| dedup_key(dedup, &result.url, &result.title))
+        .collect::<HashSet<String>>();
+    let mut added = 0;
+    for page_section in page {
+        let index = sections
+            .iter()
+            .position(|section| section.key == page_section.key)
+            .unwrap_or_else(|| {
+                sections.push(ParsedSection {
+                    results: Vec::new(),
+                    ..page_section.clone()
+                });
+                sections.len() - 1
+            });
+        let section = &mut sections[index];
+        section.section_limit_reached = page_section.section_limit_reached;
+        for result in page_section.results {
+            if section.results.len() >= limit {
                 break;
             }
+            if let Some(key) = dedup_key(dedup, &result.url, &result.title)
+                && !seen.insert(key)
+            {
+                continue;
+            }
+            section.results.push(result);
+            added += 1;
         }
+    }
+    added
+}
 
-        if !removed_any {
-            break;
+/// Removes tracking params from result URLs. Returns the number of URLs changed.
+pub fn clean_result_urls(sections: &mut [ParsedSection]) -> usize {
+    let mut cleaned = 0;
+    for result in sections.iter_mut().flat_map(|section| &mut section.results) {
+        let clean = strip_tracking_params(&result.url);
+        if clean != result.url {
+            result.url = clean;
+            cleaned += 1;
         }
     }
+    cleaned
+}
+
+/// Keeps only breaking items among news results (including news placed in a `mixed` section),
+/// dropping sections this leaves empty. Returns the number of results removed.
+pub fn retain_breaking_news(sections: &mut Vec<ParsedSection>) -> usize {
+    let mut removed = 0usize;
+    sections.retain_mut(|section| {
+        let before = section.results.len();
+        let key = section.key;
+        section.results.retain(|result| {
+            result.breaking || result.origin_section.unwrap_or(key) != BraveSectionName::News
+        });
+        removed += before - section.results.len();
+        before == section.results.len() || !section.results.is_empty()
+    });
+    removed
+}
+
+/// Keeps results whose `published` date lies within the inclusive `after`/`before` bounds
+/// (`YYYY-MM-DD`), dropping undated results and sections this leaves empty. Returns the number
+/// of results removed.
+pub fn retain_published_between(
+    sections: &mut Vec<ParsedSection>,
+    after: Option<&str>,
+    before: Option<&str>,
+) -> usize {
+    let mut removed = 0usize;
+    sections.retain_mut(|section| {
+        let count = section.results.len();
+        section.results.retain(|result| {
+            normalize_published_date(result.published.as_deref()).is_some_and(|date| {
+                after.is_none_or(|after| date.as_str() >= after)
+                    && before.is_none_or(|before| date.as_str() <= before)
+            })
+        });
+        removed += count - section.results.len();
+        count == section.results.len() || !section.results.is_empty()
+    });
+    removed
+}
 
-    let mut omitted_debug_data = false;
-    if !within_limits(response, max_lines, max_bytes) && response.debug_data.take().is_some() {
-        omitted_debug_data = true;
+fn cap_field(value: &mut String, max_chars: usize) -> bool {
+    if value.chars().count() <= max_chars {
+        return false;
     }
+    let keep = value
+        .char_indices()
+        .nth(max_chars.saturating_sub(1))
+        .map_or(value.len(), |(index, _)| index);
+    value.truncate(keep);
+    value.push('…');
+    true
+}
 
-    let mut collapsed_warnings = false;
-    if !within_limits(response, max_lines, max_bytes) && !response.warnings.is_empty() {
-        response.warnings.clear();
-        collapsed_warnings = true;
+/// Estimated page length in words, extrapolated from a typical page size for the section and how
+/// dense the returned snippets are. `None` for sections without readable text.
+#[must_use]
+pub fn estimate_word_count(section: BraveSectionName, item: &SearchResultItem) -> Option<usize> {
+    let sample_words = std::iter::once(&item.snippet)
+        .chain(&item.extra_snippets)
+        .map(|text| text.split_whitespace().count())
+        .sum::<usize>();
+    let typical_page_words = match section {
+        BraveSectionName::Web | BraveSectionName::Mixed => 900,
+        BraveSectionName::News => 600,
+        BraveSectionName::Discussions => 450,
+        BraveSectionName::Infobox => 250,
+        // The answer is the whole content.
+        BraveSectionName::Faq => return Some(sample_words.max(1)),
+        BraveSectionName::Videos | BraveSectionName::Images | BraveSectionName::Locations => {
+            return None;
+        }
+    };
+    let density = sample_words.clamp(
+        READING_SNIPPET_BASELINE_WORDS / 2,
+        READING_SNIPPET_BASELINE_WORDS * 2,
+    );
+    Some(typical_page_words * density / READING_SNIPPET_BASELINE_WORDS)
+}
+
+/// Fills `word_count` and `estimated_reading_minutes` for every result that has an estimate.
+pub fn annotate_reading_time(sections: &mut [SearchSection]) {
+    for section in sections {
+        for item in &mut section.results {
+            item.word_count = estimate_word_count(item.origin_section.unwrap_or(section.key), item);
+            item.estimated_reading_minutes = item
+                .word_count
+                .map(|words| words.div_ceil(READING_WORDS_PER_MINUTE).max(1));
+        }
     }
+}
 
-    let mut condensed_summary = false;
-    if !within_limits(response, max_lines, max_bytes) {
-        response.summary = "Output truncated by configured limits.".to_string();
-        condensed_summary = true;
+/// Prompt-injection signals in `text`, each reported once in a fixed order.
+#[must_use]
+pub fn detect_injection_signals(text: &str) -> Vec<InjectionSignal> {
+    let lowered = text.to_lowercase();
+    let collapsed = lowered.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut signals = Vec::new();
+    if INJECTION_OVERRIDE_PHRASES
+        .iter()
+        .any(|phrase| collapsed.contains(phrase))
+    {
+        signals.push(InjectionSignal::InstructionOverride);
     }
+    if INJECTION_TOOL_CALL_MARKERS
+        .iter()
+        .any(|marker| lowered.contains(marker))
+    {
+        signals.push(InjectionSignal::ToolCallMarkup);
+    }
+    if text.split_whitespace().any(is_encoded_blob) {
+        signals.push(InjectionSignal::EncodedBlob);
+    }
+    signals
+}
 
-    let mut condensed_query = false;
-    if !within_limits(response, max_lines, max_bytes) {
-        if !response.meta.query.is_empty() {
-            condensed_query = true;
+/// Sets `injection_risk` on every result whose title or snippets carry injection signals.
+/// Returns the number of flagged results.
+pub fn annotate_injection_risk(sections: &mut [SearchSection]) -> usize {
+    let mut flagged = 0usize;
+    for item in sections.iter_mut().flat_map(|section| &mut section.results) {
+        let mut signals = Vec::new();
+        for text in [&item.title, &item.snippet]
+            .into_iter()
+            .chain(&item.extra_snippets)
+        {
+            for signal in detect_injection_signals(text) {
+                if !signals.contains(&signal) {
+                    signals.push(signal);
+                }
+            }
         }
+        if signals.is_empty() {
+            continue;
+        }
+        signals.sort_unstable();
+        let level = if signals.len() > 1 || signals.contains(&InjectionSignal::InstructionOverride)
+        {
+            InjectionRiskLevel::High
+        } else {
+            InjectionRiskLevel::Medium
+        };
+        item.injection_risk = Some(InjectionRisk { level, signals });
+        flagged += 1;
+    }
+    flagged
+}
+
+fn is_encoded_blob(word: &str) -> bool {
+    let word = word.trim_matches(|ch: char| !ch.is_ascii_alphanumeric() && ch != '=');
+    word.len() >= INJECTION_ENCODED_MIN_CHARS
+        && word.bytes().all(|byte| {
+            byte.is_ascii_alphanumeric() || matches!(byte, b'+' | b'/' | b'=' | b'-' | b'_')
+        })
+        && word.bytes().any(|byte| byte.is_ascii_uppercase())
+        && word.bytes().any(|byte| byte.is_ascii_lowercase())
+        && word.bytes().any(|byte| byte.is_ascii_digit())
+}
+
+pub fn enforce_output_limits(
+    response: &mut SearchResponse,
+    max_lines: usize,
+    max_bytes: usize,
+    section_floor: usize,
+) {
+    enforce_output_limits_with(response, &JsonRenderer, max_lines, max_bytes, section_floor);
+}
 
-        while !within_limits(response, max_lines, max_bytes) && !response.meta.query.is_empty() {
+/// Shrinks `response` until its form as rendered by `renderer` fits `max_lines`/`max_bytes`.
+///
+/// Sizes are tracked with a [`SizeModel`] rather than by re-rendering after every change, and
+/// the result is checked against the real rendering once at the end.
+pub fn enforce_output_limits_with(
+    response: &mut SearchResponse,
+    renderer: &dyn ResponseRenderer,
+    max_lines: usize,
+    max_bytes: usize,
+    section_floor: usize,
+) {
+    let initial = Shape::measure(response, renderer);
+    if initial.fits(max_lines, max_bytes) {
+        return;
+    }
+
+    let mut model = SizeModel::new(response, renderer, initial);
+    let over = |model: &SizeModel| !model.total().fits(max_lines, max_bytes);
+
+    // Trim results down to the per-section floor first so every requested section keeps a
+    // representative; the floor only gives way once everything else has been condensed.
+    let mut removed_results = 0usize;
+    while over(&model) && model.pop_result(response, section_floor) {
+        removed_results += 1;
+    }
+    model.shrink(response, |response| {
+        retain_returned_explanations(response);
+        true
+    });
+
+    let omitted_debug_data =
+        over(&model) && model.shrink(response, |response| response.debug_data.take().is_some());
+    let omitted_section_summaries = over(&model)
+        && model.shrink(response, |response| {
+            !std::mem::take(&mut response.section_summaries).is_empty()
+        });
+    let omitted_topics = over(&model)
+        && model.shrink(response, |response| {
+            !std::mem::take(&mut response.topics).is_empty()
+        });
+    let omitted_effective_params = over(&model)
+        && model.shrink(response, |response| {
+            response.meta.effective_params.take().is_some()
+        });
+    let omitted_diversity = over(&model)
+        && model.shrink(response, |response| {
+            response.meta.diversity.take().is_some()
+        });
+    let omitted_notices = over(&model)
+        && model.shrink(response, |response| {
+            !std::mem::take(&mut response.notices).is_empty()
+        });
+    let omitted_cache_info = over(&model)
+        && model.shrink(response, |response| {
+            response.meta.cache_age_secs = None;
+            std::mem::take(&mut response.meta.cache_hit)
+        });
+    let collapsed_warnings = over(&model)
+        && model.shrink(response, |response| {
+            !std::mem::take(&mut response.warnings).is_empty()
+        });
+    let condensed_summary = over(&model)
+        && model.shrink(response, |response| {
+            response.summary = "Output truncated by configured limits.".to_string();
+            true
+        });
+
+    let condensed_query = over(&model) && !response.meta.query.is_empty();
+    while over(&model) && !response.meta.query.is_empty() {
+        model.shrink(response, |response| {
             let len = response.meta.query.chars().count();
             let next_len = if len > 8 {
                 len / 2
@@ -138,14 +905,34 @@ pub fn enforce_output_limits(response: &mut SearchResponse, max_lines: usize, ma
                 len.saturating_sub(1)
             };
             response.meta.query = response.meta.query.chars().take(next_len).collect();
+            true
+        });
+    }
+
+    if section_floor > 0 {
+        while over(&model) && model.pop_result(response, 0) {
+            removed_results += 1;
         }
+        retain_returned_explanations(response);
     }
 
-    if !within_limits(response, max_lines, max_bytes) && !response.sections.is_empty() {
+    // The model is an estimate: verify once against the real rendering and fall back to exact
+    // trimming for whatever it missed.
+    let mut fits = within_limits(response, renderer, max_lines, max_bytes);
+    while !fits && pop_result_above_floor(response, 0).is_some() {
+        removed_results += 1;
+        retain_returned_explanations(response);
+        fits = within_limits(response, renderer, max_lines, max_bytes);
+    }
+
+    if !fits && (!response.sections.is_empty() || !response.results.is_empty()) {
         response.sections.clear();
+        response.results.clear();
+        response.explanations.clear();
+        fits = within_limits(response, renderer, max_lines, max_bytes);
     }
 
-    if !within_limits(response, max_lines, max_bytes) && !response.summary.is_empty() {
+    if !fits && !response.summary.is_empty() {
         response.summary.clear();
     }
 
@@ -153,46 +940,504 @@ pub fn enforce_output_limits(response: &mut SearchResponse, max_lines: usize, ma
         .sections
         .iter()
         .map(|section| section.results.len())
-        .sum::<usize>();
+        .sum::<usize>()
+        + response.results.len();
     if removed_results > 0 {
         response.meta.has_more = true;
+        if response.meta.diversity.is_some() {
+            response.meta.diversity = result_diversity(response);
+        }
     }
 
     response
         .warnings
         .push(build_truncation_warning(TruncationContext {
-            initial_lines,
-            initial_bytes,
+            initial_lines: initial.lines,
+            initial_bytes: initial.bytes,
             max_lines,
             max_bytes,
             removed_results,
             omitted_debug_data,
+            omitted_section_summaries,
+            omitted_topics,
+            omitted_effective_params,
+            omitted_diversity,
+            omitted_notices,
+            omitted_cache_info,
             collapsed_warnings,
             condensed_summary,
             condensed_query,
         }));
 
-    if !within_limits(response, max_lines, max_bytes) {
+    if !within_limits(response, renderer, max_lines, max_bytes) {
         response.warnings.pop();
 
         response.warnings.push(WarningEntry {
-            code: WARNING_OUTPUT_TRUNCATED.to_string(),
+            code: WarningCode::OutputTruncated,
             message: "Output truncated by configured limits.".to_string(),
         });
     }
 
-    if !within_limits(response, max_lines, max_bytes) {
+    if !within_limits(response, renderer, max_lines, max_bytes) {
         response.warnings.clear();
     }
 }
 
-fn serialized_shape(response: &SearchResponse) -> (usize, usize) {
-    let serialized = serde_json::to_string_pretty(response).unwrap_or_else(|_| "{}".to_string());
-    (serialized.lines().count(), serialized.len())
+/// Rough token count of `text`.
+///
+/// Each whitespace-separated word costs one token per started [`APPROX_CHARS_PER_TOKEN`]
+/// characters, which tracks BPE tokenizers closely enough for budgeting English prose and JSON.
+#[must_use]
+pub fn estimate_tokens(text: &str) -> usize {
+    text.split_whitespace()
+        .map(|word| word.chars().count().div_ceil(APPROX_CHARS_PER_TOKEN))
+        .sum()
+}
+
+/// Whether `requested` results with extra snippets are expected to fit the output limits.
+///
+/// The estimate assumes typical Brave result sizes rather than measuring anything, so the
+/// limits are still enforced on the rendered response afterwards.
+#[must_use]
+pub fn extra_snippets_fit(
+    requested: usize,
+    max_lines: usize,
+    max_bytes: usize,
+    max_tokens: Option<usize>,
+) -> bool {
+    let lines = ESTIMATED_ENVELOPE_LINES
+        + requested * (ESTIMATED_RESULT_LINES + ESTIMATED_EXTRA_SNIPPETS_LINES);
+    let bytes = ESTIMATED_ENVELOPE_BYTES
+        + requested * (ESTIMATED_RESULT_BYTES + ESTIMATED_EXTRA_SNIPPETS_BYTES);
+    lines <= max_lines
+        && bytes <= max_bytes
+        && max_tokens.is_none_or(|tokens| bytes.div_ceil(APPROX_CHARS_PER_TOKEN) <= tokens)
+}
+
+/// Sets `meta.estimated_tokens` to the estimate for `response` as rendered by `renderer`.
+pub fn record_estimated_tokens(
+    response: &mut SearchResponse,
+    renderer: &dyn ResponseRenderer,
+) -> usize {
+    response.meta.estimated_tokens = Some(rendered_tokens(response, renderer));
+    // Measure again so the estimate includes the field itself.
+    let tokens = rendered_tokens(response, renderer);
+    response.meta.estimated_tokens = Some(tokens);
+    tokens
+}
+
+/// Shrinks `response` until its rendered form is estimated at no more than `max_tokens`.
+///
+/// Extra snippets go first, then snippets are shortened step by step, then trailing results are
+/// dropped. The final estimate is reported in `meta.estimated_tokens`.
+pub fn enforce_token_budget(
+    response: &mut SearchResponse,
+    renderer: &dyn ResponseRenderer,
+    max_tokens: usize,
+) {
+    let initial_tokens = record_estimated_tokens(response, renderer);
+    if initial_tokens <= max_tokens {
+        return;
+    }
+
+    // The warning is part of the output, so it is in place while measuring.
+    let warning_index = response.warnings.len();
+    response
+        .warnings
+        .push(token_budget_warning(max_tokens, initial_tokens, 0, 0));
+
+    let mut shortened = 0usize;
+    for item in response
+        .sections
+        .iter_mut()
+        .flat_map(|section| &mut section.results)
+        .chain(response.results.iter_mut().map(|merged| &mut merged.item))
+    {
+        if !item.extra_snippets.is_empty() {
+            item.extra_snippets.clear();
+            shortened += 1;
+        }
+    }
+    for &max_chars in TOKEN_BUDGET_SNIPPET_CHARS {
+        response.warnings[warning_index] =
+            token_budget_warning(max_tokens, initial_tokens, shortened, 0);
+        if record_estimated_tokens(response, renderer) <= max_tokens {
+            break;
+        }
+        for item in response
+            .sections
+            .iter_mut()
+            .flat_map(|section| &mut section.results)
+            .chain(response.results.iter_mut().map(|merged| &mut merged.item))
+        {
+            if cap_field(&mut item.snippet, max_chars) {
+                shortened += 1;
+            }
+        }
+    }
+
+    let mut removed_results = 0usize;
+    loop {
+        response.warnings[warning_index] =
+            token_budget_warning(max_tokens, initial_tokens, shortened, removed_results);
+        if record_estimated_tokens(response, renderer) <= max_tokens
+            || pop_result_above_floor(response, 0).is_none()
+        {
+            break;
+        }
+        removed_results += 1;
+    }
+    if removed_results > 0 {
+        retain_returned_explanations(response);
+        response.meta.returned = response
+            .sections
+            .iter()
+            .map(|section| section.results.len())
+            .sum::<usize>()
+            + response.results.len();
+        response.meta.has_more = true;
+        if response.meta.diversity.is_some() {
+            response.meta.diversity = result_diversity(response);
+        }
+        record_estimated_tokens(response, renderer);
+    }
+}
+
+fn token_budget_warning(
+    max_tokens: usize,
+    initial_tokens: usize,
+    shortened: usize,
+    removed_results: usize,
+) -> WarningEntry {
+    WarningEntry {
+        code: WarningCode::OutputTruncated,
+        message: format!(
+            "Output trimmed to the max_tokens budget of {max_tokens} (about {initial_tokens} tokens before; shortened {shortened} snippet field(s), removed {removed_results} results)."
+        ),
+    }
+}
+
+/// Domain spread of the results in `response`; `None` when it has no results.
+#[must_use]
+pub fn result_diversity(response: &SearchResponse) -> Option<ResultDiversity> {
+    let mut counts = Vec::<(String, usize)>::new();
+    let items = response
+        .sections
+        .iter()
+        .flat_map(|section| &section.results)
+        .chain(response.results.iter().map(|merged| &merged.item));
+    for item in items {
+        let domain = normalize_host_for_dedup(&item.url).unwrap_or_else(|| item.url.clone());
+        match counts.iter_mut().find(|(seen, _)| *seen == domain) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((domain, 1)),
+        }
+    }
+
+    let results = counts.iter().map(|(_, count)| count).sum::<usize>();
+    // First-seen order breaks ties, so the top domain is the highest ranked of the largest.
+    let (top_domain, top_count) = counts
+        .iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .cloned()?;
+    let share = |part: usize| (part as f64 / results as f64 * 100.0).round() / 100.0;
+    Some(ResultDiversity {
+        results,
+        distinct_domains: counts.len(),
+        distinct_ratio: share(counts.len()),
+        top_domain,
+        top_domain_share: share(top_count),
+    })
+}
+
+/// Leaves `response` intact and, when its rendered form is over `max_lines`/`max_bytes`, sets
+/// `meta.exceeds_limits` and `meta.measured_size`. Returns whether the limits were exceeded.
+pub fn annotate_output_limits(
+    response: &mut SearchResponse,
+    renderer: &dyn ResponseRenderer,
+    max_lines: usize,
+    max_bytes: usize,
+) -> bool {
+    let (lines, bytes) = rendered_shape(response, renderer);
+    if lines <= max_lines && bytes <= max_bytes {
+        return false;
+    }
+    response.meta.exceeds_limits = true;
+    // Measure again so the reported size includes the annotation itself.
+    response.meta.measured_size = Some(OutputSize { lines, bytes });
+    let (lines, bytes) = rendered_shape(response, renderer);
+    response.meta.measured_size = Some(OutputSize { lines, bytes });
+    true
+}
+
+/// Where [`pop_result_above_floor`] removed a result from.
+#[derive(Debug, Clone, Copy)]
+enum ResultSlot {
+    Section(usize),
+    Merged(usize),
 }
 
-fn within_limits(response: &SearchResponse, max_lines: usize, max_bytes: usize) -> bool {
-    let (line_count, byte_count) = serialized_shape(response);
+fn pop_result_above_floor(
+    response: &mut SearchResponse,
+    section_floor: usize,
+) -> Option<ResultSlot> {
+    if let Some(index) = response
+        .sections
+        .iter()
+        .rposition(|section| section.results.len() > section_floor)
+    {
+        response.sections[index].results.pop();
+        return Some(ResultSlot::Section(index));
+    }
+
+    let results = &response.results;
+    let position = results.iter().rposition(|candidate| {
+        results
+            .iter()
+            .filter(|result| result.origin_section == candidate.origin_section)
+            .count()
+            > section_floor
+    })?;
+    response.results.remove(position);
+    Some(ResultSlot::Merged(position))
+}
+
+/// Rendered line and byte count of a response, or of the part one element contributes to it.
+#[derive(Debug, Clone, Copy, Default)]
+struct Shape {
+    lines: usize,
+    bytes: usize,
+}
+
+impl Shape {
+    fn measure(response: &SearchResponse, renderer: &dyn ResponseRenderer) -> Self {
+        let (lines, bytes) = rendered_shape(response, renderer);
+        Self { lines, bytes }
+    }
+
+    fn fits(self, max_lines: usize, max_bytes: usize) -> bool {
+        self.lines <= max_lines && self.bytes <= max_bytes
+    }
+
+    const fn plus(self, other: Self) -> Self {
+        Self {
+            lines: self.lines + other.lines,
+            bytes: self.bytes + other.bytes,
+        }
+    }
+
+    const fn minus(self, other: Self) -> Self {
+        Self {
+            lines: self.lines.saturating_sub(other.lines),
+            bytes: self.bytes.saturating_sub(other.bytes),
+        }
+    }
+}
+
+/// Estimated rendered size of a response that is being shrunk by the output limiter.
+///
+/// Each result's cost is measured once, on a probe response holding only that result, and the
+/// rest of the response (the envelope) is re-measured with its results detached whenever part of
+/// it is dropped. Neither measurement grows with the number of results, so trimming a large
+/// response no longer re-renders it once per dropped result.
+struct SizeModel<'a> {
+    renderer: &'a dyn ResponseRenderer,
+    section_costs: Vec<Vec<Shape>>,
+    /// What a section's result list costs beyond its results: the brackets an empty list drops.
+    section_overheads: Vec<Shape>,
+    merged_costs: Vec<Shape>,
+    merged_overhead: Shape,
+    /// Running sum of the result costs and the overheads of non-empty lists.
+    results: Shape,
+    envelope: Shape,
+    detached: Shape,
+}
+
+impl<'a> SizeModel<'a> {
+    fn new(
+        response: &mut SearchResponse,
+        renderer: &'a dyn ResponseRenderer,
+        total: Shape,
+    ) -> Self {
+        let mut probe = probe_response(response);
+        let mut section_costs = Vec::with_capacity(response.sections.len());
+        let mut section_overheads = Vec::with_capacity(response.sections.len());
+        for section in &response.sections {
+            probe.sections = vec![SearchSection {
+                key: section.key,
+                label: section.label.clone(),
+                provider: section.provider.clone(),
+                results: Vec::new(),
+                section_limit_reached: section.section_limit_reached,
+            }];
+            let (costs, overhead) = list_costs(&mut probe, renderer, &section.results, |probe| {
+                &mut probe.sections[0].results
+            });
+            section_costs.push(costs);
+            section_overheads.push(overhead);
+        }
+        probe.sections.clear();
+        let (merged_costs, merged_overhead) =
+            list_costs(&mut probe, renderer, &response.results, |probe| {
+                &mut probe.results
+            });
+
+        let results = section_costs
+            .iter()
+            .zip(&section_overheads)
+            .chain(std::iter::once((&merged_costs, &merged_overhead)))
+            .filter(|(costs, _)| !costs.is_empty())
+            .flat_map(|(costs, overhead)| costs.iter().chain(std::iter::once(overhead)))
+            .fold(Shape::default(), |sum, cost| sum.plus(*cost));
+        Self {
+            renderer,
+            section_costs,
+            section_overheads,
+            merged_costs,
+            merged_overhead,
+            results,
+            // Whatever the results do not account for is the envelope.
+            envelope: total.minus(results),
+            detached: detached_shape(response, renderer),
+        }
+    }
+
+    const fn total(&self) -> Shape {
+        self.envelope.plus(self.results)
+    }
+
+    fn pop_result(&mut self, response: &mut SearchResponse, section_floor: usize) -> bool {
+        let (cost, emptied, overhead) = match pop_result_above_floor(response, section_floor) {
+            Some(ResultSlot::Section(index)) => {
+                let costs = &mut self.section_costs[index];
+                let cost = costs.pop().unwrap_or_default();
+                (cost, costs.is_empty(), self.section_overheads[index])
+            }
+            Some(ResultSlot::Merged(index)) => {
+                let cost = self.merged_costs.remove(index);
+                (cost, self.merged_costs.is_empty(), self.merged_overhead)
+            }
+            None => return false,
+        };
+        self.results = self.results.minus(cost);
+        if emptied {
+            self.results = self.results.minus(overhead);
+        }
+        true
+    }
+
+    /// Applies `drop_part` to the envelope and updates the estimate when it reports a change.
+    fn shrink(
+        &mut self,
+        response: &mut SearchResponse,
+        drop_part: impl FnOnce(&mut SearchResponse) -> bool,
+    ) -> bool {
+        if !drop_part(response) {
+            return false;
+        }
+        let detached = detached_shape(response, self.renderer);
+        self.envelope = self.envelope.minus(self.detached).plus(detached);
+        self.detached = detached;
+        true
+    }
+}
+
+/// A copy of `response` without results or any optional part, used to price single results.
+fn probe_response(response: &SearchResponse) -> SearchResponse {
+    SearchResponse {
+        api_version: response.api_version.clone(),
+        summary: String::new(),
+        sections: Vec::new(),
+        results: Vec::new(),
+        section_summaries: Vec::new(),
+        topics: Vec::new(),
+        next_cursor: None,
+        meta: response.meta.clone(),
+        warnings: Vec::new(),
+        notices: Vec::new(),
+        explanations: Vec::new(),
+        debug_data: None,
+    }
+}
+
+/// Prices each of `items` by rendering `probe` with the item once and twice, plus what the list
+/// itself costs once it holds anything.
+fn list_costs<T: Clone>(
+    probe: &mut SearchResponse,
+    renderer: &dyn ResponseRenderer,
+    items: &[T],
+    list: impl Fn(&mut SearchResponse) -> &mut Vec<T>,
+) -> (Vec<Shape>, Shape) {
+    let Some(first) = items.first() else {
+        return (Vec::new(), Shape::default());
+    };
+    list(probe).clear();
+    let empty = Shape::measure(probe, renderer);
+    *list(probe) = vec![first.clone()];
+    let first_alone = Shape::measure(probe, renderer);
+
+    let costs = items
+        .iter()
+        .map(|item| {
+            *list(probe) = vec![item.clone()];
+            let once = Shape::measure(probe, renderer);
+            list(probe).push(item.clone());
+            Shape::measure(probe, renderer).minus(once)
+        })
+        .collect::<Vec<_>>();
+    list(probe).clear();
+    let overhead = first_alone.minus(empty).minus(costs[0]);
+    (costs, overhead)
+}
+
+/// Rendered size of `response` with every result list temporarily emptied.
+fn detached_shape(response: &mut SearchResponse, renderer: &dyn ResponseRenderer) -> Shape {
+    let sections = response
+        .sections
+        .iter_mut()
+        .map(|section| std::mem::take(&mut section.results))
+        .collect::<Vec<_>>();
+    let merged = std::mem::take(&mut response.results);
+    let shape = Shape::measure(response, renderer);
+    for (section, results) in response.sections.iter_mut().zip(sections) {
+        section.results = results;
+    }
+    response.results = merged;
+    shape
+}
+
+fn retain_returned_explanations(response: &mut SearchResponse) {
+    response.explanations.retain(|explanation| {
+        response.sections.iter().any(|section| {
+            section.key == explanation.section && section.results.len() >= explanation.position
+        }) || response.results.iter().any(|result| {
+            result.origin_section == explanation.section
+                && result.section_rank == explanation.position
+        })
+    });
+}
+
+fn rendered_tokens(response: &SearchResponse, renderer: &dyn ResponseRenderer) -> usize {
+    renderer
+        .render(response)
+        .map_or(0, |output| estimate_tokens(&output))
+}
+
+fn rendered_shape(response: &SearchResponse, renderer: &dyn ResponseRenderer) -> (usize, usize) {
+    let output = renderer
+        .render(response)
+        .unwrap_or_else(|_| "{}".to_string());
+    (output.lines().count(), output.len())
+}
+
+fn within_limits(
+    response: &SearchResponse,
+    renderer: &dyn ResponseRenderer,
+    max_lines: usize,
+    max_bytes: usize,
+) -> bool {
+    let (line_count, byte_count) = rendered_shape(response, renderer);
     line_count <= max_lines && byte_count <= max_bytes
 }
 
@@ -204,6 +1449,24 @@ fn build_truncation_warning(context: TruncationContext) -> WarningEntry {
     if context.omitted_debug_data {
         notes.push("debug_data");
     }
+    if context.omitted_section_summaries {
+        notes.push("section_summaries");
+    }
+    if context.omitted_topics {
+        notes.push("topics");
+    }
+    if context.omitted_effective_params {
+        notes.push("meta.effective_params");
+    }
+    if context.omitted_diversity {
+        notes.push("meta.diversity");
+    }
+    if context.omitted_notices {
+        notes.push("notices");
+    }
+    if context.omitted_cache_info {
+        notes.push("meta.cache_hit");
+    }
     if context.collapsed_warnings {
         notes.push("warnings");
     }
@@ -221,7 +1484,7 @@ fn build_truncation_warning(context: TruncationContext) -> WarningEntry {
     };
 
     WarningEntry {
-        code: WARNING_OUTPUT_TRUNCATED.to_string(),
+        code: WarningCode::OutputTruncated,
         message: format!(
             "Output truncated by configured limits ({} -> <= {} lines, {} -> <= {} bytes, removed {} results).{details}",
             context.initial_lines,
@@ -0,0 +1,146 @@
+//! Ring buffer of recent searches behind `brave_web_search_history`, optionally mirrored to a
+//! JSON-lines file so it survives restarts.
+
+use crate::config::HistorySettings;
+use crate::types::HistoryEntry;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+#[derive(Debug)]
+pub struct RequestHistory {
+    capacity: usize,
+    state: Mutex<HistoryState>,
+    file: Option<PathBuf>,
+}
+
+#[derive(Debug, Default)]
+struct HistoryState {
+    entries: VecDeque<HistoryEntry>,
+    /// Lines appended to the file since it last held exactly `entries`.
+    appended: usize,
+}
+
+impl RequestHistory {
+    /// Loads the newest `settings.size` entries of the history file, if any. The file is
+    /// rewritten with just the buffered entries at startup and whenever as many lines again have
+    /// been appended, so it stays under twice the buffer size.
+    #[must_use]
+    pub fn new(settings: &HistorySettings) -> Self {
+        let capacity = settings.size;
+        let file = settings.file.clone().filter(|_| capacity > 0);
+        let entries = file
+            .as_deref()
+            .map(|path| load_entries(path, capacity))
+            .unwrap_or_default();
+        if let Some(path) = &file
+            && let Err(error) = rewrite_entries(path, &entries)
+        {
+            tracing::warn!(
+                "failed to compact request history {}: {error}",
+                path.display()
+            );
+        }
+        Self {
+            capacity,
+            state: Mutex::new(HistoryState {
+                entries,
+                appended: 0,
+            }),
+            file,
+        }
+    }
+
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn record(&self, entry: HistoryEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.entries.len() == self.capacity {
+            state.entries.pop_front();
+        }
+        state.entries.push_back(entry);
+        let Some(path) = &self.file else {
+            return;
+        };
+        state.appended += 1;
+        let written = if state.appended >= self.capacity {
+            state.appended = 0;
+            rewrite_entries(path, &state.entries)
+        } else {
+            state
+                .entries
+                .back()
+                .map_or(Ok(()), |entry| append_entry(path, entry))
+        };
+        if let Err(error) = written {
+            tracing::warn!(
+                "failed to write request history {}: {error}",
+                path.display()
+            );
+        }
+    }
+
+    /// Up to `limit` entries matching `keep`, newest first, and how many are recorded in total.
+    pub fn recent<K>(&self, limit: usize, keep: K) -> (Vec<HistoryEntry>, usize)
+    where
+        K: Fn(&HistoryEntry) -> bool,
+    {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let recent = state
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| keep(entry))
+            .take(limit)
+            .cloned()
+            .collect();
+        (recent, state.entries.len())
+    }
+}
+
+fn load_entries(path: &Path, capacity: usize) -> VecDeque<HistoryEntry> {
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return VecDeque::new();
+    };
+    let mut entries = text
+        .lines()
+        .filter_map(|line| serde_json::from_str::<HistoryEntry>(line).ok())
+        .collect::<VecDeque<HistoryEntry>>();
+    let excess = entries.len().saturating_sub(capacity);
+    entries.drain(..excess);
+    entries
+}
+
+fn rewrite_entries(path: &Path, entries: &VecDeque<HistoryEntry>) -> std::io::Result<()> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut text = String::new();
+    for entry in entries {
+        text.push_str(&serde_json::to_string(entry).map_err(std::io::Error::other)?);
+        text.push('\n');
+    }
+    let temp_path = path.with_extension("jsonl.tmp");
+    std::fs::write(&temp_path, text)?;
+    std::fs::rename(&temp_path, path)
+}
+
+fn append_entry(path: &Path, entry: &HistoryEntry) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(entry).map_err(std::io::Error::other)?;
+    line.push(b'\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&line)
+}
@@ -0,0 +1,224 @@
+//! Streamable HTTP transport: JSON-RPC over `POST /mcp`, one MCP session per `Mcp-Session-Id`.
+//!
+//! Each session runs its own server loop over an in-memory transport; a request's response is
+//! returned as the HTTP body and notifications are acknowledged with `202 Accepted`. There is no
+//! server-initiated SSE stream, so `GET /mcp` answers `405`.
+
+use crate::constants::{HTTP_MCP_PATH, HTTP_SESSION_HEADER, MAX_HTTP_SESSIONS};
+use crate::mcp_server::BraveSearchMcpServer;
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use mcpkit::ServerBuilder;
+use mcpkit::protocol::{Message, Request};
+use mcpkit::transport::{MemoryTransport, Transport, TransportError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+struct Session {
+    client: MemoryTransport,
+    /// Held from sending a request until its response arrives, so concurrent requests on one
+    /// session cannot read each other's responses.
+    exchange: tokio::sync::Mutex<()>,
+    server: tokio::task::JoinHandle<()>,
+    last_used: Mutex<Instant>,
+}
+
+impl Session {
+    fn open(handler: BraveSearchMcpServer) -> Self {
+        let (client, server_side) = MemoryTransport::pair();
+        let server = tokio::spawn(async move {
+            let server = ServerBuilder::new(handler.clone())
+                .with_tools(handler)
+                .build();
+            if let Err(error) = server.serve(server_side).await {
+                tracing::debug!("HTTP session ended: {error}");
+            }
+        });
+        Self {
+            client,
+            exchange: tokio::sync::Mutex::new(()),
+            server,
+            last_used: Mutex::new(Instant::now()),
+        }
+    }
+
+    fn touch(&self) {
+        *self
+            .last_used
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Instant::now();
+    }
+
+    fn last_used(&self) -> Instant {
+        *self
+            .last_used
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    async fn call(&self, request: Request) -> Result<Message, TransportError> {
+        let id = request.id.clone();
+        let _exchange = self.exchange.lock().await;
+        self.touch();
+        self.client.send(Message::Request(request)).await?;
+        loop {
+            match self.client.recv().await? {
+                Some(Message::Response(response)) if response.id == id => {
+                    return Ok(Message::Response(response));
+                }
+                // Progress and log notifications have no stream to go to, and a response whose
+                // HTTP caller went away is nobody's.
+                Some(_) => {}
+                None => return Err(TransportError::ConnectionClosed),
+            }
+        }
+    }
+
+    async fn notify(&self, message: Message) -> Result<(), TransportError> {
+        self.touch();
+        self.client.send(message).await
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+#[derive(Clone)]
+struct HttpState {
+    handler: BraveSearchMcpServer,
+    sessions: Arc<Mutex<HashMap<String, Arc<Session>>>>,
+}
+
+impl HttpState {
+    fn sessions(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<Session>>> {
+        self.sessions.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Starts a session, closing the least recently used one when at [`MAX_HTTP_SESSIONS`].
+    fn open_session(&self) -> (String, Arc<Session>) {
+        let session = Arc::new(Session::open(self.handler.clone()));
+        let id = uuid::Uuid::new_v4().to_string();
+        let mut sessions = self.sessions();
+        if sessions.len() >= MAX_HTTP_SESSIONS
+            && let Some(stale) = sessions
+                .iter()
+                .min_by_key(|(_, session)| session.last_used())
+                .map(|(id, _)| id.clone())
+        {
+            sessions.remove(&stale);
+        }
+        sessions.insert(id.clone(), Arc::clone(&session));
+        (id, session)
+    }
+}
+
+/// Router serving `handler` at [`HTTP_MCP_PATH`].
+pub fn router(handler: BraveSearchMcpServer) -> Router {
+    let state = HttpState {
+        handler,
+        sessions: Arc::default(),
+    };
+    Router::new()
+        .route(
+            HTTP_MCP_PATH,
+            post(handle_post)
+                .delete(handle_delete)
+                .get(|| async { StatusCode::METHOD_NOT_ALLOWED }),
+        )
+        .with_state(state)
+}
+
+/// Serves `handler` on `listener` until the server fails.
+pub async fn serve(
+    handler: BraveSearchMcpServer,
+    listener: tokio::net::TcpListener,
+) -> std::io::Result<()> {
+    axum::serve(listener, router(handler)).await
+}
+
+async fn handle_post(State(state): State<HttpState>, headers: HeaderMap, body: Bytes) -> Response {
+    if !origin_allowed(&headers) {
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+    let message = match serde_json::from_slice::<Message>(&body) {
+        Ok(message) => message,
+        Err(error) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                axum::Json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": {"code": -32700, "message": format!("Parse error: {error}")}
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    let initialize =
+        matches!(&message, Message::Request(request) if request.method == "initialize");
+    let (session_id, session) = if initialize {
+        state.open_session()
+    } else {
+        let Some(session_id) = session_header(&headers) else {
+            return (StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header").into_response();
+        };
+        let Some(session) = state.sessions().get(session_id).cloned() else {
+            return (StatusCode::NOT_FOUND, "Unknown or closed session").into_response();
+        };
+        (session_id.to_string(), session)
+    };
+
+    let outcome = match message {
+        Message::Request(request) => session.call(request).await.map(Some),
+        other => session.notify(other).await.map(|()| None),
+    };
+    let session_header = [(HTTP_SESSION_HEADER, session_id)];
+    match outcome {
+        Ok(Some(response)) => (session_header, axum::Json(response)).into_response(),
+        Ok(None) => (StatusCode::ACCEPTED, session_header).into_response(),
+        Err(error) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("MCP session failed: {error}"),
+        )
+            .into_response(),
+    }
+}
+
+async fn handle_delete(State(state): State<HttpState>, headers: HeaderMap) -> StatusCode {
+    let removed = session_header(&headers).and_then(|id| state.sessions().remove(id));
+    if removed.is_some() {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+fn session_header(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(HTTP_SESSION_HEADER)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// Browsers send `Origin`; only pages served from this machine may call the server, which keeps
+/// DNS-rebinding pages off a localhost listener. Clients that send no `Origin` are allowed.
+fn origin_allowed(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    origin
+        .to_str()
+        .ok()
+        .and_then(|origin| url::Url::parse(origin).ok())
+        .is_some_and(|origin| {
+            matches!(origin.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"))
+        })
+}
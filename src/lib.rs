@@ -1,12 +1,57 @@
+// The MCP tool input schema is one large `json!` literal.
+#![recursion_limit = "256"]
+
+#[cfg(feature = "archive")]
+pub mod archive;
+#[cfg(feature = "server")]
+pub mod batch;
+#[cfg(feature = "server")]
+pub mod blocking;
+pub mod blocklist;
+pub mod brave_schema;
+#[cfg(feature = "server")]
 pub mod cache;
+#[cfg(feature = "server")]
+pub mod cli;
+#[cfg(feature = "server")]
 pub mod client;
 pub mod config;
+#[cfg(feature = "server")]
+pub mod config_file;
 pub mod constants;
+#[cfg(feature = "server")]
+pub mod endpoint_health;
 pub mod error;
+#[cfg(feature = "server")]
+pub mod events;
+pub mod filter_expr;
 pub mod formatting;
+#[cfg(feature = "server")]
+pub mod history;
+#[cfg(feature = "server")]
+pub mod http_transport;
+#[cfg(feature = "server")]
+pub mod logging;
+#[cfg(feature = "server")]
 pub mod mcp_server;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod normalization;
+#[cfg(feature = "server")]
+pub mod notify;
 pub mod parsing;
+#[cfg(feature = "server")]
+pub mod provider;
+pub mod ranking;
+pub mod render;
+#[cfg(feature = "server")]
+pub mod repeat_guard;
+#[cfg(feature = "server")]
+pub mod seen_urls;
+#[cfg(feature = "server")]
 pub mod service;
+#[cfg(feature = "server")]
+pub mod single_flight;
+#[cfg(feature = "server")]
 pub mod throttle;
 pub mod types;
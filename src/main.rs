@@ -1,4 +1,8 @@
-use codex_brave_web_search::config::RuntimeConfig;
+use clap::Parser;
+use codex_brave_web_search::cli::{self, Cli};
+use codex_brave_web_search::config::TransportMode;
+use codex_brave_web_search::config_file;
+use codex_brave_web_search::http_transport;
 use codex_brave_web_search::mcp_server::BraveSearchMcpServer;
 use codex_brave_web_search::service::SearchService;
 use mcpkit::ServerBuilder;
@@ -7,19 +11,94 @@ use mcpkit::transport::stdio::StdioTransport;
 
 #[tokio::main]
 async fn main() -> Result<(), McpError> {
-    let config = RuntimeConfig::from_env();
+    let cli = Cli::parse();
+    let mut config = config_file::load_runtime_config(cli.config.as_deref())
+        .map_err(|error| McpError::internal(format!("startup: {error}")))?;
+    if let Some(transport) = cli.transport {
+        config.transport = transport;
+    }
+    if let Some(listen) = cli.listen {
+        config.listen_addr = listen;
+    }
+
+    codex_brave_web_search::logging::init(&config.log_filter, config.log_format);
 
-    tracing_subscriber::fmt()
-        .with_env_filter(config.log_filter.clone())
-        .with_writer(std::io::stderr)
-        .init();
+    if let Some(error) = &config.locale_data_error {
+        tracing::warn!("{error}");
+    }
+    for warning in &config.config_warnings {
+        tracing::warn!("ignoring invalid setting {warning}");
+    }
 
+    if let Some(command) = cli.command {
+        let service = SearchService::new(config)
+            .map_err(|error| McpError::internal(format!("startup: {error}")))?;
+        match cli::run(command, service).await {
+            Ok(output) => {
+                println!("{output}");
+                return Ok(());
+            }
+            Err(error) => {
+                eprintln!("{}: {}", error.code(), error.message());
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let transport = config.transport;
+    let listen_addr = config.listen_addr;
     let service = SearchService::new(config)
         .map_err(|error| McpError::internal(format!("startup: {error}")))?;
+    match serde_json::to_string(&service.capabilities()) {
+        Ok(manifest) => tracing::info!(
+            "codex-brave-web-search {} capabilities: {manifest}",
+            service.server_version()
+        ),
+        Err(error) => tracing::warn!("capability manifest unavailable: {error}"),
+    }
 
     let handler = BraveSearchMcpServer::new(service);
-    let server = ServerBuilder::new(handler.clone())
-        .with_tools(handler)
-        .build();
-    server.serve(StdioTransport::new()).await
+    let resumed = handler.resume_batches();
+    if resumed > 0 {
+        tracing::info!("resumed {resumed} scheduled batch jobs");
+    }
+    #[cfg(unix)]
+    spawn_key_reload_on_sighup(handler.clone())?;
+
+    match transport {
+        TransportMode::Stdio => {
+            ServerBuilder::new(handler.clone())
+                .with_tools(handler)
+                .build()
+                .serve(StdioTransport::new())
+                .await
+        }
+        TransportMode::Http => {
+            let listener = tokio::net::TcpListener::bind(listen_addr)
+                .await
+                .map_err(|error| McpError::internal(format!("http transport: {error}")))?;
+            tracing::info!("serving MCP over streamable HTTP at http://{listen_addr}/mcp");
+            http_transport::serve(handler, listener)
+                .await
+                .map_err(|error| McpError::internal(format!("http transport: {error}")))
+        }
+    }
+}
+
+/// Reloads the API key whenever the process receives SIGHUP, so key rotation needs no restart.
+#[cfg(unix)]
+fn spawn_key_reload_on_sighup(handler: BraveSearchMcpServer) -> Result<(), McpError> {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangups = signal(SignalKind::hangup())
+        .map_err(|error| McpError::internal(format!("startup: SIGHUP handler: {error}")))?;
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            match handler.reload_api_key().await {
+                Ok(status) => tracing::info!("SIGHUP: API key reloaded, status {}", status.status),
+                Err(error) => tracing::warn!("SIGHUP: API key reload failed: {error}"),
+            }
+        }
+    });
+    Ok(())
 }
@@ -1,38 +1,88 @@
+use crate::batch::BatchScheduler;
+#[cfg(feature = "metrics")]
+use crate::constants::TOOL_BRAVE_WEB_SEARCH_METRICS;
+#[cfg(feature = "archive")]
+use crate::constants::TOOL_BRAVE_WEB_SEARCH_REPLAY;
 use crate::constants::{
-    TOOL_BRAVE_WEB_SEARCH, TOOL_BRAVE_WEB_SEARCH_HELP, TOOL_BRAVE_WEB_SEARCH_STATUS,
+    DEFAULT_BATCH_DEADLINE_SECS, MAX_BATCH_DEADLINE_SECS, MAX_BATCH_QUERIES, MCP_LOGGER_NAME,
+    TOOL_BRAVE_BATCH_STATUS, TOOL_BRAVE_COMPARE_PARAMS, TOOL_BRAVE_RELOAD_KEY,
+    TOOL_BRAVE_SCHEDULE_BATCH, TOOL_BRAVE_SUGGEST, TOOL_BRAVE_WEB_SEARCH,
+    TOOL_BRAVE_WEB_SEARCH_HELP, TOOL_BRAVE_WEB_SEARCH_HISTORY, TOOL_BRAVE_WEB_SEARCH_STATUS,
+    TOOL_BRAVE_WEB_SEARCH_SUMMARIZE,
 };
 use crate::error::AppError;
+use crate::events::EventLevel;
 use crate::service::SearchService;
-use crate::types::{BraveWebSearchArgs, HelpArgs, StatusArgs};
+#[cfg(feature = "metrics")]
+use crate::types::MetricsArgs;
+#[cfg(feature = "archive")]
+use crate::types::ReplayArgs;
+use crate::types::{
+    BatchScheduleArgs, BatchStatusArgs, BraveWebSearchArgs, CompareParamsArgs, HelpArgs,
+    HistoryArgs, LocaleOptions, ReloadKeyArgs, StatusArgs, StatusResponse, SuggestArgs,
+    SummarizeArgs,
+};
 use mcpkit::capability::{ServerCapabilities, ServerInfo};
 use mcpkit::error::McpError;
 use mcpkit::types::content::Content;
 use mcpkit::types::tool::{CallToolResult, Tool, ToolAnnotations, ToolOutput};
-use mcpkit::{Context, ServerHandler, ToolHandler};
+use mcpkit::{Context, LogLevel, LoggingHandler, ServerHandler, ToolHandler};
 use serde::Serialize;
 use serde_json::Value;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
 pub struct BraveSearchMcpServer {
     service: Arc<SearchService>,
+    batches: BatchScheduler,
+    /// Least severe `logging` notification the client asked for with `logging/setLevel`.
+    log_level: Arc<Mutex<LogLevel>>,
 }
 
 impl BraveSearchMcpServer {
     #[must_use]
     pub fn new(service: SearchService) -> Self {
+        let service = Arc::new(service);
         Self {
-            service: Arc::new(service),
+            batches: BatchScheduler::new(Arc::clone(&service)),
+            service,
+            log_level: Arc::default(),
         }
     }
 
-    fn tools() -> Vec<Tool> {
-        vec![
-            search_tool_schema(),
+    /// Reloads the API key outside of a tool call, e.g. on SIGHUP.
+    pub async fn reload_api_key(&self) -> Result<StatusResponse, AppError> {
+        self.service.reload_api_key(|| false).await
+    }
+
+    /// Resumes persisted batch jobs; call once a Tokio runtime is running.
+    pub fn resume_batches(&self) -> usize {
+        self.batches.resume()
+    }
+
+    /// Schemas of every tool the server exposes.
+    #[must_use]
+    pub fn tools(&self) -> Vec<Tool> {
+        let mut tools = vec![
+            search_tool_schema(self.service.locale_options()),
             help_tool_schema(),
             status_tool_schema(),
-        ]
+        ];
+        #[cfg(feature = "metrics")]
+        tools.push(metrics_tool_schema());
+        tools.extend([
+            history_tool_schema(),
+            summarize_tool_schema(),
+            suggest_tool_schema(self.service.locale_options()),
+            schedule_batch_tool_schema(self.service.locale_options()),
+            batch_status_tool_schema(),
+            compare_params_tool_schema(self.service.locale_options()),
+        ]);
+        #[cfg(feature = "archive")]
+        tools.push(replay_tool_schema());
+        tools.push(reload_key_tool_schema());
+        tools
     }
 }
 
@@ -42,19 +92,26 @@ impl ServerHandler for BraveSearchMcpServer {
     }
 
     fn capabilities(&self) -> ServerCapabilities {
-        ServerCapabilities::new().with_tools()
+        ServerCapabilities::new().with_tools().with_logging()
     }
 
     fn instructions(&self) -> Option<String> {
-        Some(
-            "Use brave_web_search for Brave web/news/images/videos queries. Use brave_web_search_help for schema/examples and brave_web_search_status for config/health checks.".to_string(),
-        )
+        let mut instructions = "Use brave_web_search for Brave web/news/images/videos queries. Use brave_web_search_summarize for an AI summary with cited sources (requires Brave summarizer access). Use brave_suggest for fast query autocomplete before a full search. Use brave_schedule_batch to run many queries paced within throttle limits and poll brave_batch_status with the returned job_id. Use brave_web_search_help for schema/examples and brave_web_search_status for config/health checks; brave_web_search_history returns the most recent searches.".to_string();
+        if cfg!(feature = "metrics") {
+            instructions
+                .push_str(" brave_web_search_metrics returns usage counters since startup.");
+        }
+        if cfg!(feature = "archive") {
+            instructions.push_str(" Use brave_web_search_replay with a trace_id to re-run an archived search and diff it against the original.");
+        }
+        instructions.push_str(" Use brave_reload_key after rotating the API key.");
+        Some(instructions)
     }
 }
 
 impl ToolHandler for BraveSearchMcpServer {
     async fn list_tools(&self, _ctx: &Context<'_>) -> Result<Vec<Tool>, McpError> {
-        Ok(Self::tools())
+        Ok(self.tools())
     }
 
     async fn call_tool(
@@ -62,6 +119,29 @@ impl ToolHandler for BraveSearchMcpServer {
         name: &str,
         args: Value,
         ctx: &Context<'_>,
+    ) -> Result<ToolOutput, McpError> {
+        let output = self.dispatch_tool(name, args, ctx).await;
+        self.send_operational_events(ctx).await;
+        Ok(self.redact_output(output?))
+    }
+}
+
+impl LoggingHandler for BraveSearchMcpServer {
+    async fn set_level(&self, level: LogLevel) -> Result<(), McpError> {
+        *self
+            .log_level
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = level;
+        Ok(())
+    }
+}
+
+impl BraveSearchMcpServer {
+    async fn dispatch_tool(
+        &self,
+        name: &str,
+        args: Value,
+        ctx: &Context<'_>,
     ) -> Result<ToolOutput, McpError> {
         let trace_id = Uuid::new_v4().to_string();
 
@@ -77,12 +157,20 @@ impl ToolHandler for BraveSearchMcpServer {
                         ));
                     }
                 };
+                let format = parsed.format.clone();
+                let compact = parsed.compact;
                 match self
                     .service
                     .execute_web_search(parsed, &trace_id, || ctx.is_cancelled())
                     .await
-                {
-                    Ok(response) => json_tool_output(&response),
+                    .and_then(|response| {
+                        self.service
+                            .render_response(&response, format.as_deref(), compact)
+                    }) {
+                    Ok(rendered) => Ok(ToolOutput::Success(CallToolResult {
+                        content: vec![Content::text(rendered)],
+                        is_error: None,
+                    })),
                     Err(error) => Ok(error_tool_output(
                         &error,
                         self.service.server_version(),
@@ -118,12 +206,238 @@ impl ToolHandler for BraveSearchMcpServer {
                 let response = self.service.status(parsed, || ctx.is_cancelled()).await;
                 json_tool_output(&response)
             }
+            #[cfg(feature = "metrics")]
+            TOOL_BRAVE_WEB_SEARCH_METRICS => {
+                if let Err(error) = parse_tool_args::<MetricsArgs>(args, name) {
+                    return Ok(error_tool_output(
+                        &error,
+                        self.service.server_version(),
+                        &trace_id,
+                    ));
+                }
+                json_tool_output(&self.service.metrics(&trace_id).await)
+            }
+            TOOL_BRAVE_WEB_SEARCH_HISTORY => {
+                let parsed = match parse_tool_args::<HistoryArgs>(args, name) {
+                    Ok(parsed) => parsed,
+                    Err(error) => {
+                        return Ok(error_tool_output(
+                            &error,
+                            self.service.server_version(),
+                            &trace_id,
+                        ));
+                    }
+                };
+                json_tool_output(&self.service.history(&parsed, &trace_id))
+            }
+            TOOL_BRAVE_WEB_SEARCH_SUMMARIZE => {
+                let parsed = match parse_tool_args::<SummarizeArgs>(args, name) {
+                    Ok(parsed) => parsed,
+                    Err(error) => {
+                        return Ok(error_tool_output(
+                            &error,
+                            self.service.server_version(),
+                            &trace_id,
+                        ));
+                    }
+                };
+                match self
+                    .service
+                    .execute_summarize(parsed, &trace_id, || ctx.is_cancelled())
+                    .await
+                {
+                    Ok(response) => json_tool_output(&response),
+                    Err(error) => Ok(error_tool_output(
+                        &error,
+                        self.service.server_version(),
+                        &trace_id,
+                    )),
+                }
+            }
+            TOOL_BRAVE_SUGGEST => {
+                let parsed = match parse_tool_args::<SuggestArgs>(args, name) {
+                    Ok(parsed) => parsed,
+                    Err(error) => {
+                        return Ok(error_tool_output(
+                            &error,
+                            self.service.server_version(),
+                            &trace_id,
+                        ));
+                    }
+                };
+                match self
+                    .service
+                    .execute_suggest(parsed, &trace_id, || ctx.is_cancelled())
+                    .await
+                {
+                    Ok(response) => json_tool_output(&response),
+                    Err(error) => Ok(error_tool_output(
+                        &error,
+                        self.service.server_version(),
+                        &trace_id,
+                    )),
+                }
+            }
+            TOOL_BRAVE_COMPARE_PARAMS => {
+                let parsed = match parse_tool_args::<CompareParamsArgs>(args, name) {
+                    Ok(parsed) => parsed,
+                    Err(error) => {
+                        return Ok(error_tool_output(
+                            &error,
+                            self.service.server_version(),
+                            &trace_id,
+                        ));
+                    }
+                };
+                match self
+                    .service
+                    .execute_compare_params(parsed, &trace_id, || ctx.is_cancelled())
+                    .await
+                {
+                    Ok(response) => json_tool_output(&response),
+                    Err(error) => Ok(error_tool_output(
+                        &error,
+                        self.service.server_version(),
+                        &trace_id,
+                    )),
+                }
+            }
+            #[cfg(feature = "archive")]
+            TOOL_BRAVE_WEB_SEARCH_REPLAY => {
+                let parsed = match parse_tool_args::<ReplayArgs>(args, name) {
+                    Ok(parsed) => parsed,
+                    Err(error) => {
+                        return Ok(error_tool_output(
+                            &error,
+                            self.service.server_version(),
+                            &trace_id,
+                        ));
+                    }
+                };
+                match self
+                    .service
+                    .execute_replay(parsed, &trace_id, || ctx.is_cancelled())
+                    .await
+                {
+                    Ok(response) => json_tool_output(&response),
+                    Err(error) => Ok(error_tool_output(
+                        &error,
+                        self.service.server_version(),
+                        &trace_id,
+                    )),
+                }
+            }
+            TOOL_BRAVE_SCHEDULE_BATCH => {
+                let parsed = match parse_tool_args::<BatchScheduleArgs>(args, name) {
+                    Ok(parsed) => parsed,
+                    Err(error) => {
+                        return Ok(error_tool_output(
+                            &error,
+                            self.service.server_version(),
+                            &trace_id,
+                        ));
+                    }
+                };
+                match self.batches.schedule(parsed) {
+                    Ok(response) => json_tool_output(&response),
+                    Err(error) => Ok(error_tool_output(
+                        &error,
+                        self.service.server_version(),
+                        &trace_id,
+                    )),
+                }
+            }
+            TOOL_BRAVE_BATCH_STATUS => {
+                let parsed = match parse_tool_args::<BatchStatusArgs>(args, name) {
+                    Ok(parsed) => parsed,
+                    Err(error) => {
+                        return Ok(error_tool_output(
+                            &error,
+                            self.service.server_version(),
+                            &trace_id,
+                        ));
+                    }
+                };
+                match self.batches.status(&parsed.job_id) {
+                    Ok(response) => json_tool_output(&response),
+                    Err(error) => Ok(error_tool_output(
+                        &error,
+                        self.service.server_version(),
+                        &trace_id,
+                    )),
+                }
+            }
+            TOOL_BRAVE_RELOAD_KEY => {
+                if let Err(error) = parse_tool_args::<ReloadKeyArgs>(args, name) {
+                    return Ok(error_tool_output(
+                        &error,
+                        self.service.server_version(),
+                        &trace_id,
+                    ));
+                }
+                match self.service.reload_api_key(|| ctx.is_cancelled()).await {
+                    Ok(response) => json_tool_output(&response),
+                    Err(error) => Ok(error_tool_output(
+                        &error,
+                        self.service.server_version(),
+                        &trace_id,
+                    )),
+                }
+            }
             _ => Err(McpError::invalid_params(
                 "tools/call",
                 format!("Unknown tool: {name}"),
             )),
         }
     }
+
+    /// Sends quota, rate-limit and key events recorded by the service as MCP `logging`
+    /// notifications, dropping those below the client's log level.
+    async fn send_operational_events(&self, ctx: &Context<'_>) {
+        let threshold = *self
+            .log_level
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        for event in self.service.drain_operational_events() {
+            let level = match event.level {
+                EventLevel::Warning => LogLevel::Warning,
+                EventLevel::Error => LogLevel::Error,
+            };
+            if level < threshold {
+                continue;
+            }
+            let params = serde_json::json!({
+                "level": level.to_string(),
+                "logger": MCP_LOGGER_NAME,
+                "data": { "code": event.code, "message": event.message },
+            });
+            if let Err(error) = ctx.notify("notifications/message", Some(params)).await {
+                tracing::debug!("logging notification {} not sent: {error}", event.code);
+            }
+        }
+    }
+
+    /// Masks the API key in every text block, so an upstream error body or debug field that echoes
+    /// it cannot leak it to the client.
+    fn redact_output(&self, output: ToolOutput) -> ToolOutput {
+        match output {
+            ToolOutput::Success(mut result) => {
+                for content in &mut result.content {
+                    if let Content::Text(text) = content {
+                        text.text = self.service.redact_api_key(&text.text);
+                    }
+                }
+                ToolOutput::Success(result)
+            }
+            ToolOutput::RecoverableError {
+                message,
+                suggestion,
+            } => ToolOutput::RecoverableError {
+                message: self.service.redact_api_key(&message),
+                suggestion: suggestion.map(|suggestion| self.service.redact_api_key(&suggestion)),
+            },
+        }
+    }
 }
 
 fn normalize_args(value: Value) -> Value {
@@ -173,44 +487,200 @@ fn error_tool_output(error: &AppError, server_version: &str, trace_id: &str) ->
     })
 }
 
-fn search_tool_schema() -> Tool {
+fn search_tool_schema(locales: &LocaleOptions) -> Tool {
     Tool::new(TOOL_BRAVE_WEB_SEARCH)
         .description("Search Brave web/news/images/videos endpoints with structured JSON output and diagnostics")
         .input_schema(serde_json::json!({
             "type": "object",
             "additionalProperties": false,
-            "required": ["query"],
+            "anyOf": [{ "required": ["query"] }, { "required": ["cursor"] }],
             "properties": {
                 "query": { "type": "string", "description": "Search query." },
                 "search_type": { "type": "string", "enum": ["web", "news", "images", "videos"] },
                 "result_filter": {
                     "type": "array",
                     "items": { "type": "string" },
-                    "description": "Web-only section filters; supported values: web, discussions, videos, news, infobox"
+                    "description": "Web-only section filters; supported values: web, discussions, videos, news, infobox, locations, faq"
                 },
                 "max_results": { "type": "integer", "minimum": 1, "maximum": 20 },
                 "offset": { "type": "integer", "minimum": 0 },
-                "country": { "type": "string" },
-                "search_language": { "type": "string" },
-                "ui_language": { "type": "string" },
+                "country": {
+                    "type": "string",
+                    "description": format!("Country code; supported values: {}", locales.countries.join(", "))
+                },
+                "search_language": {
+                    "type": "string",
+                    "description": format!("Search language code; supported values: {}", locales.search_languages.join(", "))
+                },
+                "ui_language": {
+                    "type": "string",
+                    "description": format!("UI language code; supported values: {}", locales.ui_languages.join(", "))
+                },
                 "safe_search": { "type": "string", "description": "off | moderate | strict" },
                 "units": { "type": "string", "description": "metric | imperial" },
-                "freshness": { "type": "string" },
+                "freshness": freshness_schema(),
                 "spellcheck": { "type": "boolean" },
                 "extra_snippets": { "type": "boolean" },
                 "text_decorations": { "type": "boolean" },
+                "dedupe_snippets": { "type": "boolean" },
                 "max_lines": { "type": "integer", "minimum": 1 },
                 "max_bytes": { "type": "integer", "minimum": 1 },
+                "max_tokens": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Estimated-token budget for the output (clamped to 256-32000): snippets are shortened, then results dropped, to fit. The estimate is reported in meta.estimated_tokens."
+                },
+                "limit_mode": {
+                    "type": "string",
+                    "enum": ["enforce", "annotate"],
+                    "default": "enforce",
+                    "description": "'annotate' returns an oversized response intact with meta.exceeds_limits and meta.measured_size instead of shrinking it to max_lines/max_bytes."
+                },
                 "debug": { "type": "boolean" },
                 "include_raw_payload": { "type": "boolean" },
+                "raw_payload_fields": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "JSON pointers (e.g. /web/results/0) to keep from include_raw_payload; the payload becomes an object keyed by pointer."
+                },
                 "disable_cache": { "type": "boolean" },
+                "max_cache_age_secs": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Reject cached responses older than this; a failed refresh returns an error instead of stale data."
+                },
                 "disable_throttle": { "type": "boolean" },
-                "include_request_url": { "type": "boolean" }
+                "include_request_url": { "type": "boolean" },
+                "explain": {
+                    "type": "boolean",
+                    "description": "Annotate each returned result with why it appears where it does (provider rank, dedup decisions, filters)."
+                },
+                "correct_query": {
+                    "type": "boolean",
+                    "description": "Run the Brave spellcheck endpoint first and search the corrected query (adds a QUERY_CORRECTED warning)."
+                },
+                "merge_sections": {
+                    "type": "boolean",
+                    "description": "Interleave results from all sections into one ranked results list (each item carries origin_section) instead of sections."
+                },
+                "order": {
+                    "type": "string",
+                    "enum": ["grouped", "ranked"],
+                    "default": "grouped",
+                    "description": "Web only: 'ranked' returns one 'mixed' section in Brave's own relevance order across web, news, videos and discussions (each item carries origin_section)."
+                },
+                "dedup": {
+                    "type": "string",
+                    "enum": ["url", "host", "title", "off"],
+                    "default": "url",
+                    "description": "What counts as a duplicate across and within sections: the same URL, the same site ('host', ignoring www./m./amp. prefixes), a near-identical title ('title'), or nothing ('off')."
+                },
+                "rerank": {
+                    "type": "string",
+                    "enum": ["none", "keyword", "recency"],
+                    "default": "none",
+                    "description": "Reorder results within each section before output limits apply: by keyword relevance to the query ('keyword') or newest first by page age ('recency')."
+                },
+                "goggles": {
+                    "type": "string",
+                    "description": "Brave Goggles re-ranking profile: an https URL to a goggle definition or a hosted goggle id."
+                },
+                "site": { "type": "string", "description": "Restrict to a host, compiled to site:<host>" },
+                "filetype": { "type": "string", "description": "File extension, compiled to filetype:<ext>" },
+                "intitle": { "type": "string", "description": "Words the title must contain, compiled to intitle:" },
+                "inurl": { "type": "string", "description": "Words the URL must contain, compiled to inurl:" },
+                "exclude_terms": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Terms results must not contain, compiled to -term"
+                },
+                "include_section_summaries": {
+                    "type": "boolean",
+                    "description": "Add a one-line summary per section (counts, sources, recency, dominant topic)."
+                },
+                "reading_time": {
+                    "type": "boolean",
+                    "description": "Add word_count and estimated_reading_minutes to each text result, extrapolated from a typical page length for the section and the snippet density."
+                },
+                "scan_injection": {
+                    "type": "boolean",
+                    "description": "Flag results whose text looks like prompt injection (instruction overrides, tool-call JSON, base64 blobs) with injection_risk and an INJECTION_RISK warning."
+                },
+                "delta_only": {
+                    "type": "boolean",
+                    "description": "On a continuation page (cursor or offset > 0), leave out results that earlier pages of the same query already returned; meta.suppressed_repeats counts them."
+                },
+                "breaking_only": {
+                    "type": "boolean",
+                    "description": "Keep only news results Brave flags as breaking; other sections are untouched."
+                },
+                "published_after": {
+                    "type": "string",
+                    "description": "YYYY-MM-DD: keep only results published on or after this date (undated results are dropped). Finer than freshness buckets; applied after Brave returns results."
+                },
+                "published_before": {
+                    "type": "string",
+                    "description": "YYYY-MM-DD: keep only results published on or before this date (undated results are dropped)."
+                },
+                "clean_urls": {
+                    "type": "boolean",
+                    "description": "Strip tracking query params (utm_*, fbclid, gclid, ref, ...) from returned result URLs."
+                },
+                "auto_paginate": {
+                    "type": "boolean",
+                    "description": "When a page comes back short after dedup and filters, fetch follow-up pages (up to a server cap) and merge them until max_results is reached; meta.pages_fetched reports how many pages were used."
+                },
+                "topic": {
+                    "type": "string",
+                    "enum": ["business", "technology", "sports", "politics", "science", "health", "entertainment"],
+                    "description": "News only: steer the query toward a topic and add a topics block counting returned results per classified topic (unmatched results count as other). 'tech' is accepted as an alias."
+                },
+                "filter": {
+                    "type": "string",
+                    "maxLength": 512,
+                    "description": "Post-filter expression over result fields, e.g. \"domain endswith '.gov' and age < 30d\". Fields: title, url, domain, snippet, text, source, type, subtype, creator, section, published, age, live. Operators: == != < <= > >= contains startswith endswith, combined with and/or/not and parentheses."
+                },
+                "format": {
+                    "type": "string",
+                    "description": "Output format: json (default), markdown, text, csv, rss, or a renderer registered by the embedder. Output limits apply to the rendered text."
+                },
+                "compact": {
+                    "type": "boolean",
+                    "description": "Serialize json output on a single line instead of pretty-printed."
+                },
+                "cursor": {
+                    "type": "string",
+                    "description": "Opaque next_cursor from a previous response; fetches the next page with the same query and params."
+                },
+                "api_version": {
+                    "type": "string",
+                    "enum": ["v1", "v2"],
+                    "description": "Response layout; v2 flattens results and adds pagination/timings objects. Defaults to CODEX_BRAVE_API_VERSION (v1)."
+                }
             }
         }))
         .annotations(ToolAnnotations::read_only())
 }
 
+/// `freshness`: Brave's string form or a `{ from, to }` date range.
+fn freshness_schema() -> Value {
+    serde_json::json!({
+        "description": "pd | pw | pm | py | <n>d/w/m/y, or a date range as \"YYYY-MM-DDtoYYYY-MM-DD\" or { \"from\": \"YYYY-MM-DD\", \"to\": \"YYYY-MM-DD\" }",
+        "oneOf": [
+            { "type": "string" },
+            {
+                "type": "object",
+                "properties": {
+                    "from": { "type": "string", "format": "date" },
+                    "to": { "type": "string", "format": "date" }
+                },
+                "required": ["from", "to"],
+                "additionalProperties": false
+            }
+        ]
+    })
+}
+
 fn help_tool_schema() -> Tool {
     Tool::new(TOOL_BRAVE_WEB_SEARCH_HELP)
         .description("Show parameter, limits, and error guidance for brave_web_search")
@@ -241,3 +711,180 @@ fn status_tool_schema() -> Tool {
         }))
         .annotations(ToolAnnotations::read_only())
 }
+
+#[cfg(feature = "archive")]
+fn replay_tool_schema() -> Tool {
+    Tool::new(TOOL_BRAVE_WEB_SEARCH_REPLAY)
+        .description("Re-run an archived brave_web_search (CODEX_BRAVE_ARCHIVE_DIR) by its trace_id with the exact normalized request and no cache; returns the original and new responses plus a field-level diff")
+        .input_schema(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["trace_id"],
+            "properties": {
+                "trace_id": { "type": "string", "description": "meta.trace_id of an earlier brave_web_search response." }
+            }
+        }))
+        .annotations(ToolAnnotations::read_only())
+}
+
+#[cfg(feature = "metrics")]
+fn metrics_tool_schema() -> Tool {
+    Tool::new(TOOL_BRAVE_WEB_SEARCH_METRICS)
+        .description("Counters since startup: searches (total and per type), search cache hits/misses, failed searches by error code, p50/p95 upstream latency and throttle waits")
+        .input_schema(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {}
+        }))
+        .annotations(ToolAnnotations::read_only())
+}
+
+fn history_tool_schema() -> Tool {
+    Tool::new(TOOL_BRAVE_WEB_SEARCH_HISTORY)
+        .description("Recent searches, newest first: time, trace_id, query hash (or redacted query), search_type, outcome, duration and cache status")
+        .input_schema(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "limit": { "type": "integer", "minimum": 1, "description": "Entries to return (default 20)." },
+                "errors_only": { "type": "boolean", "description": "Only searches that failed." }
+            }
+        }))
+        .annotations(ToolAnnotations::read_only())
+}
+
+fn reload_key_tool_schema() -> Tool {
+    Tool::new(TOOL_BRAVE_RELOAD_KEY)
+        .description("Re-read the Brave API key (CODEX_BRAVE_API_KEY_FILE, then environment), clear a revoked-key state and re-probe the endpoints; returns the resulting status")
+        .input_schema(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {}
+        }))
+}
+
+fn summarize_tool_schema() -> Tool {
+    Tool::new(TOOL_BRAVE_WEB_SEARCH_SUMMARIZE)
+        .description("Summarize Brave web results for a query via the Brave summarizer, returning summary text plus cited sources")
+        .input_schema(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["query"],
+            "properties": {
+                "query": { "type": "string", "description": "Search query to summarize." },
+                "country": { "type": "string" },
+                "search_language": { "type": "string" },
+                "ui_language": { "type": "string" },
+                "safe_search": { "type": "string", "description": "off | moderate | strict" }
+            }
+        }))
+        .annotations(ToolAnnotations::read_only())
+}
+
+fn suggest_tool_schema(locales: &LocaleOptions) -> Tool {
+    Tool::new(TOOL_BRAVE_SUGGEST)
+        .description("Get Brave autocomplete suggestions to expand or correct a query before searching")
+        .input_schema(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["query"],
+            "properties": {
+                "query": { "type": "string", "description": "Partial or full query to complete." },
+                "country": {
+                    "type": "string",
+                    "description": format!("Country code; supported values: {}", locales.countries.join(", "))
+                },
+                "count": { "type": "integer", "minimum": 1, "maximum": 20, "default": 5 }
+            }
+        }))
+        .annotations(ToolAnnotations::read_only())
+}
+
+fn compare_params_tool_schema(locales: &LocaleOptions) -> Tool {
+    let param_set = serde_json::json!({
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "country": {
+                "type": "string",
+                "description": format!("Country code; supported values: {}", locales.countries.join(", "))
+            },
+            "search_language": {
+                "type": "string",
+                "description": format!("Search language code; supported values: {}", locales.search_languages.join(", "))
+            },
+            "ui_language": {
+                "type": "string",
+                "description": format!("UI language code; supported values: {}", locales.ui_languages.join(", "))
+            },
+            "safe_search": { "type": "string", "description": "off | moderate | strict" },
+            "units": { "type": "string", "description": "metric | imperial" },
+            "freshness": freshness_schema(),
+            "goggles": { "type": "string" }
+        }
+    });
+    Tool::new(TOOL_BRAVE_COMPARE_PARAMS)
+        .description("Run one query with two parameter sets (e.g. different country or safe_search) and group deduplicated results into overlap, only_in_a and only_in_b")
+        .input_schema(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["query", "a", "b"],
+            "properties": {
+                "query": { "type": "string", "description": "Query run with both parameter sets." },
+                "search_type": { "type": "string", "enum": ["web", "news", "images", "videos"] },
+                "max_results": { "type": "integer", "minimum": 1, "maximum": 20 },
+                "a": param_set,
+                "b": param_set
+            }
+        }))
+        .annotations(ToolAnnotations::read_only())
+}
+
+fn schedule_batch_tool_schema(locales: &LocaleOptions) -> Tool {
+    Tool::new(TOOL_BRAVE_SCHEDULE_BATCH)
+        .description("Schedule a batch of Brave searches spread over a deadline to respect throttle and quota; returns a job_id to poll")
+        .input_schema(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["queries"],
+            "properties": {
+                "queries": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "minItems": 1,
+                    "maxItems": MAX_BATCH_QUERIES
+                },
+                "deadline_secs": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_BATCH_DEADLINE_SECS,
+                    "default": DEFAULT_BATCH_DEADLINE_SECS,
+                    "description": "Target time to finish the whole batch; execution is spread across it."
+                },
+                "search_type": { "type": "string", "enum": ["web", "news", "images", "videos"] },
+                "max_results": { "type": "integer", "minimum": 1, "maximum": 20 },
+                "country": {
+                    "type": "string",
+                    "description": format!("Country code; supported values: {}", locales.countries.join(", "))
+                },
+                "search_language": {
+                    "type": "string",
+                    "description": format!("Search language code; supported values: {}", locales.search_languages.join(", "))
+                }
+            }
+        }))
+}
+
+fn batch_status_tool_schema() -> Tool {
+    Tool::new(TOOL_BRAVE_BATCH_STATUS)
+        .description("Poll a scheduled batch job for progress and accumulated per-query results")
+        .input_schema(serde_json::json!({
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["job_id"],
+            "properties": {
+                "job_id": { "type": "string", "description": "Job id returned by brave_schedule_batch." }
+            }
+        }))
+        .annotations(ToolAnnotations::read_only())
+}
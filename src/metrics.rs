@@ -0,0 +1,101 @@
+//! Counters since startup, reported by `brave_web_search_metrics`.
+
+use crate::constants::METRICS_LATENCY_SAMPLES;
+use crate::types::{LatencySummary, SearchCounts, SearchType};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
+
+#[derive(Debug)]
+pub struct SearchMetrics {
+    started: Instant,
+    state: Mutex<MetricsState>,
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    searches: u64,
+    by_type: BTreeMap<&'static str, u64>,
+    errors: BTreeMap<String, u64>,
+    upstream_calls: u64,
+    /// The most recent upstream latencies, oldest first.
+    upstream_ms: VecDeque<u64>,
+}
+
+impl Default for SearchMetrics {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            state: Mutex::default(),
+        }
+    }
+}
+
+impl SearchMetrics {
+    fn state(&self) -> std::sync::MutexGuard<'_, MetricsState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn record_search(&self, search_type: SearchType) {
+        let mut state = self.state();
+        state.searches += 1;
+        *state.by_type.entry(search_type.as_str()).or_default() += 1;
+    }
+
+    pub fn record_error(&self, code: &str) {
+        *self.state().errors.entry(code.to_string()).or_default() += 1;
+    }
+
+    pub fn record_upstream(&self, elapsed_ms: u128) {
+        let mut state = self.state();
+        state.upstream_calls += 1;
+        if state.upstream_ms.len() == METRICS_LATENCY_SAMPLES {
+            state.upstream_ms.pop_front();
+        }
+        state
+            .upstream_ms
+            .push_back(u64::try_from(elapsed_ms).unwrap_or(u64::MAX));
+    }
+
+    #[must_use]
+    pub fn uptime_secs(&self) -> u64 {
+        self.started.elapsed().as_secs()
+    }
+
+    #[must_use]
+    pub fn searches(&self) -> SearchCounts {
+        let state = self.state();
+        SearchCounts {
+            total: state.searches,
+            by_type: state
+                .by_type
+                .iter()
+                .map(|(search_type, count)| ((*search_type).to_string(), *count))
+                .collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn errors(&self) -> BTreeMap<String, u64> {
+        self.state().errors.clone()
+    }
+
+    /// Percentiles are taken over the most recent [`METRICS_LATENCY_SAMPLES`] upstream calls.
+    #[must_use]
+    pub fn upstream_latency(&self) -> LatencySummary {
+        let state = self.state();
+        let mut samples = state.upstream_ms.iter().copied().collect::<Vec<u64>>();
+        samples.sort_unstable();
+        LatencySummary {
+            calls: state.upstream_calls,
+            p50_ms: percentile(&samples, 50),
+            p95_ms: percentile(&samples, 95),
+        }
+    }
+}
+
+/// Nearest-rank percentile of ascending `samples`.
+fn percentile(samples: &[u64], percent: usize) -> Option<u64> {
+    let rank = (samples.len() * percent).div_ceil(100).max(1);
+    samples.get(rank - 1).copied()
+}
@@ -1,12 +1,23 @@
 use crate::constants::{
-    ALLOWED_RESULT_FILTERS, COUNTRY_OPTIONS, DEFAULT_RESULTS, DEFAULT_SEARCH_TYPE,
-    FRESHNESS_SHORTCUT_OPTIONS, MAX_OFFSET_IMAGES, MAX_OFFSET_WEB_NEWS_VIDEOS, MAX_RESULTS,
-    SAFE_SEARCH_OPTIONS, SEARCH_LANGUAGE_OPTIONS, SEARCH_TYPES, UI_LANGUAGE_OPTIONS, UNIT_OPTIONS,
+    ALLOWED_RESULT_FILTERS, API_VERSIONS, COUNTRY_OPTIONS, DEDUP_HOST_PREFIXES, DEDUP_STRATEGIES,
+    DEDUP_TITLE_SITE_MAX_WORDS, DEDUP_TITLE_SITE_SEPARATORS, DEFAULT_RESULTS, DEFAULT_SEARCH_TYPE,
+    FRESHNESS_SHORTCUT_OPTIONS, LIMIT_MODES, MAX_OFFSET_IMAGES, MAX_OFFSET_WEB_NEWS_VIDEOS,
+    MAX_QUERY_LENGTH, MAX_RESULTS, MIN_REDACTED_SECRET_CHARS, NEWS_TOPICS,
+    QUERY_EFFECTIVE_TERM_LIMIT, QUERY_STOPWORD_SHARE, QUERY_STOPWORDS, REDACTED_SECRET,
+    RERANK_MODES, RESULT_ORDERS, SAFE_SEARCH_OPTIONS, SEARCH_LANGUAGE_OPTIONS, SEARCH_TYPES,
+    SNIPPET_DUPLICATE_OVERLAP, TRACKING_PARAM_PREFIXES, TRACKING_PARAMS, UI_LANGUAGE_OPTIONS,
+    UNIT_OPTIONS,
 };
-use crate::types::{SearchType, WebResultFilter};
+use crate::types::{
+    ApiVersion, BraveWebSearchArgs, DedupStrategy, LimitMode, NewsTopic, RerankMode, ResultOrder,
+    SearchCursor, SearchType, WebResultFilter,
+};
+use base64::Engine;
+use base64::prelude::BASE64_URL_SAFE_NO_PAD as URL_SAFE_NO_PAD;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::borrow::Cow;
+use std::collections::HashSet;
 
 static HTML_ENTITY_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"&(#x[0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);").expect("valid entity regex"));
@@ -22,6 +33,90 @@ static CONTROL_RE: Lazy<Regex> = Lazy::new(|| {
 static WHITESPACE_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"\s+").expect("valid whitespace regex"));
 
+const COUNTRY_ALIASES: &[(&str, &str)] = &[
+    ("UK", "GB"),
+    ("EN", "US"),
+    ("USA", "US"),
+    ("JA", "JP"),
+    ("KO", "KR"),
+    ("DA", "DK"),
+    ("SV", "SE"),
+    ("EL", "GR"),
+    ("ZH", "CN"),
+    ("GLOBAL", "ALL"),
+    ("WORLD", "ALL"),
+];
+
+const SEARCH_LANGUAGE_ALIASES: &[(&str, &str)] = &[
+    ("zh", "zh-hans"),
+    ("zh-cn", "zh-hans"),
+    ("zh-sg", "zh-hans"),
+    ("zh-tw", "zh-hant"),
+    ("zh-hk", "zh-hant"),
+    ("no", "nb"),
+    ("nn", "nb"),
+    ("iw", "he"),
+];
+
+const UI_LANGUAGE_ALIASES: &[(&str, &str)] = &[
+    ("en", "en-US"),
+    ("en-uk", "en-GB"),
+    ("de", "de-DE"),
+    ("fr", "fr-FR"),
+    ("es", "es-ES"),
+    ("it", "it-IT"),
+    ("ja", "ja-JP"),
+    ("ko", "ko-KR"),
+    ("pt", "pt-BR"),
+    ("zh", "zh-CN"),
+];
+
+/// whatlang ISO 639-3 code, Brave `search_lang`, and the country implied by the language.
+const DETECTED_LANGUAGES: &[(&str, &str, Option<&str>)] = &[
+    ("ara", "ar", Some("SA")),
+    ("ben", "bn", None),
+    ("bul", "bg", None),
+    ("cat", "ca", None),
+    ("cmn", "zh-hans", Some("CN")),
+    ("hrv", "hr", None),
+    ("ces", "cs", None),
+    ("dan", "da", Some("DK")),
+    ("eng", "en", None),
+    ("est", "et", None),
+    ("fin", "fi", Some("FI")),
+    ("fra", "fr", Some("FR")),
+    ("deu", "de", Some("DE")),
+    ("ell", "el", Some("GR")),
+    ("guj", "gu", Some("IN")),
+    ("heb", "he", None),
+    ("hin", "hi", Some("IN")),
+    ("hun", "hu", None),
+    ("ita", "it", Some("IT")),
+    ("jpn", "jp", Some("JP")),
+    ("kan", "kn", Some("IN")),
+    ("kor", "ko", Some("KR")),
+    ("lav", "lv", None),
+    ("lit", "lt", None),
+    ("mal", "ml", Some("IN")),
+    ("mar", "mr", Some("IN")),
+    ("nob", "nb", Some("NO")),
+    ("pol", "pl", Some("PL")),
+    ("pan", "pa", Some("IN")),
+    ("ron", "ro", None),
+    ("rus", "ru", Some("RU")),
+    ("srp", "sr", None),
+    ("slk", "sk", None),
+    ("slv", "sl", None),
+    ("spa", "es", Some("ES")),
+    ("swe", "sv", Some("SE")),
+    ("tam", "ta", Some("IN")),
+    ("tel", "te", Some("IN")),
+    ("tha", "th", None),
+    ("tur", "tr", Some("TR")),
+    ("ukr", "uk", None),
+    ("vie", "vi", None),
+];
+
 fn named_entity(entity: &str) -> Option<&'static str> {
     match entity {
         "lt" => Some("<"),
@@ -204,6 +299,51 @@ pub fn search_type_from_str(value: &str) -> Option<SearchType> {
         .find(|candidate| candidate.as_str() == value)
 }
 
+#[must_use]
+pub fn result_order_from_str(value: &str) -> Option<ResultOrder> {
+    let value = value.trim().to_lowercase();
+    RESULT_ORDERS
+        .iter()
+        .copied()
+        .find(|candidate| candidate.as_str() == value)
+}
+
+#[must_use]
+pub fn dedup_strategy_from_str(value: &str) -> Option<DedupStrategy> {
+    let value = value.trim().to_lowercase();
+    DEDUP_STRATEGIES
+        .iter()
+        .copied()
+        .find(|candidate| candidate.as_str() == value)
+}
+
+#[must_use]
+pub fn rerank_mode_from_str(value: &str) -> Option<RerankMode> {
+    let value = value.trim().to_lowercase();
+    RERANK_MODES
+        .iter()
+        .copied()
+        .find(|candidate| candidate.as_str() == value)
+}
+
+#[must_use]
+pub fn limit_mode_from_str(value: &str) -> Option<LimitMode> {
+    let value = value.trim().to_lowercase();
+    LIMIT_MODES
+        .iter()
+        .copied()
+        .find(|candidate| candidate.as_str() == value)
+}
+
+#[must_use]
+pub fn api_version_from_str(value: &str) -> Option<ApiVersion> {
+    let value = value.trim().to_lowercase();
+    API_VERSIONS
+        .iter()
+        .copied()
+        .find(|candidate| candidate.as_str() == value)
+}
+
 #[must_use]
 pub fn web_result_filter_from_str(value: &str) -> Option<WebResultFilter> {
     ALLOWED_RESULT_FILTERS
@@ -241,11 +381,20 @@ pub fn parse_result_filter_values(input: Option<&[String]>) -> (Vec<WebResultFil
 
 #[must_use]
 pub fn pick_locale_language(raw: Option<&str>) -> Option<String> {
+    pick_locale_language_with(raw, SEARCH_LANGUAGE_OPTIONS)
+}
+
+#[must_use]
+pub fn pick_locale_language_with<S: AsRef<str>>(
+    raw: Option<&str>,
+    options: &[S],
+) -> Option<String> {
     let normalized = raw?.trim().to_lowercase();
     if normalized.is_empty() {
         return None;
     }
 
+    let is_supported = |value: &str| options.iter().any(|option| option.as_ref() == value);
     let normalize_alias = |value: &str| {
         if value == "ja" {
             "jp".to_string()
@@ -255,7 +404,7 @@ pub fn pick_locale_language(raw: Option<&str>) -> Option<String> {
     };
 
     let full_candidate = normalize_alias(&normalized);
-    if SEARCH_LANGUAGE_OPTIONS.contains(&full_candidate.as_str()) {
+    if is_supported(&full_candidate) {
         return Some(full_candidate);
     }
 
@@ -268,7 +417,7 @@ pub fn pick_locale_language(raw: Option<&str>) -> Option<String> {
     }
 
     let short_candidate = normalize_alias(&short);
-    if SEARCH_LANGUAGE_OPTIONS.contains(&short_candidate.as_str()) {
+    if is_supported(&short_candidate) {
         return Some(short_candidate);
     }
 
@@ -283,12 +432,65 @@ pub fn normalize_safe_search(raw: Option<&str>) -> Option<String> {
         .then_some(value)
 }
 
+#[must_use]
+pub fn safe_search_rank(value: &str) -> Option<usize> {
+    SAFE_SEARCH_OPTIONS
+        .iter()
+        .position(|option| *option == value)
+}
+
+#[must_use]
+pub fn apply_safe_search_floor(
+    requested: Option<String>,
+    floor: Option<&str>,
+) -> (Option<String>, bool) {
+    let Some(floor) = floor else {
+        return (requested, false);
+    };
+    match requested {
+        Some(value) if safe_search_rank(&value) < safe_search_rank(floor) => {
+            (Some(floor.to_string()), true)
+        }
+        Some(value) => (Some(value), false),
+        None => (Some(floor.to_string()), false),
+    }
+}
+
 #[must_use]
 pub fn normalize_units(raw: Option<&str>) -> Option<String> {
     let value = raw?.trim().to_lowercase();
     UNIT_OPTIONS.contains(&value.as_str()).then_some(value)
 }
 
+#[must_use]
+pub fn normalize_news_topic(raw: Option<&str>) -> Option<NewsTopic> {
+    let value = raw?.trim().to_lowercase();
+    let value = match value.as_str() {
+        "tech" => "technology",
+        "sport" => "sports",
+        "finance" => "business",
+        other => other,
+    };
+    NEWS_TOPICS
+        .iter()
+        .copied()
+        .find(|candidate| candidate.as_str() == value)
+}
+
+/// Appends the topic term to `query` unless it already contains it as a word, so a composed
+/// query replayed from a cursor is not extended twice.
+#[must_use]
+pub fn compose_topic_query(query: &str, topic: NewsTopic) -> String {
+    let term = topic.as_str();
+    let present = query
+        .split(|character: char| !character.is_alphanumeric())
+        .any(|word| word.eq_ignore_ascii_case(term));
+    if present || query.chars().count() + term.len() + 1 > MAX_QUERY_LENGTH {
+        return query.to_string();
+    }
+    format!("{query} {term}")
+}
+
 #[must_use]
 pub fn normalize_freshness(raw: Option<&str>) -> Option<String> {
     let value = raw?.trim().to_lowercase();
@@ -298,11 +500,149 @@ pub fn normalize_freshness(raw: Option<&str>) -> Option<String> {
     if FRESHNESS_SHORTCUT_OPTIONS.contains(&value.as_str()) {
         return Some(value);
     }
+    if let Some((from, to)) = value.split_once("to") {
+        return (parse_date(from)? <= parse_date(to)?).then_some(value);
+    }
     static FRESHNESS_RE: Lazy<Regex> =
         Lazy::new(|| Regex::new(r"^\d{1,4}[dwmy]$").expect("valid freshness regex"));
     FRESHNESS_RE.is_match(&value).then_some(value)
 }
 
+/// The `YYYY-MM-DD` date of a date or `page_age`-style timestamp (`2024-03-01T12:00:00`), if it
+/// is a real calendar date.
+#[must_use]
+pub fn normalize_published_date(raw: Option<&str>) -> Option<String> {
+    let value = raw?.trim();
+    let date = value.get(..10)?;
+    if !(value.len() == 10 || value[10..].starts_with('T')) {
+        return None;
+    }
+    parse_date(date)?;
+    Some(date.to_string())
+}
+
+/// Whether `text` is an RFC 6901 JSON pointer: empty, or `/`-prefixed with `~` only in the `~0`
+/// and `~1` escapes.
+#[must_use]
+pub fn is_json_pointer(text: &str) -> bool {
+    (text.is_empty() || text.starts_with('/'))
+        && text
+            .split('~')
+            .skip(1)
+            .all(|rest| rest.starts_with('0') || rest.starts_with('1'))
+}
+
+/// The operators for the query-operator helper arguments, in the order `site`, `filetype`,
+/// `intitle`, `inurl`, `exclude_terms`. `Err` carries the offending field and the reason.
+pub fn query_operators(
+    args: &BraveWebSearchArgs,
+) -> Result<Vec<(&'static str, String)>, (&'static str, String)> {
+    static SITE_RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"^[a-z0-9]([a-z0-9-]*[a-z0-9])?(\.[a-z0-9]([a-z0-9-]*[a-z0-9])?)+$")
+            .expect("valid site regex")
+    });
+    static FILETYPE_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^[a-z0-9]{1,10}$").expect("valid filetype regex"));
+
+    let mut operators = Vec::new();
+    if let Some(raw) = args.site.as_deref() {
+        let lowered = raw.trim().to_lowercase();
+        let site = lowered
+            .strip_prefix("https://")
+            .or_else(|| lowered.strip_prefix("http://"))
+            .unwrap_or(&lowered)
+            .trim_end_matches('/');
+        if !SITE_RE.is_match(site) {
+            return Err((
+                "site",
+                "expected a host name such as example.com".to_string(),
+            ));
+        }
+        operators.push(("site", format!("site:{site}")));
+    }
+    if let Some(raw) = args.filetype.as_deref() {
+        let lowered = raw.trim().to_lowercase();
+        let filetype = lowered.trim_start_matches('.');
+        if !FILETYPE_RE.is_match(filetype) {
+            return Err(("filetype", "expected an extension such as pdf".to_string()));
+        }
+        operators.push(("filetype", format!("filetype:{filetype}")));
+    }
+    for (field, raw) in [("intitle", &args.intitle), ("inurl", &args.inurl)] {
+        if let Some(raw) = raw.as_deref() {
+            let term =
+                operator_term(raw).ok_or_else(|| (field, "must not be empty".to_string()))?;
+            operators.push((field, format!("{field}:{term}")));
+        }
+    }
+    for raw in args.exclude_terms.iter().flatten() {
+        let term = operator_term(raw.trim().trim_start_matches('-'))
+            .ok_or_else(|| ("exclude_terms", "terms must not be empty".to_string()))?;
+        operators.push(("exclude_terms", format!("-{term}")));
+    }
+    Ok(operators)
+}
+
+/// An operator argument with quotes removed, quoted again when it spans several words.
+fn operator_term(raw: &str) -> Option<String> {
+    let words = raw
+        .split(|character: char| character.is_whitespace() || character == '"')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>();
+    match words.as_slice() {
+        [] => None,
+        [word] => Some((*word).to_string()),
+        words => Some(format!("\"{}\"", words.join(" "))),
+    }
+}
+
+/// Parses a calendar date written `YYYY-MM-DD` into a comparable `(year, month, day)`.
+fn parse_date(raw: &str) -> Option<(u32, u32, u32)> {
+    static DATE_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"^(\d{4})-(\d{2})-(\d{2})$").expect("valid date regex"));
+    let captures = DATE_RE.captures(raw)?;
+    let year = captures[1].parse::<u32>().ok()?;
+    let month = captures[2].parse::<u32>().ok()?;
+    let day = captures[3].parse::<u32>().ok()?;
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    (1..=days_in_month)
+        .contains(&day)
+        .then_some((year, month, day))
+}
+
+#[must_use]
+pub fn normalize_goggles(raw: Option<&str>) -> Option<String> {
+    let value = raw?.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(url) = url::Url::parse(value) {
+        return (url.scheme() == "https" && url.host_str().is_some()).then(|| value.to_string());
+    }
+    static GOGGLE_ID_RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"^[A-Za-z0-9][A-Za-z0-9_.-]{0,127}$").expect("valid goggle id regex")
+    });
+    GOGGLE_ID_RE.is_match(value).then(|| value.to_string())
+}
+
+#[must_use]
+pub fn encode_cursor(cursor: &SearchCursor) -> String {
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(cursor).unwrap_or_default())
+}
+
+#[must_use]
+pub fn decode_cursor(raw: &str) -> Option<SearchCursor> {
+    let bytes = URL_SAFE_NO_PAD.decode(raw.trim()).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
 #[must_use]
 pub fn clamp_offset(raw_offset: Option<usize>, search_type: SearchType) -> usize {
     let value = raw_offset.unwrap_or(0);
@@ -320,28 +660,230 @@ pub fn to_limited_count(raw_count: Option<usize>) -> usize {
 
 #[must_use]
 pub fn normalize_ui_language(raw: Option<&str>) -> Option<String> {
+    normalize_ui_language_with(raw, UI_LANGUAGE_OPTIONS)
+}
+
+#[must_use]
+pub fn normalize_ui_language_with<S: AsRef<str>>(
+    raw: Option<&str>,
+    options: &[S],
+) -> Option<String> {
     let value = raw?.trim();
     if value.is_empty() {
         return None;
     }
 
-    let normalized = value.replace('_', "-");
+    let candidate = canonical_ui_language(value);
+    options
+        .iter()
+        .any(|option| option.as_ref() == candidate)
+        .then_some(candidate)
+}
+
+#[must_use]
+pub fn canonical_ui_language(value: &str) -> String {
+    let normalized = value.trim().replace('_', "-");
     let parts: Vec<&str> = normalized.split('-').collect();
-    let candidate = if parts.len() == 2 {
+    if parts.len() == 2 {
         format!("{}-{}", parts[0].to_lowercase(), parts[1].to_uppercase())
     } else {
         normalized
-    };
+    }
+}
 
-    UI_LANGUAGE_OPTIONS
-        .contains(&candidate.as_str())
-        .then_some(candidate)
+/// Builds an `Accept-Language` value preferring `ui_language`, then its bare language, then
+/// `search_language`, e.g. `fr-CA`/`en` becomes `fr-CA,fr;q=0.9,en;q=0.8`.
+#[must_use]
+pub fn accept_language_for(
+    ui_language: Option<&str>,
+    search_language: Option<&str>,
+) -> Option<String> {
+    let mut tags = Vec::<String>::new();
+    if let Some(ui_language) = ui_language {
+        let ui_language = canonical_ui_language(ui_language);
+        let primary = ui_language
+            .split('-')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        tags.push(ui_language);
+        tags.push(primary);
+    }
+    if let Some(search_language) = search_language {
+        // Brave's search_lang list uses `jp` for Japanese; Accept-Language expects BCP 47.
+        let search_language = match search_language.trim().to_lowercase().as_str() {
+            "jp" => "ja".to_string(),
+            other => other.to_string(),
+        };
+        tags.push(search_language);
+    }
+
+    let mut seen = HashSet::new();
+    tags.retain(|tag| !tag.is_empty() && seen.insert(tag.to_lowercase()));
+    let header = tags
+        .iter()
+        .enumerate()
+        .map(|(index, tag)| match index {
+            0 => tag.clone(),
+            _ => format!("{tag};q=0.{}", 10usize.saturating_sub(index).max(1)),
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    (!header.is_empty()).then_some(header)
+}
+
+#[must_use]
+pub fn country_from_ui_language(ui_language: &str) -> Option<String> {
+    let normalized = ui_language.trim().replace('_', "-");
+    let (_, region) = normalized.split_once('-')?;
+    let region = region.trim().to_uppercase();
+    (!region.is_empty()).then_some(region)
 }
 
 #[must_use]
 pub fn normalize_country(raw: Option<&str>) -> Option<String> {
+    normalize_country_with(raw, COUNTRY_OPTIONS)
+}
+
+#[must_use]
+pub fn normalize_country_with<S: AsRef<str>>(raw: Option<&str>, options: &[S]) -> Option<String> {
     let value = raw?.trim().to_uppercase();
-    COUNTRY_OPTIONS.contains(&value.as_str()).then_some(value)
+    options
+        .iter()
+        .any(|option| option.as_ref() == value)
+        .then_some(value)
+}
+
+fn lookup_alias(aliases: &[(&str, &'static str)], key: &str) -> Option<&'static str> {
+    aliases
+        .iter()
+        .find(|(alias, _)| *alias == key)
+        .map(|(_, target)| *target)
+}
+
+#[must_use]
+pub fn country_alias(raw: &str) -> Option<&'static str> {
+    lookup_alias(COUNTRY_ALIASES, &raw.trim().to_uppercase())
+}
+
+#[must_use]
+pub fn search_language_alias(raw: &str, country_hint: Option<&str>) -> Option<&'static str> {
+    let key = raw.trim().to_lowercase().replace('_', "-");
+    if key == "pt" {
+        return Some(if country_hint == Some("BR") {
+            "pt-br"
+        } else {
+            "pt-pt"
+        });
+    }
+    lookup_alias(SEARCH_LANGUAGE_ALIASES, &key)
+}
+
+#[must_use]
+pub fn ui_language_alias(raw: &str) -> Option<&'static str> {
+    lookup_alias(
+        UI_LANGUAGE_ALIASES,
+        &raw.trim().to_lowercase().replace('_', "-"),
+    )
+}
+
+/// Brave `search_lang` for the query's detected language; always `None` without the
+/// `language-detection` feature.
+#[cfg(feature = "language-detection")]
+#[must_use]
+pub fn detect_query_language(query: &str, country_hint: Option<&str>) -> Option<&'static str> {
+    let info = whatlang::detect(query)?;
+    if info.confidence() < crate::constants::QUERY_LANGUAGE_MIN_CONFIDENCE {
+        return None;
+    }
+    let detected = info.lang().code();
+    if detected == "por" {
+        return search_language_alias("pt", country_hint);
+    }
+    DETECTED_LANGUAGES
+        .iter()
+        .find(|(lang, _, _)| *lang == detected)
+        .map(|(_, code, _)| *code)
+}
+
+#[cfg(not(feature = "language-detection"))]
+#[must_use]
+pub const fn detect_query_language(
+    _query: &str,
+    _country_hint: Option<&str>,
+) -> Option<&'static str> {
+    None
+}
+
+/// Advice for queries Brave is likely to handle poorly: more words than it considers, mostly
+/// stopwords, or operators that contradict each other.
+#[must_use]
+pub fn query_advisories(query: &str) -> Vec<String> {
+    let words = query.split_whitespace().collect::<Vec<&str>>();
+    let mut advisories = Vec::new();
+    if words.len() > QUERY_EFFECTIVE_TERM_LIMIT {
+        advisories.push(format!(
+            "Query has {} words; Brave only considers the first {QUERY_EFFECTIVE_TERM_LIMIT}, so keep the key terms.",
+            words.len()
+        ));
+    }
+
+    let plain = words
+        .iter()
+        .filter(|word| !word.starts_with('-') && !word.contains(':'))
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<String>>();
+    let stopwords = plain
+        .iter()
+        .filter(|word| QUERY_STOPWORDS.contains(&word.as_str()))
+        .count();
+    if plain.len() >= 3 && stopwords as f64 >= plain.len() as f64 * QUERY_STOPWORD_SHARE {
+        advisories.push(format!(
+            "Query is mostly stopwords ({stopwords} of {} words); use distinctive keywords instead.",
+            plain.len()
+        ));
+    }
+
+    for operator in ["site:", "filetype:"] {
+        let count = words
+            .iter()
+            .filter(|word| word.to_lowercase().starts_with(operator))
+            .count();
+        if count > 1 && !words.contains(&"OR") {
+            advisories.push(format!(
+                "Query has {count} {operator} operators, which all must match; join them with OR or keep one."
+            ));
+        }
+    }
+    let excluded = words
+        .iter()
+        .filter_map(|word| word.strip_prefix('-'))
+        .map(str::to_lowercase)
+        .filter(|term| plain.contains(term))
+        .collect::<Vec<String>>();
+    if !excluded.is_empty() {
+        advisories.push(format!(
+            "Query both includes and excludes {}; drop one of them.",
+            excluded.join(", ")
+        ));
+    }
+    advisories
+}
+
+#[must_use]
+pub fn country_for_search_language(search_language: &str) -> Option<&'static str> {
+    match search_language {
+        "pt-br" => Some("BR"),
+        "pt-pt" => Some("PT"),
+        _ => DETECTED_LANGUAGES
+            .iter()
+            .find(|(_, code, _)| *code == search_language)
+            .and_then(|(_, _, country)| *country),
+    }
 }
 
 #[must_use]
@@ -356,23 +898,200 @@ pub fn sanitize_param_for_warning(value: &str) -> String {
         .collect()
 }
 
+/// `text` with every occurrence of `secret`, raw or percent-encoded, replaced by
+/// [`REDACTED_SECRET`].
+#[must_use]
+pub fn redact_secret<'a>(text: &'a str, secret: &str) -> Cow<'a, str> {
+    if secret.chars().count() < MIN_REDACTED_SECRET_CHARS {
+        return Cow::Borrowed(text);
+    }
+    let encoded = url::form_urlencoded::byte_serialize(secret.as_bytes()).collect::<String>();
+    let mut redacted = Cow::Borrowed(text);
+    for form in [secret, encoded.as_str()] {
+        if redacted.contains(form) {
+            redacted = Cow::Owned(redacted.replace(form, REDACTED_SECRET));
+        }
+    }
+    redacted
+}
+
+/// `query` with email addresses and runs of five or more digits (phone, account or card
+/// numbers) masked, for records that should not keep personal data.
+#[must_use]
+pub fn redact_query(query: &str) -> String {
+    static EMAIL_RE: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").expect("valid email regex")
+    });
+    static NUMBER_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"\d[\d -]{2,}\d").expect("valid number regex"));
+    let without_emails = EMAIL_RE.replace_all(query, "[email]");
+    NUMBER_RE
+        .replace_all(&without_emails, |captures: &regex::Captures<'_>| {
+            let digits = captures[0].chars().filter(char::is_ascii_digit).count();
+            if digits >= 5 {
+                "[number]".to_string()
+            } else {
+                captures[0].to_string()
+            }
+        })
+        .into_owned()
+}
+
+/// Dedup key for a URL: scheme and host lowercased, `www.`/`m.`/`amp.` host prefixes, tracking
+/// params, the fragment and trailing slashes removed.
 #[must_use]
 pub fn normalize_url_for_dedup(url: &str) -> String {
     let trimmed = url.trim();
     match url::Url::parse(trimmed) {
         Ok(parsed) => {
             let protocol = parsed.scheme().to_lowercase();
-            let host = parsed
-                .host_str()
-                .map_or_else(String::new, str::to_lowercase);
+            let host = parsed.host_str().map_or_else(String::new, |host| {
+                strip_mirror_host_prefixes(host.to_lowercase())
+            });
             let port = parsed.port().map_or_else(String::new, |p| format!(":{p}"));
             let mut path = parsed.path().to_string();
             while path.ends_with('/') && path.len() > 1 {
                 path.pop();
             }
-            let query = parsed.query().map_or_else(String::new, |q| format!("?{q}"));
+            let query = parsed
+                .query()
+                .and_then(without_tracking_params)
+                .map_or_else(String::new, |q| format!("?{q}"));
             format!("{protocol}://{host}{port}{path}{query}")
         }
         Err(_) => trimmed.to_string(),
     }
 }
+
+/// `url` without well-known tracking query params (`utm_*`, `fbclid`, `gclid`, `ref`, ...);
+/// everything else, including the parameter encoding, is left as is.
+#[must_use]
+pub fn strip_tracking_params(url: &str) -> String {
+    let Ok(mut parsed) = url::Url::parse(url.trim()) else {
+        return url.to_string();
+    };
+    let Some(query) = parsed.query() else {
+        return url.to_string();
+    };
+    let kept = without_tracking_params(query);
+    if kept.as_deref() == Some(query) {
+        return url.to_string();
+    }
+    parsed.set_query(kept.as_deref());
+    parsed.into()
+}
+
+fn is_tracking_param(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    TRACKING_PARAM_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+        || TRACKING_PARAMS.contains(&name.as_str())
+}
+
+/// The raw query string with tracking params dropped; `None` when nothing is left.
+fn without_tracking_params(query: &str) -> Option<String> {
+    let kept = query
+        .split('&')
+        .filter(|pair| {
+            let name = pair.split_once('=').map_or(*pair, |(name, _)| name);
+            !pair.is_empty() && !is_tracking_param(name)
+        })
+        .collect::<Vec<_>>();
+    (!kept.is_empty()).then(|| kept.join("&"))
+}
+
+fn strip_mirror_host_prefixes(mut host: String) -> String {
+    while let Some(prefix) = DEDUP_HOST_PREFIXES.iter().find(|prefix| {
+        host.strip_prefix(*prefix)
+            .is_some_and(|rest| rest.contains('.'))
+    }) {
+        host.drain(..prefix.len());
+    }
+    host
+}
+
+/// Key two results share when `strategy` treats them as duplicates; `None` never matches.
+#[must_use]
+pub fn dedup_key(strategy: DedupStrategy, url: &str, title: &str) -> Option<String> {
+    match strategy {
+        DedupStrategy::Url => Some(normalize_url_for_dedup(url)),
+        DedupStrategy::Host => {
+            Some(normalize_host_for_dedup(url).unwrap_or_else(|| normalize_url_for_dedup(url)))
+        }
+        DedupStrategy::Title => normalize_title_for_dedup(title),
+        DedupStrategy::Off => None,
+    }
+}
+
+/// Lowercased host with mirror prefixes (`www.`, `m.`, `amp.`) removed, so
+/// `https://m.example.com/a` and `https://www.example.com/b` share `example.com`.
+#[must_use]
+pub fn normalize_host_for_dedup(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url.trim()).ok()?;
+    Some(strip_mirror_host_prefixes(
+        parsed.host_str()?.to_lowercase(),
+    ))
+}
+
+/// Drops extra snippets that mostly repeat `primary` or an earlier kept extra snippet.
+///
+/// One is redundant when at least [`SNIPPET_DUPLICATE_OVERLAP`] of its lowercased words already
+/// appear in a kept snippet. At most `max` snippets are returned, in their original order.
+#[must_use]
+pub fn dedupe_extra_snippets(primary: &str, extras: Vec<String>, max: usize) -> Vec<String> {
+    let mut kept_words = vec![snippet_words(primary)];
+    let mut kept = Vec::new();
+    for extra in extras {
+        if kept.len() == max {
+            break;
+        }
+        let words = snippet_words(&extra);
+        if words.is_empty() {
+            continue;
+        }
+        let redundant = kept_words.iter().any(|seen| {
+            let shared = words.intersection(seen).count();
+            shared as f64 >= words.len() as f64 * SNIPPET_DUPLICATE_OVERLAP
+        });
+        if !redundant {
+            kept_words.push(words);
+            kept.push(extra);
+        }
+    }
+    kept
+}
+
+fn snippet_words(text: &str) -> HashSet<String> {
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Fuzzy title key: a short trailing site name (`Headline | Site`) is dropped, then the
+/// lowercased alphanumeric words are sorted, so case, punctuation and word order do not matter.
+#[must_use]
+pub fn normalize_title_for_dedup(title: &str) -> Option<String> {
+    let mut title = title.trim();
+    if let Some((head, tail)) = DEDUP_TITLE_SITE_SEPARATORS
+        .iter()
+        .filter_map(|separator| title.rsplit_once(separator))
+        .max_by_key(|(head, _)| head.len())
+        && tail.split_whitespace().count() <= DEDUP_TITLE_SITE_MAX_WORDS
+        && head.split_whitespace().count() > 1
+    {
+        title = head;
+    }
+
+    let mut words = title
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    if words.is_empty() {
+        return None;
+    }
+    words.sort_unstable();
+    Some(words.join(" "))
+}
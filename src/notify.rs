@@ -0,0 +1,144 @@
+use crate::client::compute_retry_delay_ms;
+use crate::config::{NotifySettings, RuntimeConfig};
+use crate::constants::{NOTIFY_EVENT_HEADER, NOTIFY_SIGNATURE_HEADER, NOTIFY_WEBHOOK_MAX_ATTEMPTS};
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use sha2::Sha256;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Serialize)]
+struct NotificationEnvelope<'a, T> {
+    event: &'a str,
+    id: &'a str,
+    sent_at_unix_secs: u64,
+    data: &'a T,
+}
+
+/// Pushes completed results to a webhook and/or a drop directory.
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    http: reqwest::Client,
+    settings: NotifySettings,
+    per_attempt_timeout: Duration,
+    retry_base_delay_ms: u64,
+    retry_max_delay_ms: u64,
+}
+
+impl Notifier {
+    /// Returns `None` when neither a webhook nor a drop directory is configured.
+    #[must_use]
+    pub fn from_config(config: &RuntimeConfig) -> Option<Self> {
+        if !config.notify.is_enabled() {
+            return None;
+        }
+        Some(Self {
+            http: reqwest::Client::new(),
+            settings: config.notify.clone(),
+            per_attempt_timeout: Duration::from_millis(config.per_attempt_timeout_ms),
+            retry_base_delay_ms: config.retry_base_delay_ms,
+            retry_max_delay_ms: config.retry_max_delay_ms,
+        })
+    }
+
+    /// Delivers `data` to every configured sink; failures are logged, never returned.
+    pub async fn deliver<T: Serialize>(&self, event: &str, id: &str, data: &T) {
+        let envelope = NotificationEnvelope {
+            event,
+            id,
+            sent_at_unix_secs: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            data,
+        };
+        let body = match serde_json::to_vec(&envelope) {
+            Ok(body) => body,
+            Err(error) => {
+                tracing::warn!("failed to serialize {event} notification: {error}");
+                return;
+            }
+        };
+
+        if let Some(dir) = &self.settings.drop_dir {
+            let path = dir.join(format!("{event}-{id}.json"));
+            let temp_path = path.with_extension("json.tmp");
+            let result = std::fs::create_dir_all(dir)
+                .and_then(|()| std::fs::write(&temp_path, &body))
+                .and_then(|()| std::fs::rename(&temp_path, &path));
+            if let Err(error) = result {
+                tracing::warn!(
+                    "failed to write {event} notification to {}: {error}",
+                    path.display()
+                );
+            }
+        }
+
+        if let Some(url) = &self.settings.webhook_url {
+            self.post_webhook(url, event, body).await;
+        }
+    }
+
+    async fn post_webhook(&self, url: &str, event: &str, body: Vec<u8>) {
+        let signature = self
+            .settings
+            .webhook_secret
+            .as_deref()
+            .map(|secret| format!("sha256={}", sign_payload(secret, &body)));
+
+        for attempt in 0..NOTIFY_WEBHOOK_MAX_ATTEMPTS {
+            let mut request = self
+                .http
+                .post(url)
+                .timeout(self.per_attempt_timeout)
+                .header(CONTENT_TYPE, "application/json")
+                .header(NOTIFY_EVENT_HEADER, event)
+                .body(body.clone());
+            if let Some(signature) = &signature {
+                request = request.header(NOTIFY_SIGNATURE_HEADER, signature);
+            }
+
+            let retry_after = match request.send().await {
+                Ok(response) if response.status().is_success() => return,
+                Ok(response) => {
+                    let status = response.status();
+                    if status.is_client_error() && status.as_u16() != 429 {
+                        tracing::warn!("{event} webhook rejected with HTTP {status}");
+                        return;
+                    }
+                    tracing::debug!("{event} webhook attempt {} got HTTP {status}", attempt + 1);
+                    response
+                        .headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string)
+                }
+                Err(error) => {
+                    tracing::debug!("{event} webhook attempt {} failed: {error}", attempt + 1);
+                    None
+                }
+            };
+
+            if attempt + 1 < NOTIFY_WEBHOOK_MAX_ATTEMPTS {
+                let delay_ms = compute_retry_delay_ms(
+                    attempt,
+                    retry_after.as_deref(),
+                    self.retry_base_delay_ms,
+                    self.retry_max_delay_ms,
+                );
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            }
+        }
+        tracing::warn!(
+            "{event} webhook delivery failed after {NOTIFY_WEBHOOK_MAX_ATTEMPTS} attempts"
+        );
+    }
+}
+
+/// Hex-encoded HMAC-SHA256 of `body`, sent as `sha256=<hex>` in the signature header.
+#[must_use]
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(secret.as_bytes())
+        .unwrap_or_else(|_| unreachable!("HMAC accepts keys of any size"));
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
@@ -1,13 +1,18 @@
-use crate::constants::{
-    MAX_EXTRA_SNIPPETS, WARNING_DEDUPLICATED, WARNING_NO_RECOGNIZED_SECTIONS, section_specs_for,
+use crate::brave_schema::{
+    ImageProperties, LocalDescriptionsPayload, LocalPoisPayload, MixedOrder, OpeningHours,
+    PostalAddress, ResultList, SearchPayload, SearchResult,
 };
-use crate::normalization::{clean_text, normalize_url_for_dedup};
+use crate::constants::{IMAGE_FORMATS, MAX_EXTRA_SNIPPETS, SECTION_RANKED, section_specs_for};
+use crate::normalization::{clean_text, dedup_key, dedupe_extra_snippets, normalize_url_for_dedup};
 use crate::types::{
-    BraveSectionName, NormalizedResult, ParseSectionsResult, ParsedSection, SearchType,
-    WarningEntry, WebResultFilter,
+    BraveSectionName, DedupStrategy, DuplicateDecision, ImageResultItem, LocalResultItem,
+    NormalizedResult, ParseOptions, ParseSectionsResult, ParsedSection, ParsedSummary, ResultOrder,
+    SearchType, SummarySource, WarningCode, WarningEntry, WebResultFilter,
 };
+use serde::Deserialize;
 use serde_json::{Map, Value};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
 
 const MAX_ERROR_DETAIL_LENGTH: usize = 500;
 
@@ -70,6 +75,38 @@ pub fn parse_brave_error_message(payload_text: &str, fallback: &str) -> String {
     fallback.to_string()
 }
 
+/// Brave's machine-readable error `code` (falling back to the top-level `type`) and the request
+/// parameters named by validation errors (`error.meta.errors[].loc`).
+#[must_use]
+pub fn parse_brave_error_code(payload_text: &str) -> (Option<String>, Vec<String>) {
+    let Ok(payload) = serde_json::from_str::<Value>(payload_text) else {
+        return (None, Vec::new());
+    };
+    let error = payload.get("error");
+    let code = error
+        .and_then(|error| error.get("code"))
+        .or_else(|| payload.get("type").filter(|_| error.is_none()))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let mut params = Vec::<String>::new();
+    for entry in to_objects(
+        error
+            .and_then(|error| error.get("meta"))
+            .and_then(|meta| meta.get("errors")),
+    ) {
+        if let Some(param) = entry
+            .get("loc")
+            .and_then(Value::as_array)
+            .and_then(|loc| loc.last())
+            .and_then(Value::as_str)
+            && !params.iter().any(|known| known == param)
+        {
+            params.push(param.to_string());
+        }
+    }
+    (code, params)
+}
+
 fn to_objects(value: Option<&Value>) -> Vec<&Map<String, Value>> {
     value
         .and_then(Value::as_array)
@@ -78,76 +115,72 @@ fn to_objects(value: Option<&Value>) -> Vec<&Map<String, Value>> {
         })
 }
 
-fn collect_raw_results(payload: &Value, section: BraveSectionName) -> Vec<&Map<String, Value>> {
+fn collect_raw_results(payload: &SearchPayload, section: BraveSectionName) -> &[SearchResult] {
     match section {
-        BraveSectionName::Web => to_objects(payload.get("web").and_then(|v| v.get("results"))),
-        BraveSectionName::Discussions => {
-            to_objects(payload.get("discussions").and_then(|v| v.get("results")))
-        }
-        BraveSectionName::Infobox => {
-            to_objects(payload.get("infobox").and_then(|v| v.get("results")))
-        }
-        BraveSectionName::Videos => {
-            let nested = to_objects(payload.get("videos").and_then(|v| v.get("results")));
-            if !nested.is_empty() {
-                return nested;
-            }
-            if payload.get("type").and_then(Value::as_str) == Some("videos") {
-                return to_objects(payload.get("results"));
-            }
-            Vec::new()
-        }
-        BraveSectionName::News => {
-            let nested = to_objects(payload.get("news").and_then(|v| v.get("results")));
-            if !nested.is_empty() {
-                return nested;
-            }
-            if payload.get("type").and_then(Value::as_str) == Some("news") {
-                return to_objects(payload.get("results"));
-            }
-            Vec::new()
-        }
-        BraveSectionName::Images => {
-            let nested = to_objects(payload.get("images").and_then(|v| v.get("results")));
-            if !nested.is_empty() {
-                return nested;
-            }
-            if payload.get("type").and_then(Value::as_str) == Some("images") {
-                return to_objects(payload.get("results"));
-            }
-            Vec::new()
-        }
+        BraveSectionName::Web => nested_results(payload.web.as_ref()),
+        BraveSectionName::Discussions => nested_results(payload.discussions.as_ref()),
+        BraveSectionName::Infobox => nested_results(payload.infobox.as_ref()),
+        BraveSectionName::Locations => nested_results(payload.locations.as_ref()),
+        BraveSectionName::Faq => nested_results(payload.faq.as_ref()),
+        BraveSectionName::Videos => standalone_results(payload, section, payload.videos.as_ref()),
+        BraveSectionName::News => standalone_results(payload, section, payload.news.as_ref()),
+        BraveSectionName::Images => standalone_results(payload, section, payload.images.as_ref()),
+        BraveSectionName::Mixed => &[],
+    }
+}
+
+fn nested_results(list: Option<&ResultList>) -> &[SearchResult] {
+    list.map_or(&[], |list| list.results.as_slice())
+}
+
+/// News, images and videos come nested in web payloads but top-level from their own endpoints.
+fn standalone_results<'a>(
+    payload: &'a SearchPayload,
+    section: BraveSectionName,
+    list: Option<&'a ResultList>,
+) -> &'a [SearchResult] {
+    let nested = nested_results(list);
+    if nested.is_empty() && payload.payload_type.as_deref() == Some(section.as_str()) {
+        &payload.results
+    } else {
+        nested
     }
 }
 
 fn to_clean_string(value: Option<&Value>) -> Option<String> {
     value.and_then(|v| match v {
-        Value::String(text) => {
-            let cleaned = clean_text(text, false);
-            (!cleaned.is_empty()).then_some(cleaned)
-        }
-        Value::Number(number) => {
-            let cleaned = clean_text(&number.to_string(), false);
-            (!cleaned.is_empty()).then_some(cleaned)
-        }
+        Value::String(text) => clean_field(Some(text)),
+        Value::Number(number) => clean_field(Some(&number.to_string())),
         _ => None,
     })
 }
 
+fn clean_field(value: Option<&str>) -> Option<String> {
+    let cleaned = clean_text(value?, false);
+    (!cleaned.is_empty()).then_some(cleaned)
+}
+
 fn normalize_result(
-    item: &Map<String, Value>,
+    item: &SearchResult,
     source: BraveSectionName,
+    provider_rank: usize,
     preserve_decorations: bool,
+    dedupe_snippets: bool,
 ) -> Option<NormalizedResult> {
-    let title = clean_text(
-        item.get("title")
-            .and_then(Value::as_str)
-            .unwrap_or_default(),
-        preserve_decorations,
-    );
+    // FAQ entries read as the question, answered by the snippet.
+    let (title, primary_snippet) = if source == BraveSectionName::Faq {
+        (item.question.as_deref(), item.answer.as_deref())
+    } else {
+        (
+            item.title.as_deref(),
+            item.description.as_deref().or(item.snippet.as_deref()),
+        )
+    };
+    let title = clean_text(title.unwrap_or_default(), preserve_decorations);
     let url = item
-        .get("url")
-        .and_then(Value::as_str)
+        .url
+        .as_deref()
+        .or(item.provider_url.as_deref())
         .map(str::trim)
         .unwrap_or_default()
         .to_string();
@@ -156,56 +189,67 @@ fn normalize_result(
         return None;
     }
 
-    let primary_snippet = item
-        .get("description")
-        .and_then(Value::as_str)
-        .or_else(|| item.get("snippet").and_then(Value::as_str))
-        .unwrap_or_default();
-
-    let mut extra_snippets = Vec::new();
-    if let Some(snippets) = item.get("extra_snippets").and_then(Value::as_array) {
-        for snippet in snippets.iter().take(MAX_EXTRA_SNIPPETS) {
-            if let Some(text) = snippet.as_str() {
-                let cleaned = clean_text(text, preserve_decorations);
-                if !cleaned.is_empty() {
-                    extra_snippets.push(cleaned);
-                }
-            }
-        }
-    }
+    let snippet = clean_text(primary_snippet.unwrap_or_default(), preserve_decorations);
 
-    let snippet = clean_text(primary_snippet, preserve_decorations);
+    let cleaned_extras = item
+        .extra_snippets
+        .iter()
+        .map(|text| clean_text(text, preserve_decorations))
+        .filter(|cleaned| !cleaned.is_empty());
+    let extra_snippets = if dedupe_snippets {
+        dedupe_extra_snippets(&snippet, cleaned_extras.collect(), MAX_EXTRA_SNIPPETS)
+    } else {
+        cleaned_extras.take(MAX_EXTRA_SNIPPETS).collect()
+    };
 
     let source_name = item
-        .get("profile")
-        .and_then(Value::as_object)
+        .profile
+        .as_ref()
         .and_then(|profile| {
-            to_clean_string(profile.get("name"))
-                .or_else(|| to_clean_string(profile.get("long_name")))
+            clean_field(profile.name.as_deref())
+                .or_else(|| clean_field(profile.long_name.as_deref()))
         })
-        .or_else(|| to_clean_string(item.get("source")))
-        .or_else(|| to_clean_string(item.get("source_name")));
-
-    let age = to_clean_string(item.get("age"));
-    let published = to_clean_string(item.get("page_age"));
-    let item_type = to_clean_string(item.get("type")).filter(|value| value != "search_result");
-    let subtype = to_clean_string(item.get("subtype"));
-
-    let (duration, creator) = if source == BraveSectionName::Videos {
-        let video_obj = item.get("video").and_then(Value::as_object);
+        .or_else(|| clean_field(item.source.as_deref()))
+        .or_else(|| clean_field(item.source_name.as_deref()));
+
+    let age = clean_field(item.age.as_deref());
+    let published = clean_field(item.page_age.as_deref());
+    let item_type =
+        clean_field(item.result_type.as_deref()).filter(|value| value != "search_result");
+    let subtype = clean_field(item.subtype.as_deref());
+
+    let news_details = source == BraveSectionName::News;
+    let video = item
+        .video
+        .as_ref()
+        .filter(|_| source == BraveSectionName::Videos);
+    let duration = video.and_then(|video| clean_field(video.duration.as_deref()));
+    let creator = video.and_then(|video| clean_field(video.creator.as_deref()));
+    let video_publisher = video.and_then(|video| clean_field(video.publisher.as_deref()));
+    let views = video.and_then(|video| parse_view_count(video.views.as_deref()?));
+    let embeddable = video.and_then(|video| video.embeddable);
+
+    let (location, poi_id) = if source == BraveSectionName::Locations {
         (
-            video_obj.and_then(|video| to_clean_string(video.get("duration"))),
-            video_obj.and_then(|video| to_clean_string(video.get("creator"))),
+            parse_display_address(item.postal_address.as_ref()),
+            clean_field(item.id.as_deref()),
         )
     } else {
-        (None, None)
+        (clean_field(item.location.as_deref()), None)
+    };
+    let thumbnail = item
+        .thumbnail
+        .as_ref()
+        .or_else(|| video.and_then(|video| video.thumbnail.as_ref()))
+        .and_then(|thumbnail| thumbnail.src.as_deref())
+        .map(str::trim)
+        .filter(|src| is_http_url(src))
+        .map(str::to_string);
+    let image = if source == BraveSectionName::Images {
+        parse_image_properties(item.properties.as_ref())
+    } else {
+        None
     };
-
-    let location = to_clean_string(item.get("location"));
-    let is_live = item
-        .get("is_live")
-        .and_then(Value::as_bool)
-        .unwrap_or(false);
 
     Some(NormalizedResult {
         title,
@@ -219,27 +263,132 @@ fn normalize_result(
         subtype,
         duration,
         creator,
+        publisher: video_publisher,
+        views,
+        embeddable,
         location,
-        is_live,
+        is_live: item.is_live,
+        breaking: news_details && item.breaking,
+        hostname: news_details
+            .then(|| clean_field(item.meta_url.as_ref()?.hostname.as_deref()))
+            .flatten(),
+        thumbnail,
+        poi_id,
+        local: None,
+        image,
+        origin_section: None,
+        provider_rank,
     })
 }
 
-fn parse_query_original(payload: &Value) -> Option<String> {
-    payload
-        .get("query")
-        .and_then(Value::as_object)
-        .and_then(|query| query.get("original"))
-        .and_then(Value::as_str)
-        .map(str::to_string)
+/// Accepts plain and digit-grouped counts (`1234`, `1,234`, `1 234`).
+fn parse_view_count(raw: &str) -> Option<u64> {
+    let digits = raw
+        .trim()
+        .chars()
+        .filter(|character| !matches!(character, ',' | '.' | ' ' | '_'))
+        .collect::<String>();
+    digits.parse().ok()
 }
 
-fn parse_more_results_available(payload: &Value) -> bool {
-    payload
-        .get("query")
-        .and_then(Value::as_object)
-        .and_then(|query| query.get("more_results_available"))
-        .and_then(Value::as_bool)
-        .unwrap_or(false)
+fn is_http_url(value: &str) -> bool {
+    value.starts_with("https://") || value.starts_with("http://")
+}
+
+fn parse_image_properties(properties: Option<&ImageProperties>) -> Option<ImageResultItem> {
+    let properties = properties?;
+    let url = properties
+        .url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| is_http_url(url))?;
+    let format = clean_field(properties.format.as_deref())
+        .or_else(|| {
+            url::Url::parse(url).ok().and_then(|parsed| {
+                parsed
+                    .path()
+                    .rsplit_once('.')
+                    .map(|(_, extension)| extension.to_string())
+            })
+        })
+        .map(|format| format.to_lowercase())
+        .map(|format| {
+            if format == "jpg" {
+                "jpeg".to_string()
+            } else {
+                format
+            }
+        })
+        .filter(|format| IMAGE_FORMATS.contains(&format.as_str()));
+
+    Some(ImageResultItem {
+        url: url.to_string(),
+        width: properties.width.filter(|width| *width > 0),
+        height: properties.height.filter(|height| *height > 0),
+        format,
+    })
+}
+
+fn parse_display_address(address: Option<&PostalAddress>) -> Option<String> {
+    clean_field(address?.display_address.as_deref())
+}
+
+fn parse_opening_hours(hours: Option<&OpeningHours>) -> Vec<String> {
+    hours
+        .into_iter()
+        .flat_map(|hours| &hours.days)
+        .flatten()
+        .filter_map(|slot| {
+            let day = clean_field(slot.abbr_name.as_deref())
+                .or_else(|| clean_field(slot.full_name.as_deref()))?;
+            let opens = clean_field(slot.opens.as_deref())?;
+            let closes = clean_field(slot.closes.as_deref())?;
+            Some(format!("{day} {opens}-{closes}"))
+        })
+        .collect()
+}
+
+#[must_use]
+pub fn parse_local_pois(payload: &Value) -> HashMap<String, LocalResultItem> {
+    LocalPoisPayload::deserialize(payload)
+        .unwrap_or_default()
+        .results
+        .into_iter()
+        .filter_map(|poi| {
+            let id = clean_field(poi.id.as_deref())?;
+            let rating = poi.rating.as_ref();
+            Some((
+                id.clone(),
+                LocalResultItem {
+                    id,
+                    address: parse_display_address(poi.postal_address.as_ref()),
+                    phone: poi
+                        .contact
+                        .as_ref()
+                        .and_then(|contact| clean_field(contact.telephone.as_deref())),
+                    rating: rating.and_then(|rating| rating.rating_value),
+                    review_count: rating.and_then(|rating| rating.review_count),
+                    opening_hours: parse_opening_hours(poi.opening_hours.as_ref()),
+                    description: None,
+                },
+            ))
+        })
+        .collect()
+}
+
+#[must_use]
+pub fn parse_local_descriptions(payload: &Value) -> HashMap<String, String> {
+    LocalDescriptionsPayload::deserialize(payload)
+        .unwrap_or_default()
+        .results
+        .into_iter()
+        .filter_map(|entry| {
+            Some((
+                clean_field(entry.id.as_deref())?,
+                clean_field(entry.description.as_deref())?,
+            ))
+        })
+        .collect()
 }
 
 #[must_use]
@@ -250,6 +399,36 @@ pub fn parse_sections(
     requested: usize,
     preserve_decorations: bool,
 ) -> ParseSectionsResult {
+    parse_sections_with_options(
+        payload,
+        search_type,
+        result_filter_values,
+        requested,
+        preserve_decorations,
+        ParseOptions::default(),
+    )
+}
+
+/// [`parse_sections`] with a choice of layout and dedup strategy.
+///
+/// [`ResultOrder::Ranked`] folds the sections into a single `mixed` section that follows Brave's
+/// `mixed.main` ordering; web payloads without that ordering keep their grouped sections and
+/// report `RANKED_ORDER_UNAVAILABLE`. `options.dedup` decides which later results are dropped as
+/// duplicates of an earlier one, and `options.dedupe_snippets` drops repetitive extra snippets.
+#[must_use]
+pub fn parse_sections_with_options(
+    payload: &Value,
+    search_type: SearchType,
+    result_filter_values: &[WebResultFilter],
+    requested: usize,
+    preserve_decorations: bool,
+    options: ParseOptions,
+) -> ParseSectionsResult {
+    let ParseOptions {
+        order,
+        dedup,
+        dedupe_snippets,
+    } = options;
     let normalized_filters = if result_filter_values.is_empty() {
         vec![WebResultFilter::Web]
     } else {
@@ -266,16 +445,38 @@ pub fn parse_sections(
                 WebResultFilter::Videos => BraveSectionName::Videos,
                 WebResultFilter::News => BraveSectionName::News,
                 WebResultFilter::Infobox => BraveSectionName::Infobox,
+                WebResultFilter::Locations => BraveSectionName::Locations,
+                WebResultFilter::Faq => BraveSectionName::Faq,
             })
             .collect()
     } else {
         vec![configured[0].1]
     };
 
+    let raw_payload = payload;
+    let payload = SearchPayload::from_value(payload);
+    let more_available = payload.more_results_available();
     let mut warnings = Vec::<WarningEntry>::new();
     let mut sections = Vec::<ParsedSection>::new();
-    let mut seen_url_keys = HashSet::<String>::new();
-    let mut duplicate_count = 0usize;
+    let mut seen_keys = HashMap::<String, (BraveSectionName, usize)>::new();
+    let mut duplicates = Vec::<DuplicateDecision>::new();
+    let mixed = if order == ResultOrder::Ranked && search_type == SearchType::Web {
+        let mixed = payload
+            .mixed
+            .as_ref()
+            .filter(|mixed| !mixed.main.is_empty());
+        if mixed.is_none() {
+            warnings.push(WarningEntry {
+                code: WarningCode::RankedOrderUnavailable,
+                message: "Brave returned no mixed ranking; results stay grouped by section."
+                    .to_string(),
+            });
+        }
+        mixed
+    } else {
+        None
+    };
+    let mut ranked_pools = Vec::<(BraveSectionName, Vec<NormalizedResult>)>::new();
 
     for section_name in allowed_sections {
         let Some(section_spec) = configured
@@ -285,28 +486,59 @@ pub fn parse_sections(
             continue;
         };
 
-        let raw = collect_raw_results(payload, section_name);
+        if let Some(problem) = section_parse_failure(raw_payload, section_name) {
+            warnings.push(WarningEntry {
+                code: WarningCode::SectionParseFailed,
+                message: format!(
+                    "Section '{}' is malformed ({problem}); kept whatever parsed.",
+                    section_name.as_str()
+                ),
+            });
+        }
+        let raw = collect_raw_results(&payload, section_name);
         let parsed: Vec<NormalizedResult> = raw
-            .into_iter()
-            .filter_map(|entry| normalize_result(entry, section_name, preserve_decorations))
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                normalize_result(
+                    entry,
+                    section_name,
+                    index + 1,
+                    preserve_decorations,
+                    dedupe_snippets,
+                )
+            })
             .collect();
 
         let mut unique = Vec::<NormalizedResult>::new();
         for result in parsed {
-            let dedup_key = normalize_url_for_dedup(&result.url);
-            if seen_url_keys.contains(&dedup_key) {
-                duplicate_count += 1;
+            let Some(key) = dedup_key(dedup, &result.url, &result.title) else {
+                unique.push(result);
+                continue;
+            };
+            if let Some((kept_section, kept_rank)) = seen_keys.get(&key) {
+                duplicates.push(DuplicateDecision {
+                    kept_section: *kept_section,
+                    kept_rank: *kept_rank,
+                    removed_section: section_name,
+                    removed_rank: result.provider_rank,
+                    removed_url: result.url,
+                });
                 continue;
             }
-            seen_url_keys.insert(dedup_key);
+            seen_keys.insert(key, (section_name, result.provider_rank));
             unique.push(result);
         }
 
+        if mixed.is_some() {
+            ranked_pools.push((section_name, unique));
+            continue;
+        }
+
         let limited = unique
             .into_iter()
             .take(requested)
             .collect::<Vec<NormalizedResult>>();
-        let more_available = parse_more_results_available(payload);
         let section_limit_reached = limited.len() == requested && more_available;
 
         sections.push(ParsedSection {
@@ -318,9 +550,25 @@ pub fn parse_sections(
         });
     }
 
+    if let Some(mixed) = mixed {
+        let ranked = interleave_by_mixed(mixed, ranked_pools)
+            .into_iter()
+            .take(requested)
+            .collect::<Vec<NormalizedResult>>();
+        if !ranked.is_empty() {
+            sections.push(ParsedSection {
+                key: BraveSectionName::Mixed,
+                label: SECTION_RANKED.to_string(),
+                provider: BraveSectionName::Mixed.as_str().to_string(),
+                section_limit_reached: ranked.len() == requested && more_available,
+                results: ranked,
+            });
+        }
+    }
+
     if sections.is_empty() {
         warnings.push(WarningEntry {
-            code: WARNING_NO_RECOGNIZED_SECTIONS.to_string(),
+            code: WarningCode::NoRecognizedSections,
             message: format!(
                 "No recognized result sections for search_type '{}'.",
                 search_type.as_str()
@@ -328,19 +576,25 @@ pub fn parse_sections(
         });
     }
 
+    let duplicate_count = duplicates.len();
     if duplicate_count > 0 {
         warnings.push(WarningEntry {
-            code: WARNING_DEDUPLICATED.to_string(),
+            code: WarningCode::Deduplicated,
             message: format!(
-                "Deduplicated {duplicate_count} duplicate result{} across sections by URL.",
-                if duplicate_count == 1 { "" } else { "s" }
+                "Deduplicated {duplicate_count} duplicate result{} across sections by {}.",
+                if duplicate_count == 1 { "" } else { "s" },
+                if dedup == DedupStrategy::Url {
+                    "URL"
+                } else {
+                    dedup.as_str()
+                }
             ),
         });
     }
 
     let has_renderable_results = sections.iter().any(|section| !section.results.is_empty());
     let has_more = has_renderable_results
-        && (parse_more_results_available(payload)
+        && (more_available
             || sections.iter().any(|section| {
                 section.section_limit_reached && section.results.len() == requested
             }));
@@ -349,10 +603,188 @@ pub fn parse_sections(
         sections,
         has_more,
         warnings,
+        duplicates,
+    }
+}
+
+/// What is wrong with `section` in the raw payload, if anything the lenient schema would skip.
+/// Standalone news/images/videos payloads carry their results at the top level.
+fn section_parse_failure(payload: &Value, section: BraveSectionName) -> Option<String> {
+    let list = match payload.get(section.as_str()) {
+        Some(Value::Null) => return None,
+        Some(list) => list,
+        None if payload.get("type").and_then(Value::as_str) == Some(section.as_str()) => payload,
+        None => return None,
+    };
+    let Some(list) = list.as_object() else {
+        return Some(format!("expected an object, got {}", json_kind(list)));
+    };
+    let results = match list.get("results") {
+        None | Some(Value::Null) => return None,
+        Some(Value::Array(results)) => results,
+        Some(other) => {
+            return Some(format!(
+                "`results` is {} instead of an array",
+                json_kind(other)
+            ));
+        }
+    };
+    let skipped = results.iter().filter(|result| !result.is_object()).count();
+    (skipped > 0).then(|| format!("{skipped} of {} results are not objects", results.len()))
+}
+
+const fn json_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
     }
 }
 
+/// Orders the per-section results by the `mixed.main` slots, tagging each with its section.
+/// Results no slot places (such as a sidebar infobox) follow in section order.
+fn interleave_by_mixed(
+    mixed: &MixedOrder,
+    pools: Vec<(BraveSectionName, Vec<NormalizedResult>)>,
+) -> Vec<NormalizedResult> {
+    let mut pools = pools
+        .into_iter()
+        .map(|(section, results)| {
+            let slots = results
+                .into_iter()
+                .map(|mut result| {
+                    result.origin_section = Some(section);
+                    Some(result)
+                })
+                .collect::<Vec<_>>();
+            (section, slots)
+        })
+        .collect::<Vec<_>>();
+
+    let mut ranked = Vec::<NormalizedResult>::new();
+    for entry in &mixed.main {
+        let Some(slots) = pools
+            .iter_mut()
+            .find(|(section, _)| entry.section.as_deref() == Some(section.as_str()))
+            .map(|(_, slots)| slots)
+        else {
+            continue;
+        };
+        if entry.all {
+            ranked.extend(slots.iter_mut().filter_map(Option::take));
+        } else if let Some(index) = entry.index
+            && let Some(slot) = slots.iter_mut().find(|slot| {
+                slot.as_ref()
+                    .is_some_and(|result| result.provider_rank == index + 1)
+            })
+        {
+            ranked.extend(slot.take());
+        }
+    }
+    ranked.extend(pools.into_iter().flat_map(|(_, slots)| slots).flatten());
+    ranked
+}
+
 #[must_use]
 pub fn query_echo_or_original(payload: &Value, fallback_query: &str) -> String {
-    parse_query_original(payload).unwrap_or_else(|| fallback_query.to_string())
+    SearchPayload::from_value(payload)
+        .query
+        .and_then(|query| query.original)
+        .unwrap_or_else(|| fallback_query.to_string())
+}
+
+#[must_use]
+pub fn parse_summarizer_key(payload: &Value) -> Option<String> {
+    payload
+        .get("summarizer")
+        .and_then(|summarizer| summarizer.get("key"))
+        .and_then(Value::as_str)
+        .filter(|key| !key.trim().is_empty())
+        .map(str::to_string)
+}
+
+#[must_use]
+pub fn parse_summarizer(payload: &Value) -> ParsedSummary {
+    let status = payload
+        .get("status")
+        .and_then(Value::as_str)
+        .unwrap_or("complete")
+        .to_lowercase();
+
+    let mut sources = Vec::<SummarySource>::new();
+    let mut seen_urls = HashSet::<String>::new();
+    let contexts = payload
+        .get("enrichments")
+        .and_then(|enrichments| enrichments.get("context"));
+    for context in to_objects(contexts) {
+        let Some(url) = to_clean_string(context.get("url")) else {
+            continue;
+        };
+        if !seen_urls.insert(normalize_url_for_dedup(&url)) {
+            continue;
+        }
+        sources.push(SummarySource {
+            title: to_clean_string(context.get("title")).unwrap_or_else(|| url.clone()),
+            url,
+        });
+    }
+
+    let mut text = String::new();
+    for token in payload
+        .get("summary")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        match token.get("type").and_then(Value::as_str) {
+            Some("token") => {
+                if let Some(data) = token.get("data").and_then(Value::as_str) {
+                    text.push_str(data);
+                }
+            }
+            Some("inline_reference") => {
+                let url = token
+                    .get("data")
+                    .and_then(|data| data.get("url"))
+                    .and_then(Value::as_str)
+                    .map(normalize_url_for_dedup);
+                if let Some(position) = url.and_then(|url| {
+                    sources
+                        .iter()
+                        .position(|source| normalize_url_for_dedup(&source.url) == url)
+                }) {
+                    let _ = write!(text, "[{}]", position + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    ParsedSummary {
+        status,
+        title: to_clean_string(payload.get("title")),
+        text: clean_text(&text, false),
+        sources,
+    }
+}
+
+#[must_use]
+pub fn parse_suggestions(payload: &Value, limit: usize) -> Vec<String> {
+    let mut seen = HashSet::<String>::new();
+    to_objects(payload.get("results"))
+        .into_iter()
+        .filter_map(|result| to_clean_string(result.get("query")))
+        .filter(|suggestion| seen.insert(suggestion.to_lowercase()))
+        .take(limit)
+        .collect()
+}
+
+#[must_use]
+pub fn parse_spellcheck(payload: &Value) -> Option<String> {
+    to_objects(payload.get("results"))
+        .into_iter()
+        .find_map(|result| to_clean_string(result.get("query")))
 }
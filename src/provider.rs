@@ -0,0 +1,147 @@
+use crate::client::BraveClient;
+use crate::config::ApiKeyConfig;
+use crate::constants::PROVIDER_NAME;
+use crate::error::AppError;
+use crate::types::{FetchSearchParams, FetchSearchResult, KeyState, MirrorStats, SearchType};
+
+/// Search backend behind [`crate::service::SearchService`].
+///
+/// Only `fetch_search` and `probe` are required; spellcheck and thumbnail inlining fall back to
+/// "not available" so engines without those endpoints degrade gracefully.
+#[allow(async_fn_in_trait)]
+pub trait SearchProvider {
+    fn provider_name(&self) -> &'static str;
+
+    fn key_config(&self) -> ApiKeyConfig;
+
+    async fn fetch_search<F>(
+        &self,
+        query: &str,
+        search_type: SearchType,
+        params: &FetchSearchParams,
+        is_cancelled: F,
+    ) -> Result<FetchSearchResult, AppError>
+    where
+        F: Fn() -> bool;
+
+    async fn probe<F>(&self, search_type: SearchType, is_cancelled: F) -> Result<(), AppError>
+    where
+        F: Fn() -> bool;
+
+    async fn fetch_spellcheck<F>(
+        &self,
+        _query: &str,
+        _country: Option<&str>,
+        _is_cancelled: F,
+    ) -> Result<Option<String>, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        Ok(None)
+    }
+
+    async fn fetch_thumbnail<F>(
+        &self,
+        _thumbnail_url: &str,
+        _max_bytes: usize,
+        _is_cancelled: F,
+    ) -> Result<String, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        Err(AppError::Upstream(format!(
+            "{} does not support thumbnail inlining",
+            self.provider_name()
+        )))
+    }
+
+    /// Shadow-traffic comparison counters, for providers that mirror requests.
+    fn mirror_stats(&self) -> Option<MirrorStats> {
+        None
+    }
+
+    fn key_state(&self) -> KeyState {
+        KeyState::default()
+    }
+
+    /// Overridden search endpoints that keep failing while the default endpoint answers.
+    fn misconfigured_endpoints(&self) -> Vec<SearchType> {
+        Vec::new()
+    }
+
+    /// Swaps in a freshly loaded API key and clears any verified/revoked state. `None` means the
+    /// provider cannot reload its key at runtime.
+    fn reload_key(&self) -> Option<ApiKeyConfig> {
+        None
+    }
+}
+
+impl SearchProvider for BraveClient {
+    fn provider_name(&self) -> &'static str {
+        PROVIDER_NAME
+    }
+
+    fn key_config(&self) -> ApiKeyConfig {
+        Self::key_config(self)
+    }
+
+    async fn fetch_search<F>(
+        &self,
+        query: &str,
+        search_type: SearchType,
+        params: &FetchSearchParams,
+        is_cancelled: F,
+    ) -> Result<FetchSearchResult, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        Self::fetch_search(self, query, search_type, params, is_cancelled).await
+    }
+
+    async fn probe<F>(&self, search_type: SearchType, is_cancelled: F) -> Result<(), AppError>
+    where
+        F: Fn() -> bool,
+    {
+        self.probe_endpoint(search_type, is_cancelled).await
+    }
+
+    async fn fetch_spellcheck<F>(
+        &self,
+        query: &str,
+        country: Option<&str>,
+        is_cancelled: F,
+    ) -> Result<Option<String>, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        Self::fetch_spellcheck(self, query, country, is_cancelled).await
+    }
+
+    async fn fetch_thumbnail<F>(
+        &self,
+        thumbnail_url: &str,
+        max_bytes: usize,
+        is_cancelled: F,
+    ) -> Result<String, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        Self::fetch_thumbnail(self, thumbnail_url, max_bytes, is_cancelled).await
+    }
+
+    fn mirror_stats(&self) -> Option<MirrorStats> {
+        Self::mirror_stats(self)
+    }
+
+    fn key_state(&self) -> KeyState {
+        Self::key_state(self)
+    }
+
+    fn misconfigured_endpoints(&self) -> Vec<SearchType> {
+        Self::misconfigured_endpoints(self)
+    }
+
+    fn reload_key(&self) -> Option<ApiKeyConfig> {
+        Some(Self::reload_key(self))
+    }
+}
@@ -0,0 +1,110 @@
+//! Optional re-ranking of parsed results, applied before output limits trim them so the most
+//! relevant results are the ones that survive.
+
+use crate::types::{NormalizedResult, ParsedSection, RerankMode};
+use std::collections::HashMap;
+
+/// BM25 term-frequency saturation.
+const BM25_K1: f64 = 1.2;
+/// BM25 document-length normalization.
+const BM25_B: f64 = 0.75;
+
+/// Reorders the results of each section in place. Ties keep Brave's order.
+pub fn rerank_sections(sections: &mut [ParsedSection], mode: RerankMode, query: &str) {
+    for section in sections {
+        match mode {
+            RerankMode::None => {}
+            RerankMode::Keyword => rerank_by_keywords(&mut section.results, query),
+            RerankMode::Recency => section
+                .results
+                .sort_by(|a, b| recency_key(b).cmp(&recency_key(a))),
+        }
+    }
+}
+
+/// Lowercased alphanumeric words of `text`.
+#[must_use]
+pub fn keyword_terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Query words to score against, skipping `site:`-style operators and `-excluded` terms.
+fn query_terms(query: &str) -> Vec<String> {
+    let mut terms = query
+        .split_whitespace()
+        .filter(|word| !word.starts_with('-') && !word.contains(':'))
+        .flat_map(keyword_terms)
+        .collect::<Vec<String>>();
+    terms.sort_unstable();
+    terms.dedup();
+    terms
+}
+
+/// Title words count twice, so a match in the title outweighs one in a snippet.
+fn document_terms(result: &NormalizedResult) -> Vec<String> {
+    let title = keyword_terms(&result.title);
+    let mut terms = title.clone();
+    terms.extend(title);
+    terms.extend(keyword_terms(&result.snippet));
+    for extra in &result.extra_snippets {
+        terms.extend(keyword_terms(extra));
+    }
+    terms
+}
+
+fn rerank_by_keywords(results: &mut Vec<NormalizedResult>, query: &str) {
+    let terms = query_terms(query);
+    if terms.is_empty() || results.len() < 2 {
+        return;
+    }
+    let documents = results.iter().map(document_terms).collect::<Vec<_>>();
+    let count = documents.len() as f64;
+    let average_len = (documents.iter().map(Vec::len).sum::<usize>() as f64 / count).max(1.0);
+    let weights = terms
+        .iter()
+        .map(|term| {
+            let containing = documents
+                .iter()
+                .filter(|document| document.contains(term))
+                .count() as f64;
+            (
+                term.as_str(),
+                ((count - containing + 0.5) / (containing + 0.5)).ln_1p(),
+            )
+        })
+        .collect::<HashMap<&str, f64>>();
+
+    let mut scored = documents
+        .iter()
+        .map(|document| {
+            let length_norm = 1.0 - BM25_B + BM25_B * document.len() as f64 / average_len;
+            weights
+                .iter()
+                .map(|(term, idf)| {
+                    let frequency = document.iter().filter(|word| word == term).count() as f64;
+                    idf * frequency * (BM25_K1 + 1.0) / (frequency + BM25_K1 * length_norm)
+                })
+                .sum::<f64>()
+        })
+        .zip(results.drain(..))
+        .collect::<Vec<(f64, NormalizedResult)>>();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    results.extend(scored.into_iter().map(|(_, result)| result));
+}
+
+/// `page_age` when it starts with an ISO date, which then sorts chronologically as text; results
+/// without one sort last.
+fn recency_key(result: &NormalizedResult) -> Option<&str> {
+    let published = result.published.as_deref()?.trim();
+    let date = published.as_bytes().get(..10)?;
+    date.iter()
+        .enumerate()
+        .all(|(index, byte)| match index {
+            4 | 7 => *byte == b'-',
+            _ => byte.is_ascii_digit(),
+        })
+        .then_some(published)
+}
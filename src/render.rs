@@ -0,0 +1,403 @@
+use crate::constants::DEFAULT_RESPONSE_FORMAT;
+use crate::formatting::build_response_payload;
+use crate::types::{BraveSectionName, SearchResponse, SearchResultItem};
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Turns a [`SearchResponse`] into the text returned to the caller for one `format` value.
+///
+/// Output limits are measured against the rendered text, so a renderer should be deterministic
+/// for a given response.
+pub trait ResponseRenderer: Send + Sync {
+    fn format(&self) -> &'static str;
+    fn render(&self, response: &SearchResponse) -> Result<String, serde_json::Error>;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct JsonRenderer;
+
+/// Single-line JSON for the `compact` argument; it shares the `json` format rather than being
+/// registered as a format of its own.
+#[derive(Debug, Clone, Copy)]
+pub struct CompactJsonRenderer;
+
+#[derive(Debug, Clone, Copy)]
+pub struct MarkdownRenderer;
+
+/// Plain text for clients that show tool output verbatim.
+#[derive(Debug, Clone, Copy)]
+pub struct TextRenderer;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CsvRenderer;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RssRenderer;
+
+impl ResponseRenderer for JsonRenderer {
+    fn format(&self) -> &'static str {
+        DEFAULT_RESPONSE_FORMAT
+    }
+
+    fn render(&self, response: &SearchResponse) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&build_response_payload(response)?)
+    }
+}
+
+impl ResponseRenderer for CompactJsonRenderer {
+    fn format(&self) -> &'static str {
+        DEFAULT_RESPONSE_FORMAT
+    }
+
+    fn render(&self, response: &SearchResponse) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&build_response_payload(response)?)
+    }
+}
+
+impl ResponseRenderer for MarkdownRenderer {
+    fn format(&self) -> &'static str {
+        "markdown"
+    }
+
+    fn render(&self, response: &SearchResponse) -> Result<String, serde_json::Error> {
+        let mut out = format!("# Brave search: {}\n", response.meta.query);
+        if !response.summary.is_empty() {
+            let _ = write!(out, "\n{}\n", response.summary);
+        }
+        let _ = write!(out, "\n_{}_\n", meta_line(response));
+
+        let mut current_section = None;
+        for entry in rendered_items(response) {
+            if current_section != Some(entry.heading) {
+                current_section = Some(entry.heading);
+                let _ = write!(out, "\n## {}\n\n", entry.heading);
+            }
+            let _ = writeln!(
+                out,
+                "{}. [{}]({})",
+                entry.rank,
+                markdown_escape(&entry.item.title),
+                markdown_link_destination(&entry.item.url)
+            );
+            let byline = byline(entry.item);
+            if !byline.is_empty() {
+                let _ = writeln!(out, "   {byline}");
+            }
+            if !entry.item.snippet.is_empty() {
+                let _ = writeln!(out, "   {}", markdown_escape(&entry.item.snippet));
+            }
+        }
+
+        if !response.warnings.is_empty() {
+            out.push_str("\n## Warnings\n\n");
+            for warning in &response.warnings {
+                let _ = writeln!(out, "- `{}`: {}", warning.code, warning.message);
+            }
+        }
+
+        if let Some(cursor) = &response.next_cursor {
+            let _ = write!(out, "\nNext page cursor: `{cursor}`\n");
+        }
+        Ok(out)
+    }
+}
+
+impl ResponseRenderer for TextRenderer {
+    fn format(&self) -> &'static str {
+        "text"
+    }
+
+    fn render(&self, response: &SearchResponse) -> Result<String, serde_json::Error> {
+        let mut out = format!("Brave search: {}\n", response.meta.query);
+        if !response.summary.is_empty() {
+            let _ = writeln!(out, "{}", response.summary);
+        }
+        let _ = writeln!(out, "{}", meta_line(response));
+
+        let mut current_section = None;
+        for entry in rendered_items(response) {
+            if current_section != Some(entry.heading) {
+                current_section = Some(entry.heading);
+                let _ = write!(out, "\n{}\n", entry.heading);
+            }
+            let _ = writeln!(out, "{}. {}", entry.rank, single_line(&entry.item.title));
+            let _ = writeln!(out, "   {}", entry.item.url);
+            let byline = byline(entry.item);
+            if !byline.is_empty() {
+                let _ = writeln!(out, "   {byline}");
+            }
+            if !entry.item.snippet.is_empty() {
+                let _ = writeln!(out, "   {}", single_line(&entry.item.snippet));
+            }
+        }
+
+        if !response.warnings.is_empty() {
+            out.push_str("\nWarnings\n");
+            for warning in &response.warnings {
+                let _ = writeln!(out, "- {}: {}", warning.code, warning.message);
+            }
+        }
+
+        if let Some(cursor) = &response.next_cursor {
+            let _ = write!(out, "\nNext page cursor: {cursor}\n");
+        }
+        Ok(out)
+    }
+}
+
+impl ResponseRenderer for CsvRenderer {
+    fn format(&self) -> &'static str {
+        "csv"
+    }
+
+    fn render(&self, response: &SearchResponse) -> Result<String, serde_json::Error> {
+        let mut out = String::from("section,rank,title,url,source,published,snippet\n");
+        for entry in rendered_items(response) {
+            let fields = [
+                entry.section.as_str(),
+                &entry.rank.to_string(),
+                &entry.item.title,
+                &entry.item.url,
+                entry.item.source.as_deref().unwrap_or_default(),
+                entry
+                    .item
+                    .published
+                    .as_deref()
+                    .or(entry.item.age.as_deref())
+                    .unwrap_or_default(),
+                &entry.item.snippet,
+            ]
+            .map(csv_field);
+            out.push_str(&fields.join(","));
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+impl ResponseRenderer for RssRenderer {
+    fn format(&self) -> &'static str {
+        "rss"
+    }
+
+    fn render(&self, response: &SearchResponse) -> Result<String, serde_json::Error> {
+        let link = url::Url::parse_with_params(
+            "https://search.brave.com/search",
+            [("q", response.meta.query.as_str())],
+        )
+        .map_or_else(|_| "https://search.brave.com/".to_string(), String::from);
+
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<rss version=\"2.0\">\n  <channel>\n");
+        let _ = writeln!(
+            out,
+            "    <title>Brave search: {}</title>",
+            xml_escape(&response.meta.query)
+        );
+        let _ = writeln!(out, "    <link>{}</link>", xml_escape(&link));
+        let _ = writeln!(
+            out,
+            "    <description>{}</description>",
+            xml_escape(&response.summary)
+        );
+        for entry in rendered_items(response) {
+            out.push_str("    <item>\n");
+            let _ = writeln!(
+                out,
+                "      <title>{}</title>",
+                xml_escape(&entry.item.title)
+            );
+            let _ = writeln!(out, "      <link>{}</link>", xml_escape(&entry.item.url));
+            let _ = writeln!(out, "      <guid>{}</guid>", xml_escape(&entry.item.url));
+            let _ = writeln!(out, "      <category>{}</category>", entry.section.as_str());
+            if !entry.item.snippet.is_empty() {
+                let _ = writeln!(
+                    out,
+                    "      <description>{}</description>",
+                    xml_escape(&entry.item.snippet)
+                );
+            }
+            out.push_str("    </item>\n");
+        }
+        out.push_str("  </channel>\n</rss>\n");
+        Ok(out)
+    }
+}
+
+/// Renderers available to the `format` argument, keyed by [`ResponseRenderer::format`].
+#[derive(Clone)]
+pub struct RendererRegistry {
+    renderers: Vec<Arc<dyn ResponseRenderer>>,
+}
+
+impl Default for RendererRegistry {
+    fn default() -> Self {
+        Self {
+            renderers: vec![
+                Arc::new(JsonRenderer),
+                Arc::new(MarkdownRenderer),
+                Arc::new(TextRenderer),
+                Arc::new(CsvRenderer),
+                Arc::new(RssRenderer),
+            ],
+        }
+    }
+}
+
+impl std::fmt::Debug for RendererRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.formats()).finish()
+    }
+}
+
+impl RendererRegistry {
+    /// Adds `renderer`, replacing any existing renderer for the same format.
+    pub fn register(&mut self, renderer: Arc<dyn ResponseRenderer>) {
+        let format = renderer.format();
+        self.renderers
+            .retain(|existing| existing.format() != format);
+        self.renderers.push(renderer);
+    }
+
+    #[must_use]
+    pub fn get(&self, format: &str) -> Option<&dyn ResponseRenderer> {
+        self.renderers
+            .iter()
+            .find(|renderer| renderer.format().eq_ignore_ascii_case(format))
+            .map(AsRef::as_ref)
+    }
+
+    pub fn formats(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.renderers.iter().map(|renderer| renderer.format())
+    }
+}
+
+struct RenderedItem<'a> {
+    section: BraveSectionName,
+    heading: &'a str,
+    rank: usize,
+    item: &'a SearchResultItem,
+}
+
+fn rendered_items(response: &SearchResponse) -> Vec<RenderedItem<'_>> {
+    if !response.results.is_empty() {
+        return response
+            .results
+            .iter()
+            .map(|merged| RenderedItem {
+                section: merged.origin_section,
+                heading: "Results",
+                rank: merged.rank,
+                item: &merged.item,
+            })
+            .collect();
+    }
+
+    response
+        .sections
+        .iter()
+        .flat_map(|section| {
+            section
+                .results
+                .iter()
+                .enumerate()
+                .map(|(index, item)| RenderedItem {
+                    section: section.key,
+                    heading: &section.label,
+                    rank: index + 1,
+                    item,
+                })
+        })
+        .collect()
+}
+
+fn byline(item: &SearchResultItem) -> String {
+    [
+        item.source.as_deref(),
+        item.published.as_deref().or(item.age.as_deref()),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|part| !part.is_empty())
+    .collect::<Vec<_>>()
+    .join(" · ")
+}
+
+/// Search type, page position and cache state in one line.
+fn meta_line(response: &SearchResponse) -> String {
+    let meta = &response.meta;
+    let mut line = format!(
+        "{} · {} of {} results · offset {}",
+        meta.search_type.as_str(),
+        meta.returned,
+        meta.requested,
+        meta.offset
+    );
+    if let Some(age) = meta.cache_age_secs {
+        let _ = write!(line, " · cached {age}s ago");
+    }
+    if meta.has_more {
+        line.push_str(" · more available");
+    }
+    line
+}
+
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+fn markdown_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if matches!(ch, '[' | ']' | '*' | '_' | '`' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(if ch == '\n' { ' ' } else { ch });
+    }
+    escaped
+}
+
+/// Percent-encodes the characters that would end or break a `[title](url)` destination.
+fn markdown_link_destination(url: &str) -> String {
+    let mut encoded = String::with_capacity(url.len());
+    for ch in url.chars() {
+        match ch {
+            '(' => encoded.push_str("%28"),
+            ')' => encoded.push_str("%29"),
+            '<' => encoded.push_str("%3C"),
+            '>' => encoded.push_str("%3E"),
+            ' ' => encoded.push_str("%20"),
+            ch if ch.is_whitespace() => {
+                let mut buf = [0; 4];
+                for byte in ch.encode_utf8(&mut buf).bytes() {
+                    let _ = write!(encoded, "%{byte:02X}");
+                }
+            }
+            ch => encoded.push(ch),
+        }
+    }
+    encoded
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
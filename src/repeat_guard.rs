@@ -0,0 +1,47 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+const PRUNE_THRESHOLD: usize = 1_024;
+
+/// Counts identical searches inside a sliding window so agent loops that keep re-issuing the
+/// same query can be told so.
+#[derive(Debug)]
+pub struct RepeatGuard {
+    window: Duration,
+    seen: Mutex<HashMap<String, VecDeque<Instant>>>,
+}
+
+impl RepeatGuard {
+    #[must_use]
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records one search for `key` and returns how many were seen within the window,
+    /// including this one.
+    pub fn record(&self, key: &str) -> usize {
+        let now = Instant::now();
+        let mut seen = self.seen.lock().unwrap_or_else(PoisonError::into_inner);
+        if seen.len() >= PRUNE_THRESHOLD {
+            seen.retain(|_, times| {
+                times
+                    .back()
+                    .is_some_and(|last| now.duration_since(*last) < self.window)
+            });
+        }
+
+        let times = seen.entry(key.to_string()).or_default();
+        while times
+            .front()
+            .is_some_and(|first| now.duration_since(*first) >= self.window)
+        {
+            times.pop_front();
+        }
+        times.push_back(now);
+        times.len()
+    }
+}
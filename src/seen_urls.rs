@@ -0,0 +1,61 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+const PRUNE_THRESHOLD: usize = 1_024;
+
+/// Normalized URLs already returned to a caller, per pagination session, so continuation pages
+/// can leave out what earlier pages showed.
+#[derive(Debug)]
+pub struct SeenUrlStore {
+    ttl: Duration,
+    sessions: Mutex<HashMap<String, SeenSession>>,
+}
+
+#[derive(Debug)]
+struct SeenSession {
+    urls: HashSet<String>,
+    touched: Instant,
+}
+
+impl SeenUrlStore {
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// URLs recorded for `session` that have not expired.
+    #[must_use]
+    pub fn seen(&self, session: &str) -> HashSet<String> {
+        let sessions = self.sessions.lock().unwrap_or_else(PoisonError::into_inner);
+        sessions
+            .get(session)
+            .filter(|entry| entry.touched.elapsed() < self.ttl)
+            .map(|entry| entry.urls.clone())
+            .unwrap_or_default()
+    }
+
+    /// Adds `urls` to `session`; `restart` forgets what the session saw before (a new first page).
+    pub fn record(&self, session: &str, urls: impl IntoIterator<Item = String>, restart: bool) {
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap_or_else(PoisonError::into_inner);
+        if sessions.len() >= PRUNE_THRESHOLD {
+            sessions.retain(|_, entry| now.duration_since(entry.touched) < self.ttl);
+        }
+
+        let entry = sessions
+            .entry(session.to_string())
+            .or_insert_with(|| SeenSession {
+                urls: HashSet::new(),
+                touched: now,
+            });
+        if restart || now.duration_since(entry.touched) >= self.ttl {
+            entry.urls.clear();
+        }
+        entry.urls.extend(urls);
+        entry.touched = now;
+    }
+}
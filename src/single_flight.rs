@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::sync::OnceCell;
+
+/// Coalesces concurrent calls for the same key into one execution whose result every caller
+/// receives. Keys are forgotten once the call completes; caching is left to the caller.
+#[derive(Debug)]
+pub struct SingleFlight<T> {
+    calls: Mutex<HashMap<String, Arc<OnceCell<T>>>>,
+}
+
+impl<T> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self {
+            calls: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T: Clone> SingleFlight<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `work` unless an identical call is already in flight. The flag is `true` when the
+    /// value came from another caller's execution.
+    pub async fn run<W, Fut>(&self, key: &str, work: W) -> (T, bool)
+    where
+        W: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let cell = {
+            let mut calls = self.calls.lock().unwrap_or_else(PoisonError::into_inner);
+            Arc::clone(calls.entry(key.to_string()).or_default())
+        };
+
+        // If the executing caller is dropped mid-flight, OnceCell hands initialization to the
+        // next waiter, so nobody waits on an abandoned call.
+        let mut executed = false;
+        let value = cell
+            .get_or_init(|| {
+                executed = true;
+                work()
+            })
+            .await
+            .clone();
+
+        let mut calls = self.calls.lock().unwrap_or_else(PoisonError::into_inner);
+        if calls
+            .get(key)
+            .is_some_and(|current| Arc::ptr_eq(current, &cell))
+        {
+            calls.remove(key);
+        }
+        (value, !executed)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.calls
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+}
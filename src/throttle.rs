@@ -1,16 +1,46 @@
+//! Token-bucket pacing of upstream Brave calls.
+//!
+//! Only calls that actually reach Brave take a token: cache hits, coalesced in-flight requests
+//! and cached spellcheck pre-flights cost nothing. Each request tallies what it spent in a
+//! [`TokenLedger`], reported as `meta.throttle_tokens`.
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
+use crate::types::{RateLimitInfo, ThrottleWaitStats};
+
 #[derive(Debug)]
 struct BucketState {
     available_tokens: f64,
     last_refill: Instant,
+    tokens_per_second: f64,
+    burst_capacity: f64,
+}
+
+/// Throttle tokens consumed on behalf of one request, and how long it waited for them.
+#[derive(Debug, Default)]
+pub struct TokenLedger {
+    consumed: AtomicU32,
+    waited_micros: AtomicU64,
+}
+
+impl TokenLedger {
+    #[must_use]
+    pub fn consumed(&self) -> u32 {
+        self.consumed.load(Ordering::Relaxed)
+    }
+
+    #[must_use]
+    pub fn waited(&self) -> Duration {
+        Duration::from_micros(self.waited_micros.load(Ordering::Relaxed))
+    }
 }
 
 #[derive(Debug)]
 pub struct RequestThrottle {
-    tokens_per_second: f64,
-    burst_capacity: f64,
     state: tokio::sync::Mutex<BucketState>,
+    waits: AtomicU64,
+    wait_ms_total: AtomicU64,
 }
 
 impl RequestThrottle {
@@ -19,23 +49,79 @@ impl RequestThrottle {
         let rate = f64::from(tokens_per_second.max(1));
         let burst = f64::from(burst_capacity.max(1));
         Self {
-            tokens_per_second: rate,
-            burst_capacity: burst,
             state: tokio::sync::Mutex::new(BucketState {
                 available_tokens: burst,
                 last_refill: Instant::now(),
+                tokens_per_second: rate,
+                burst_capacity: burst,
             }),
+            waits: AtomicU64::new(0),
+            wait_ms_total: AtomicU64::new(0),
+        }
+    }
+
+    /// Grants that had to wait for a refill, and their total wait, since startup.
+    #[must_use]
+    pub fn wait_stats(&self) -> ThrottleWaitStats {
+        ThrottleWaitStats {
+            waits: self.waits.load(Ordering::Relaxed),
+            wait_ms_total: self.wait_ms_total.load(Ordering::Relaxed),
         }
     }
 
+    /// Retunes the bucket to the upstream per-second window: rate and burst follow its limit, and
+    /// an exhausted window drains the bucket so the next caller waits for a refill.
+    pub async fn observe_rate_limit(&self, rate_limit: RateLimitInfo) {
+        let limit = f64::from(rate_limit.limit.max(1));
+        let mut state = self.state.lock().await;
+        state.tokens_per_second = limit;
+        state.burst_capacity = limit;
+        state.available_tokens = if rate_limit.remaining == 0 {
+            state.available_tokens.min(0.0)
+        } else {
+            state
+                .available_tokens
+                .min(f64::from(rate_limit.remaining))
+                .min(limit)
+        };
+    }
+
+    pub async fn tokens_per_second(&self) -> f64 {
+        self.state.lock().await.tokens_per_second
+    }
+
     pub async fn acquire(&self) {
         let _ = self.acquire_cancellable(&|| false).await;
     }
 
+    /// Like [`Self::acquire_cancellable`], recording the token in `ledger` once granted.
+    pub async fn acquire_charged<F>(&self, ledger: &TokenLedger, is_cancelled: &F) -> Result<(), ()>
+    where
+        F: Fn() -> bool,
+    {
+        let waited = self.acquire_waiting(is_cancelled).await?;
+        ledger.consumed.fetch_add(1, Ordering::Relaxed);
+        ledger.waited_micros.fetch_add(
+            u64::try_from(waited.as_micros()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+        Ok(())
+    }
+
     pub async fn acquire_cancellable<F>(&self, is_cancelled: &F) -> Result<(), ()>
     where
         F: Fn() -> bool,
     {
+        self.acquire_waiting(is_cancelled).await.map(|_| ())
+    }
+
+    /// Takes a token, returning how long it had to wait for one.
+    async fn acquire_waiting<F>(&self, is_cancelled: &F) -> Result<Duration, ()>
+    where
+        F: Fn() -> bool,
+    {
+        let started = Instant::now();
+        let mut waited = false;
         loop {
             if is_cancelled() {
                 return Err(());
@@ -50,19 +136,27 @@ impl RequestThrottle {
             let elapsed = now.duration_since(state.last_refill).as_secs_f64();
             if elapsed > 0.0 {
                 state.available_tokens = (state.available_tokens
-                    + elapsed * self.tokens_per_second)
-                    .min(self.burst_capacity);
+                    + elapsed * state.tokens_per_second)
+                    .min(state.burst_capacity);
                 state.last_refill = now;
             }
 
             if state.available_tokens >= 1.0 {
                 state.available_tokens -= 1.0;
-                return Ok(());
+                if !waited {
+                    return Ok(Duration::ZERO);
+                }
+                let wait = started.elapsed();
+                let wait_ms = u64::try_from(wait.as_millis()).unwrap_or(u64::MAX);
+                self.waits.fetch_add(1, Ordering::Relaxed);
+                self.wait_ms_total.fetch_add(wait_ms, Ordering::Relaxed);
+                return Ok(wait);
             }
 
             let deficit = 1.0 - state.available_tokens;
-            let wait_seconds = deficit / self.tokens_per_second;
+            let wait_seconds = deficit / state.tokens_per_second;
             drop(state);
+            waited = true;
 
             let total_wait = Duration::from_secs_f64(wait_seconds.max(0.001));
             let start = Instant::now();
@@ -77,3 +171,43 @@ impl RequestThrottle {
         }
     }
 }
+
+/// Retries shared by every request: a bucket of `per_minute` tokens refilled continuously, so an
+/// outage cannot multiply upstream load by the per-request retry count. Never waits.
+#[derive(Debug)]
+pub struct RetryBudget {
+    per_minute: u32,
+    state: std::sync::Mutex<(f64, Instant)>,
+}
+
+impl RetryBudget {
+    /// A `per_minute` of 0 leaves retries unbudgeted.
+    #[must_use]
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute,
+            state: std::sync::Mutex::new((f64::from(per_minute), Instant::now())),
+        }
+    }
+
+    /// Spends one retry; `false` when the budget is empty.
+    pub fn try_take(&self) -> bool {
+        if self.per_minute == 0 {
+            return true;
+        }
+        let capacity = f64::from(self.per_minute);
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let (available, last_refill) = &mut *state;
+        let refill = last_refill.elapsed().as_secs_f64() * capacity / 60.0;
+        *available = (*available + refill).min(capacity);
+        *last_refill = Instant::now();
+        if *available < 1.0 {
+            return false;
+        }
+        *available -= 1.0;
+        true
+    }
+}
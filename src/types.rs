@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -21,6 +22,178 @@ impl SearchType {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiVersion {
+    #[default]
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::V1 => "v1",
+            Self::V2 => "v2",
+        }
+    }
+}
+
+/// How parsed results are arranged: one section per Brave section, or a single section in the
+/// order of Brave's `mixed.main` ranking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultOrder {
+    #[default]
+    Grouped,
+    Ranked,
+}
+
+impl ResultOrder {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Grouped => "grouped",
+            Self::Ranked => "ranked",
+        }
+    }
+}
+
+/// What counts as a duplicate when results are deduplicated across sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DedupStrategy {
+    /// Same URL after case, `www.`/`m.`/`amp.` host, tracking-param and trailing-slash
+    /// normalization.
+    #[default]
+    Url,
+    /// Same host, ignoring `www.`, `m.` and `amp.` prefixes: one result per site.
+    Host,
+    /// Same title after case, punctuation, word order and a trailing site name are ignored.
+    Title,
+    Off,
+}
+
+impl DedupStrategy {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Url => "url",
+            Self::Host => "host",
+            Self::Title => "title",
+            Self::Off => "off",
+        }
+    }
+}
+
+/// How results are reordered before output limits apply: Brave's order, keyword relevance to the
+/// query, or newest first by `page_age`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RerankMode {
+    #[default]
+    None,
+    Keyword,
+    Recency,
+}
+
+impl RerankMode {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Keyword => "keyword",
+            Self::Recency => "recency",
+        }
+    }
+}
+
+/// What happens when a response exceeds `max_lines`/`max_bytes`: shrink it until it fits, or
+/// return it intact with `meta.exceeds_limits` and the measured size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitMode {
+    #[default]
+    Enforce,
+    Annotate,
+}
+
+impl LimitMode {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Enforce => "enforce",
+            Self::Annotate => "annotate",
+        }
+    }
+}
+
+/// Rendered size of a response in the requested `format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct OutputSize {
+    pub lines: usize,
+    pub bytes: usize,
+}
+
+/// How spread the returned results are across sites (hosts without `www.`/`m.`/`amp.`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResultDiversity {
+    pub results: usize,
+    pub distinct_domains: usize,
+    /// `distinct_domains / results`; 1.0 when every result comes from a different site.
+    pub distinct_ratio: f64,
+    pub top_domain: String,
+    /// Share of the results that come from `top_domain`.
+    pub top_domain_share: f64,
+}
+
+/// Layout and dedup choices applied while parsing a payload into sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    pub order: ResultOrder,
+    pub dedup: DedupStrategy,
+    /// Drop extra snippets that repeat the main snippet or each other.
+    pub dedupe_snippets: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            order: ResultOrder::default(),
+            dedup: DedupStrategy::default(),
+            dedupe_snippets: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NewsTopic {
+    Business,
+    Technology,
+    Sports,
+    Politics,
+    Science,
+    Health,
+    Entertainment,
+}
+
+impl NewsTopic {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Business => "business",
+            Self::Technology => "technology",
+            Self::Sports => "sports",
+            Self::Politics => "politics",
+            Self::Science => "science",
+            Self::Health => "health",
+            Self::Entertainment => "entertainment",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum BraveSectionName {
@@ -30,6 +203,10 @@ pub enum BraveSectionName {
     News,
     Images,
     Infobox,
+    Locations,
+    Faq,
+    /// Results of several sections interleaved in Brave's relevance order.
+    Mixed,
 }
 
 impl BraveSectionName {
@@ -42,6 +219,9 @@ impl BraveSectionName {
             Self::News => "news",
             Self::Images => "images",
             Self::Infobox => "infobox",
+            Self::Locations => "locations",
+            Self::Faq => "faq",
+            Self::Mixed => "mixed",
         }
     }
 }
@@ -54,6 +234,8 @@ pub enum WebResultFilter {
     Videos,
     News,
     Infobox,
+    Locations,
+    Faq,
 }
 
 impl WebResultFilter {
@@ -65,13 +247,16 @@ impl WebResultFilter {
             Self::Videos => "videos",
             Self::News => "news",
             Self::Infobox => "infobox",
+            Self::Locations => "locations",
+            Self::Faq => "faq",
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct BraveWebSearchArgs {
+    #[serde(default)]
     pub query: String,
     pub search_type: Option<String>,
     pub result_filter: Option<Vec<String>>,
@@ -82,17 +267,166 @@ pub struct BraveWebSearchArgs {
     pub ui_language: Option<String>,
     pub safe_search: Option<String>,
     pub units: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_freshness")]
     pub freshness: Option<String>,
     pub spellcheck: Option<bool>,
     pub extra_snippets: Option<bool>,
     pub text_decorations: Option<bool>,
+    pub dedupe_snippets: Option<bool>,
     pub max_lines: Option<usize>,
     pub max_bytes: Option<usize>,
+    pub max_tokens: Option<usize>,
+    pub limit_mode: Option<String>,
     pub debug: Option<bool>,
     pub include_raw_payload: Option<bool>,
+    pub raw_payload_fields: Option<Vec<String>>,
     pub disable_cache: Option<bool>,
     pub disable_throttle: Option<bool>,
     pub include_request_url: Option<bool>,
+    pub api_version: Option<String>,
+    pub explain: Option<bool>,
+    pub correct_query: Option<bool>,
+    pub merge_sections: Option<bool>,
+    pub order: Option<String>,
+    pub dedup: Option<String>,
+    pub rerank: Option<String>,
+    pub include_section_summaries: Option<bool>,
+    pub reading_time: Option<bool>,
+    pub scan_injection: Option<bool>,
+    pub breaking_only: Option<bool>,
+    pub published_after: Option<String>,
+    pub published_before: Option<String>,
+    pub clean_urls: Option<bool>,
+    pub auto_paginate: Option<bool>,
+    pub delta_only: Option<bool>,
+    pub goggles: Option<String>,
+    /// Query-operator helpers, compiled into `site:`, `filetype:`, `intitle:`, `inurl:` and
+    /// `-term` operators appended to `query`.
+    pub site: Option<String>,
+    pub filetype: Option<String>,
+    pub intitle: Option<String>,
+    pub inurl: Option<String>,
+    pub exclude_terms: Option<Vec<String>>,
+    pub cursor: Option<String>,
+    pub max_cache_age_secs: Option<u64>,
+    pub filter: Option<String>,
+    pub format: Option<String>,
+    pub compact: Option<bool>,
+    pub topic: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SearchCursor {
+    pub query: String,
+    pub search_type: SearchType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub result_filter: Vec<String>,
+    pub count: usize,
+    pub offset: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ui_language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safe_search: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub units: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub freshness: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goggles: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<NewsTopic>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SummarizeArgs {
+    pub query: String,
+    pub country: Option<String>,
+    pub search_language: Option<String>,
+    pub ui_language: Option<String>,
+    pub safe_search: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SuggestArgs {
+    pub query: String,
+    pub country: Option<String>,
+    pub count: Option<usize>,
+}
+
+/// One side of a `brave_compare_params` call: the parameters that differ between the two runs.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompareParamSet {
+    pub country: Option<String>,
+    pub search_language: Option<String>,
+    pub ui_language: Option<String>,
+    pub safe_search: Option<String>,
+    pub units: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_freshness")]
+    pub freshness: Option<String>,
+    pub goggles: Option<String>,
+}
+
+/// `freshness` as Brave's string form, or `{ "from": "YYYY-MM-DD", "to": "YYYY-MM-DD" }`, which
+/// becomes `YYYY-MM-DDtoYYYY-MM-DD`. Dates are validated with the rest of the arguments.
+fn deserialize_freshness<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Freshness {
+        Value(String),
+        Range(FreshnessRange),
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct FreshnessRange {
+        from: String,
+        to: String,
+    }
+
+    Ok(
+        Option::<Freshness>::deserialize(deserializer)?.map(|freshness| match freshness {
+            Freshness::Value(value) => value,
+            Freshness::Range(range) => format!("{}to{}", range.from.trim(), range.to.trim()),
+        }),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompareParamsArgs {
+    pub query: String,
+    pub search_type: Option<String>,
+    pub max_results: Option<usize>,
+    pub a: CompareParamSet,
+    pub b: CompareParamSet,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchScheduleArgs {
+    pub queries: Vec<String>,
+    pub deadline_secs: Option<u64>,
+    pub search_type: Option<String>,
+    pub max_results: Option<usize>,
+    pub country: Option<String>,
+    pub search_language: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchStatusArgs {
+    pub job_id: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -109,9 +443,25 @@ pub struct StatusArgs {
     pub include_limits: Option<bool>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReloadKeyArgs {}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsArgs {}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HistoryArgs {
+    pub limit: Option<usize>,
+    pub errors_only: Option<bool>,
+}
+
 #[derive(Debug, Clone)]
 pub struct NormalizedSearchRequest {
     pub query: String,
+    pub query_modified: bool,
     pub search_type: SearchType,
     pub result_filter_values: Vec<WebResultFilter>,
     pub requested: usize,
@@ -125,22 +475,205 @@ pub struct NormalizedSearchRequest {
     pub spellcheck: bool,
     pub extra_snippets: bool,
     pub text_decorations: bool,
+    pub dedupe_snippets: bool,
     pub max_lines: usize,
     pub max_bytes: usize,
+    /// Estimated-token budget for the rendered output, on top of `max_lines`/`max_bytes`.
+    pub max_tokens: Option<usize>,
+    pub limit_mode: LimitMode,
     pub debug: bool,
     pub include_raw_payload: bool,
+    /// JSON pointers the raw payload is reduced to; empty keeps the whole payload.
+    pub raw_payload_fields: Vec<String>,
     pub disable_cache: bool,
+    pub max_cache_age: Option<std::time::Duration>,
     pub disable_throttle: bool,
     pub include_request_url: bool,
+    pub api_version: ApiVersion,
+    pub explain: bool,
+    pub correct_query: bool,
+    pub merge_sections: bool,
+    pub order: ResultOrder,
+    pub dedup: DedupStrategy,
+    pub rerank: RerankMode,
+    pub include_section_summaries: bool,
+    pub reading_time: bool,
+    pub scan_injection: bool,
+    pub breaking_only: bool,
+    /// Inclusive `YYYY-MM-DD` bounds on each result's `published` date.
+    pub published_after: Option<String>,
+    pub published_before: Option<String>,
+    pub clean_urls: bool,
+    pub auto_paginate: bool,
+    pub delta_only: bool,
+    pub goggles: Option<String>,
+    pub filter: Option<crate::filter_expr::FilterExpr>,
+    pub format: String,
+    pub compact: bool,
+    pub topic: Option<NewsTopic>,
+    pub inferred: Vec<InferredParam>,
     pub warnings: Vec<WarningEntry>,
 }
 
+/// Machine-readable code of a [`WarningEntry`]; serializes to the `SCREAMING_SNAKE_CASE` string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
+pub enum WarningCode {
+    QueryTruncated,
+    QueryAdvisory,
+    InvalidSearchType,
+    InvalidResultFilter,
+    ResultFilterIgnored,
+    InvalidSearchLanguage,
+    InvalidUiLanguage,
+    InvalidCountry,
+    InvalidSafeSearch,
+    InvalidUnits,
+    InvalidFreshness,
+    InvalidPublishedDate,
+    OffsetCapped,
+    Deduplicated,
+    NoRecognizedSections,
+    OutputTruncated,
+    RawPayloadTruncated,
+    InvalidRawPayloadField,
+    ParamAliased,
+    PolicyOverridden,
+    SafeSearchEnforced,
+    ResultsBlocked,
+    InvalidApiVersion,
+    ThumbnailOmitted,
+    SectionsCapped,
+    QueryCorrected,
+    LocalDetailsUnavailable,
+    InvalidGoggles,
+    BatchDeadlineExceeded,
+    DebugToolsUnavailable,
+    ResultsFiltered,
+    InvalidTopic,
+    TopicIgnored,
+    FieldsTruncated,
+    RepeatedQuery,
+    InvalidOrder,
+    InvalidDedup,
+    InvalidRerank,
+    LanguageAutodetected,
+    InvalidLimitMode,
+    RankedOrderUnavailable,
+    InjectionRisk,
+    SectionParseFailed,
+    OfflineMode,
+    MisconfiguredEndpoint,
+}
+
+impl WarningCode {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::QueryTruncated => "QUERY_TRUNCATED",
+            Self::QueryAdvisory => "QUERY_ADVISORY",
+            Self::InvalidSearchType => "INVALID_SEARCH_TYPE",
+            Self::InvalidResultFilter => "INVALID_RESULT_FILTER",
+            Self::ResultFilterIgnored => "RESULT_FILTER_IGNORED",
+            Self::InvalidSearchLanguage => "INVALID_SEARCH_LANGUAGE",
+            Self::InvalidUiLanguage => "INVALID_UI_LANGUAGE",
+            Self::InvalidCountry => "INVALID_COUNTRY",
+            Self::InvalidSafeSearch => "INVALID_SAFE_SEARCH",
+            Self::InvalidUnits => "INVALID_UNITS",
+            Self::InvalidFreshness => "INVALID_FRESHNESS",
+            Self::InvalidPublishedDate => "INVALID_PUBLISHED_DATE",
+            Self::OffsetCapped => "OFFSET_CAPPED",
+            Self::Deduplicated => "DEDUPLICATED",
+            Self::NoRecognizedSections => "NO_RECOGNIZED_SECTIONS",
+            Self::OutputTruncated => "OUTPUT_TRUNCATED",
+            Self::RawPayloadTruncated => "RAW_PAYLOAD_TRUNCATED",
+            Self::InvalidRawPayloadField => "INVALID_RAW_PAYLOAD_FIELD",
+            Self::ParamAliased => "PARAM_ALIASED",
+            Self::PolicyOverridden => "POLICY_OVERRIDDEN",
+            Self::SafeSearchEnforced => "SAFE_SEARCH_ENFORCED",
+            Self::ResultsBlocked => "RESULTS_BLOCKED",
+            Self::InvalidApiVersion => "INVALID_API_VERSION",
+            Self::ThumbnailOmitted => "THUMBNAIL_OMITTED",
+            Self::SectionsCapped => "SECTIONS_CAPPED",
+            Self::QueryCorrected => "QUERY_CORRECTED",
+            Self::LocalDetailsUnavailable => "LOCAL_DETAILS_UNAVAILABLE",
+            Self::InvalidGoggles => "INVALID_GOGGLES",
+            Self::BatchDeadlineExceeded => "BATCH_DEADLINE_EXCEEDED",
+            Self::DebugToolsUnavailable => "DEBUG_TOOLS_UNAVAILABLE",
+            Self::ResultsFiltered => "RESULTS_FILTERED",
+            Self::InvalidTopic => "INVALID_TOPIC",
+            Self::TopicIgnored => "TOPIC_IGNORED",
+            Self::FieldsTruncated => "FIELDS_TRUNCATED",
+            Self::RepeatedQuery => "REPEATED_QUERY",
+            Self::InvalidOrder => "INVALID_ORDER",
+            Self::InvalidDedup => "INVALID_DEDUP",
+            Self::InvalidRerank => "INVALID_RERANK",
+            Self::LanguageAutodetected => "LANGUAGE_AUTODETECTED",
+            Self::InvalidLimitMode => "INVALID_LIMIT_MODE",
+            Self::RankedOrderUnavailable => "RANKED_ORDER_UNAVAILABLE",
+            Self::InjectionRisk => "INJECTION_RISK",
+            Self::SectionParseFailed => "SECTION_PARSE_FAILED",
+            Self::OfflineMode => "OFFLINE_MODE",
+            Self::MisconfiguredEndpoint => "MISCONFIGURED_ENDPOINT",
+        }
+    }
+}
+
+impl std::fmt::Display for WarningCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<&str> for WarningCode {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WarningEntry {
+    pub code: WarningCode,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoticeEntry {
     pub code: String,
     pub message: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct InferredParam {
+    pub field: String,
+    pub value: String,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search_language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ui_language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safe_search: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub units: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freshness: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goggles: Option<String>,
+    pub spellcheck: bool,
+    pub extra_snippets: bool,
+    pub text_decorations: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inferred: Vec<InferredParam>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ToolErrorEnvelope {
     pub api_version: String,
@@ -148,7 +681,7 @@ pub struct ToolErrorEnvelope {
     pub meta: ErrorMeta,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolErrorInfo {
     pub code: String,
     pub message: String,
@@ -168,13 +701,156 @@ pub struct SearchResponse {
     pub api_version: String,
     pub summary: String,
     pub sections: Vec<SearchSection>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub results: Vec<MergedResultItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub section_summaries: Vec<SectionSummary>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<TopicFacet>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
     pub meta: SearchMeta,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub warnings: Vec<WarningEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notices: Vec<NoticeEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub explanations: Vec<ResultExplanation>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub debug_data: Option<DebugData>,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryResponse {
+    pub api_version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub summary: String,
+    pub sources: Vec<SummarySource>,
+    pub meta: SummaryMeta,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<WarningEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SummarySource {
+    pub title: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryMeta {
+    pub query: String,
+    pub provider: String,
+    pub duration_ms: u128,
+    pub server_version: String,
+    pub trace_id: String,
+    pub endpoint_host: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuggestResponse {
+    pub api_version: String,
+    pub query: String,
+    pub suggestions: Vec<String>,
+    pub meta: SuggestMeta,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<WarningEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompareParamsResponse {
+    pub api_version: String,
+    pub query: String,
+    pub overlap: Vec<CompareOverlapItem>,
+    pub only_in_a: Vec<CompareResultItem>,
+    pub only_in_b: Vec<CompareResultItem>,
+    pub meta: CompareMeta,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<WarningEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompareOverlapItem {
+    pub title: String,
+    pub url: String,
+    pub rank_a: usize,
+    pub rank_b: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompareResultItem {
+    pub title: String,
+    pub url: String,
+    pub rank: usize,
+    pub section: BraveSectionName,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompareMeta {
+    pub returned_a: usize,
+    pub returned_b: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_params_a: Option<EffectiveParams>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_params_b: Option<EffectiveParams>,
+    pub provider: String,
+    pub duration_ms: u128,
+    pub server_version: String,
+    pub trace_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReplayArgs {
+    pub trace_id: String,
+}
+
+/// An archived search next to a fresh run of the same normalized request.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayResponse {
+    pub api_version: String,
+    pub original_trace_id: String,
+    pub archived_at_unix_secs: u64,
+    pub original: serde_json::Value,
+    pub replayed: serde_json::Value,
+    pub diff: Vec<FieldDiff>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub diff_truncated: bool,
+    pub meta: ReplayMeta,
+}
+
+/// One JSON path whose value differs between two responses; a side is `None` when the path is
+/// absent there.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldDiff {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replayed: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayMeta {
+    pub provider: String,
+    pub duration_ms: u128,
+    pub server_version: String,
+    pub trace_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuggestMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    pub count: usize,
+    pub provider: String,
+    pub duration_ms: u128,
+    pub server_version: String,
+    pub trace_id: String,
+    pub throttle_tokens: u32,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct SearchSection {
     pub key: BraveSectionName,
@@ -207,14 +883,97 @@ pub struct SearchResultItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub creator: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub views: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeddable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub location: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_live: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breaking: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local: Option<LocalResultItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<ImageResultItem>,
+    /// Section a result of a `mixed` section came from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin_section: Option<BraveSectionName>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_reading_minutes: Option<usize>,
+    /// Set by `scan_injection` when the title or snippets look like a prompt-injection attempt.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub injection_risk: Option<InjectionRisk>,
+}
+
+/// Prompt-injection pattern found in a result's text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionSignal {
+    /// Phrases addressed to a model, such as "ignore previous instructions".
+    InstructionOverride,
+    /// JSON or markup shaped like a tool/function call.
+    ToolCallMarkup,
+    /// A long base64-looking run that may hide encoded instructions.
+    EncodedBlob,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InjectionRiskLevel {
+    Medium,
+    /// An instruction override, or more than one kind of signal.
+    High,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InjectionRisk {
+    pub level: InjectionRiskLevel,
+    pub signals: Vec<InjectionSignal>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LocalResultItem {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub opening_hours: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Full-size image behind an image search result; `thumbnail` stays on the result itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImageResultItem {
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SearchMeta {
     pub query: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sent_query: Option<String>,
     pub search_type: SearchType,
     pub requested: usize,
     pub returned: usize,
@@ -225,6 +984,154 @@ pub struct SearchMeta {
     pub warnings_count: usize,
     pub server_version: String,
     pub trace_id: String,
+    pub endpoint_host: String,
+    pub endpoint_override: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_params: Option<EffectiveParams>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cache_hit: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_age_secs: Option<u64>,
+    /// Throttle tokens this request consumed; omitted (zero) when everything came from the cache.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub throttle_tokens: u32,
+    /// Brave's rate-limit window as of this response; omitted when it came from the cache.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitInfo>,
+    /// Dedup strategy applied, when the request chose one other than the default `url`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dedup: Option<DedupStrategy>,
+    /// Re-ranking applied, when the request chose one other than `none`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rerank: Option<RerankMode>,
+    /// Results left out by `delta_only` because an earlier page already returned them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppressed_repeats: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diversity: Option<ResultDiversity>,
+    /// Brave pages merged into this response; set when `auto_paginate` is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages_fetched: Option<usize>,
+    /// Set by `limit_mode="annotate"` when the untouched response is over `max_lines`/`max_bytes`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub exceeds_limits: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub measured_size: Option<OutputSize>,
+    /// Estimated tokens of the rendered output; set when `max_tokens` is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_tokens: Option<usize>,
+    #[serde(skip)]
+    pub upstream_ms: Option<u128>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResponseV2 {
+    pub api_version: String,
+    pub summary: String,
+    pub results: Vec<FlatResultItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub section_summaries: Vec<SectionSummary>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<TopicFacet>,
+    pub pagination: PaginationV2,
+    pub timings: TimingsV2,
+    pub meta: SearchMetaV2,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<WarningEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notices: Vec<NoticeEntry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub explanations: Vec<ResultExplanation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_data: Option<DebugData>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionSummary {
+    pub section: BraveSectionName,
+    pub summary: String,
+}
+
+/// Number of returned results classified under `topic`; unmatched results count as `other`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TopicFacet {
+    pub topic: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MergedResultItem {
+    pub rank: usize,
+    pub origin_section: BraveSectionName,
+    pub section_rank: usize,
+    #[serde(flatten)]
+    pub item: SearchResultItem,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlatResultItem {
+    pub section: BraveSectionName,
+    pub rank: usize,
+    #[serde(flatten)]
+    pub item: SearchResultItem,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PaginationV2 {
+    pub offset: usize,
+    pub requested: usize,
+    pub returned: usize,
+    pub has_more: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimingsV2 {
+    pub total_ms: u128,
+    pub upstream_ms: Option<u128>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMetaV2 {
+    pub query: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sent_query: Option<String>,
+    pub search_type: SearchType,
+    pub provider: String,
+    pub server_version: String,
+    pub trace_id: String,
+    pub endpoint_host: String,
+    pub endpoint_override: bool,
+    pub warnings_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_params: Option<EffectiveParams>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cache_hit: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_age_secs: Option<u64>,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub throttle_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dedup: Option<DedupStrategy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rerank: Option<RerankMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppressed_repeats: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diversity: Option<ResultDiversity>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages_fetched: Option<usize>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub exceeds_limits: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub measured_size: Option<OutputSize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_tokens: Option<usize>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -261,6 +1168,30 @@ pub struct DebugData {
     pub raw_payload_original_bytes: Option<usize>,
     pub cache_bypassed: bool,
     pub throttle_bypassed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accept_language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings: Option<PhaseTimings>,
+    /// Protocol negotiated with Brave; absent when the results did not come from Brave.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_version: Option<String>,
+}
+
+/// Where the time of one search went, in milliseconds. The upstream phases are absent when the
+/// results came from the cache.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PhaseTimings {
+    pub throttle_wait_ms: f64,
+    pub cache_lookup_ms: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub upstream_attempts_ms: Vec<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_read_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_ms: Option<f64>,
+    pub output_limits_ms: f64,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -287,21 +1218,202 @@ pub struct StatusResponse {
     pub provider: String,
     pub key_config: KeyConfigStatus,
     pub settings: RuntimeSettingsStatus,
+    pub capabilities: Capabilities,
+    pub cache: CacheStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<MirrorStats>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub probe: Option<ProbeStatus>,
+    /// The most recent rate-limit window Brave reported; omitted until a search has reached it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<ObservedRateLimit>,
+    /// Environment settings that failed validation and were ignored or clamped.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub config_warnings: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notices: Vec<NoticeEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ObservedRateLimit {
+    #[serde(flatten)]
+    pub window: RateLimitInfo,
+    pub observed_secs_ago: u64,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct KeyConfigStatus {
     pub has_key: bool,
     pub source: Option<String>,
+    pub state: KeyState,
+}
+
+/// What the provider has learned about the configured key from Brave's responses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyState {
+    /// No request has succeeded or been rejected yet.
+    #[default]
+    Unverified,
+    Valid,
+    /// Rejected with 401 after having worked; requests fail fast with `API_KEY_REVOKED`.
+    Invalid,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct MirrorStats {
+    pub percent: u32,
+    pub mirrored: u64,
+    pub matched: u64,
+    pub mismatched: u64,
+    pub failed: u64,
+}
+
+/// One search recorded by the request history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub at_unix_ms: u64,
+    pub trace_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_type: Option<SearchType>,
+    /// `ok`, or the error code the search failed with.
+    pub outcome: String,
+    pub duration_ms: u64,
+    /// `hit`, `miss` or `bypass`; absent for failed searches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryResponse {
+    pub api_version: String,
+    /// Newest first.
+    pub entries: Vec<HistoryEntry>,
+    pub recorded: usize,
+    pub capacity: usize,
+    pub meta: HistoryMeta,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryMeta {
+    pub server_version: String,
+    pub trace_id: String,
+}
+
+/// Counters since startup, reported by `brave_web_search_metrics`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsResponse {
+    pub api_version: String,
+    pub uptime_secs: u64,
+    pub searches: SearchCounts,
+    pub cache: CacheStats,
+    /// Failed searches by error code.
+    pub errors: BTreeMap<String, u64>,
+    pub upstream_latency: LatencySummary,
+    pub throttle: ThrottleWaitStats,
+    pub meta: MetricsMeta,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SearchCounts {
+    pub total: u64,
+    pub by_type: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LatencySummary {
+    pub calls: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p50_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p95_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ThrottleWaitStats {
+    pub waits: u64,
+    pub wait_ms_total: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsMeta {
+    pub server_version: String,
+    pub trace_id: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_entries: Option<usize>,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+/// Inventory of a deployment derived from its runtime configuration: logged at startup and
+/// reported by `brave_web_search_status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Capabilities {
+    /// Cargo features compiled into the binary.
+    pub build_features: Vec<&'static str>,
+    /// Optional behaviours switched on by configuration.
+    pub features: Vec<&'static str>,
+    pub transport: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub listen_addr: Option<String>,
+    pub api_version: ApiVersion,
+    pub provider: String,
+    pub endpoints: Vec<EndpointCapability>,
+    pub cache: CacheCapability,
+    pub policy: PolicyCapability,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointCapability {
+    pub search_type: SearchType,
+    pub host: String,
+    pub overridden: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheCapability {
+    pub backend: &'static str,
+    pub ttl_secs: u64,
+    pub max_entries: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyCapability {
+    /// Minimum `safe_search` per search type; requests asking for less are raised to it.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub safe_search_floors: BTreeMap<&'static str, String>,
+    /// Server-wide `safe_search` minimum that no request can go below.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub safe_search_min: Option<String>,
+    /// Number of blocked-domain patterns; the patterns themselves are not disclosed.
+    #[serde(skip_serializing_if = "is_zero_usize")]
+    pub blocked_domain_patterns: usize,
+    pub max_query_length: usize,
+    pub thumbnail_mode: &'static str,
+    /// Whether `debug=true` can echo raw upstream payloads and request URLs.
+    pub raw_payloads: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub suppressed_notices: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct RuntimeSettingsStatus {
     pub cache_ttl_secs: u64,
+    pub cache_max_entries: usize,
     pub throttle_rate_per_sec: u32,
     pub throttle_burst: u32,
+    pub throttle_adaptive: bool,
+    pub throttle_effective_rate_per_sec: f64,
     pub retry_count: usize,
     pub retry_base_delay_ms: u64,
     pub retry_max_delay_ms: u64,
@@ -318,6 +1430,18 @@ pub struct OutputLimitSettings {
     pub min_max_bytes: usize,
     pub max_max_lines: usize,
     pub max_max_bytes: usize,
+    pub section_result_floor: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_sections: Option<usize>,
+    pub max_title_chars: usize,
+    pub max_snippet_chars: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocaleOptions {
+    pub countries: Vec<String>,
+    pub search_languages: Vec<String>,
+    pub ui_languages: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -350,8 +1474,37 @@ pub struct NormalizedResult {
     pub subtype: Option<String>,
     pub duration: Option<String>,
     pub creator: Option<String>,
+    pub publisher: Option<String>,
+    pub views: Option<u64>,
+    pub embeddable: Option<bool>,
     pub location: Option<String>,
     pub is_live: bool,
+    pub breaking: bool,
+    pub hostname: Option<String>,
+    pub thumbnail: Option<String>,
+    pub poi_id: Option<String>,
+    pub local: Option<LocalResultItem>,
+    pub image: Option<ImageResultItem>,
+    pub origin_section: Option<BraveSectionName>,
+    pub provider_rank: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct DuplicateDecision {
+    pub kept_section: BraveSectionName,
+    pub kept_rank: usize,
+    pub removed_section: BraveSectionName,
+    pub removed_rank: usize,
+    pub removed_url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultExplanation {
+    pub section: BraveSectionName,
+    pub position: usize,
+    pub provider_rank: usize,
+    pub url: String,
+    pub reasons: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -368,6 +1521,7 @@ pub struct ParseSectionsResult {
     pub sections: Vec<ParsedSection>,
     pub has_more: bool,
     pub warnings: Vec<WarningEntry>,
+    pub duplicates: Vec<DuplicateDecision>,
 }
 
 #[derive(Debug, Clone)]
@@ -384,6 +1538,28 @@ pub struct FetchSearchParams {
     pub spellcheck: bool,
     pub extra_snippets: bool,
     pub text_decorations: bool,
+    pub order: ResultOrder,
+    pub dedup: DedupStrategy,
+    pub dedupe_snippets: bool,
+    pub summary: bool,
+    pub goggles: Option<String>,
+    pub accept_language: Option<String>,
+    pub trace_context: Option<TraceContext>,
+}
+
+/// W3C trace context sent upstream as `traceparent` (and `baggage`) request headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: String,
+    pub span_id: String,
+    pub baggage: Option<String>,
+}
+
+impl TraceContext {
+    #[must_use]
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-01", self.trace_id, self.span_id)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -391,8 +1567,113 @@ pub struct FetchSearchResult {
     pub sections: Vec<ParsedSection>,
     pub has_more: bool,
     pub warnings: Vec<WarningEntry>,
+    pub duplicates: Vec<DuplicateDecision>,
     pub query_echo: String,
     pub request_url: String,
     pub raw_payload: serde_json::Value,
     pub raw_payload_bytes: usize,
+    pub rate_limit: Option<RateLimitInfo>,
+    pub quota: Option<QuotaInfo>,
+    /// Retries Brave needed before answering.
+    pub retries: usize,
+    pub timings: UpstreamTimings,
+    /// Protocol Brave answered over, e.g. `HTTP/2.0`; `None` when no request was made.
+    pub http_version: Option<String>,
+    pub trace_id: Option<String>,
+}
+
+/// Time spent on the upstream call behind a [`FetchSearchResult`].
+#[derive(Debug, Clone, Default)]
+pub struct UpstreamTimings {
+    /// Every attempt, failed ones included, from sending the request to having its body.
+    pub attempts: Vec<std::time::Duration>,
+    /// Reading the body of the answering attempt.
+    pub body_read: std::time::Duration,
+    /// Decoding the JSON and extracting results.
+    pub parse: std::time::Duration,
+}
+
+/// Shortest (per-second) window reported by Brave's `X-RateLimit-*` headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RateLimitInfo {
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset_secs: u64,
+}
+
+/// Longest (monthly quota) window of Brave's `X-RateLimit-*` headers, when more than one is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaInfo {
+    pub limit: u64,
+    pub remaining: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ParsedSummary {
+    pub status: String,
+    pub title: Option<String>,
+    pub text: String,
+    pub sources: Vec<SummarySource>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchJobStatus {
+    Pending,
+    Running,
+    Partial,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchQueryStatus {
+    Pending,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchQueryResult {
+    pub query: String,
+    pub status: BatchQueryStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ToolErrorInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchScheduleResponse {
+    pub api_version: String,
+    pub job_id: String,
+    pub status: BatchJobStatus,
+    pub total: usize,
+    pub interval_ms: u64,
+    pub estimated_completion_secs: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<WarningEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchStatusResponse {
+    pub api_version: String,
+    pub job_id: String,
+    pub status: BatchJobStatus,
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub results: Vec<BatchQueryResult>,
+}
+
+// serde hands `skip_serializing_if` predicates a reference.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_zero_usize(value: &usize) -> bool {
+    *value == 0
 }
@@ -0,0 +1,140 @@
+use codex_brave_web_search::brave_schema::SearchPayload;
+use codex_brave_web_search::parsing::{parse_local_pois, parse_sections};
+use codex_brave_web_search::types::{SearchType, WarningCode, WebResultFilter};
+
+#[test]
+fn wrong_field_types_read_as_missing() {
+    let payload = SearchPayload::from_value(&serde_json::json!({
+        "query": {"original": 42, "more_results_available": "yes"},
+        "web": {
+            "results": [
+                "not an object",
+                {
+                    "title": "Typed",
+                    "url": "https://example.com/typed",
+                    "description": ["wrong"],
+                    "extra_snippets": ["kept", 7, "also kept"],
+                    "profile": "wrong",
+                    "age": 3,
+                    "thumbnail": {"src": false},
+                    "is_live": "true"
+                }
+            ]
+        },
+        "news": []
+    }));
+
+    let query = payload.query.as_ref().expect("query object");
+    assert_eq!(query.original, None);
+    assert!(!payload.more_results_available());
+    assert!(payload.news.is_none());
+
+    let results = &payload.web.as_ref().expect("web section").results;
+    assert_eq!(results.len(), 1);
+    let result = &results[0];
+    assert_eq!(result.title.as_deref(), Some("Typed"));
+    assert_eq!(result.description, None);
+    assert_eq!(result.extra_snippets, ["kept", "also kept"]);
+    assert!(result.profile.is_none());
+    assert_eq!(result.age.as_deref(), Some("3"));
+    assert!(
+        result
+            .thumbnail
+            .as_ref()
+            .is_some_and(|thumb| thumb.src.is_none())
+    );
+    assert!(!result.is_live);
+}
+
+#[test]
+fn malformed_sections_warn_and_keep_the_sections_that_parsed() {
+    let payload = serde_json::json!({
+        "web": {
+            "results": [
+                {"title": "Kept", "url": "https://example.com/kept"},
+                "corrupted",
+                42
+            ]
+        },
+        "discussions": "corrupted",
+        "news": {"results": {"title": "Not a list"}},
+        "faq": {"results": [{"question": "Fine?", "answer": "Yes", "url": "https://example.com/faq"}]}
+    });
+    let filters = [
+        WebResultFilter::Web,
+        WebResultFilter::Discussions,
+        WebResultFilter::News,
+        WebResultFilter::Faq,
+    ];
+
+    let parsed = parse_sections(&payload, SearchType::Web, &filters, 5, false);
+
+    let counts = parsed
+        .sections
+        .iter()
+        .map(|section| (section.provider.as_str(), section.results.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        counts,
+        [("web", 1), ("discussions", 0), ("news", 0), ("faq", 1)]
+    );
+    let failures = parsed
+        .warnings
+        .iter()
+        .filter(|warning| warning.code == WarningCode::SectionParseFailed)
+        .map(|warning| warning.message.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(failures.len(), 3);
+    assert!(failures[0].contains("'web'") && failures[0].contains("2 of 3 results"));
+    assert!(failures[1].contains("'discussions'") && failures[1].contains("a string"));
+    assert!(failures[2].contains("'news'") && failures[2].contains("`results` is an object"));
+
+    let corrupted_standalone = serde_json::json!({"type": "videos", "results": "corrupted"});
+    let parsed = parse_sections(&corrupted_standalone, SearchType::Videos, &[], 5, false);
+    assert!(
+        parsed
+            .warnings
+            .iter()
+            .any(|warning| warning.code == WarningCode::SectionParseFailed)
+    );
+
+    let healthy = serde_json::json!({
+        "web": {"results": [{"title": "Fine", "url": "https://example.com/fine"}]}
+    });
+    let parsed = parse_sections(&healthy, SearchType::Web, &[], 5, false);
+    assert!(
+        parsed
+            .warnings
+            .iter()
+            .all(|warning| warning.code != WarningCode::SectionParseFailed)
+    );
+}
+
+#[test]
+fn standalone_endpoint_results_and_non_object_payloads() {
+    let news = serde_json::json!({
+        "type": "news",
+        "results": [{"title": "Headline", "url": "https://news.example/1", "page_age": "2026-01-30"}]
+    });
+    let parsed = parse_sections(&news, SearchType::News, &[], 5, false);
+    assert_eq!(parsed.sections[0].results.len(), 1);
+    assert_eq!(
+        parsed.sections[0].results[0].published.as_deref(),
+        Some("2026-01-30")
+    );
+
+    for payload in [
+        serde_json::json!([1, 2]),
+        serde_json::json!("text"),
+        serde_json::Value::Null,
+    ] {
+        let parsed = parse_sections(&payload, SearchType::Web, &[], 5, false);
+        assert!(
+            parsed
+                .sections
+                .iter()
+                .all(|section| section.results.is_empty())
+        );
+        assert!(parse_local_pois(&payload).is_empty());
+    }
+}
@@ -1,4 +1,7 @@
 use codex_brave_web_search::cache::SearchCache;
+use codex_brave_web_search::single_flight::SingleFlight;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 #[tokio::test]
@@ -27,3 +30,200 @@ async fn insert_keeps_unexpired_entries() {
     assert_eq!(cache.get("b").await, Some(2));
     assert_eq!(cache.len().await, 2);
 }
+
+/// Cache value whose `clone` (run by `get` under its shard's read lock) parks until released,
+/// so a test can pin one shard's lock for as long as it likes.
+#[derive(Default)]
+struct Gate {
+    entered: AtomicBool,
+    released: AtomicBool,
+}
+
+#[derive(Default)]
+struct Parked(Arc<Gate>);
+
+impl Clone for Parked {
+    fn clone(&self) -> Self {
+        self.0.entered.store(true, Ordering::SeqCst);
+        while !self.0.released.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        Self(Arc::clone(&self.0))
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn inserts_to_other_shards_complete_while_one_shard_is_locked() {
+    let cache = Arc::new(SearchCache::new(Duration::from_secs(60)));
+    let gate = Arc::new(Gate::default());
+    cache
+        .insert("held".to_string(), Parked(Arc::clone(&gate)))
+        .await;
+
+    let reader = {
+        let cache = Arc::clone(&cache);
+        tokio::spawn(async move { cache.get("held").await.is_some() })
+    };
+    while !gate.entered.load(Ordering::SeqCst) {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    // With one map behind one lock nothing could be written now; with shards only keys that
+    // hash to the held shard have to wait.
+    let total = 256usize;
+    let mut completed = 0usize;
+    for index in 0..total {
+        let insert = cache.insert(format!("key-{index}"), Parked::default());
+        if tokio::time::timeout(Duration::from_millis(20), insert)
+            .await
+            .is_ok()
+        {
+            completed += 1;
+        }
+    }
+
+    gate.released.store(true, Ordering::SeqCst);
+    assert!(reader.await.expect("reader task"));
+    assert!(
+        completed > total / 2,
+        "{completed} of {total} inserts completed"
+    );
+    assert!(completed < total, "no key hashed to the held shard");
+}
+
+/// Inserts `tasks * per_task` distinct `{prefix}-{task}-{index}` keys from concurrent tasks.
+async fn insert_concurrently(
+    cache: &Arc<SearchCache<usize>>,
+    prefix: &'static str,
+    tasks: usize,
+    per_task: usize,
+) {
+    let handles = (0..tasks)
+        .map(|task| {
+            let cache = Arc::clone(cache);
+            tokio::spawn(async move {
+                for index in 0..per_task {
+                    cache
+                        .insert(format!("{prefix}-{task}-{index}"), index)
+                        .await;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.await.expect("insert task");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_inserts_across_shards_keep_capacity_and_lru_order() {
+    // 512 entries spread over 8 shards of 64.
+    let cache = Arc::new(SearchCache::with_max_entries(Duration::from_secs(60), 512));
+    insert_concurrently(&cache, "cold", 16, 200).await;
+
+    let stats = cache.stats().await;
+    assert_eq!(stats.max_entries, Some(512));
+    assert!(stats.entries <= 512, "entries: {}", stats.entries);
+    assert_eq!(stats.evictions + stats.entries as u64, 16 * 200);
+
+    // Touching four survivors makes them more recently used than every other entry. Fewer new
+    // keys than a shard's remaining cold entries then must evict cold entries only, whichever
+    // shards the keys hash to.
+    let mut hot = Vec::new();
+    for task in 0..16 {
+        for index in 0..200 {
+            let key = format!("cold-{task}-{index}");
+            if hot.len() < 4 && cache.get(&key).await.is_some() {
+                hot.push(key);
+            }
+        }
+    }
+    assert_eq!(hot.len(), 4);
+    insert_concurrently(&cache, "new", 4, 15).await;
+
+    for key in &hot {
+        assert!(cache.get(key).await.is_some(), "{key} was evicted");
+    }
+    let stats = cache.stats().await;
+    assert!(stats.entries <= 512, "entries: {}", stats.entries);
+    assert_eq!(stats.evictions + stats.entries as u64, 16 * 200 + 4 * 15);
+}
+
+#[tokio::test]
+async fn max_age_treats_older_entries_as_misses() {
+    let cache = SearchCache::new(Duration::from_secs(60));
+
+    cache.insert("key".to_string(), 1usize).await;
+    tokio::time::sleep(Duration::from_millis(30)).await;
+
+    assert_eq!(
+        cache
+            .get_max_age("key", Some(Duration::from_millis(10)))
+            .await,
+        None
+    );
+    assert_eq!(
+        cache.get_max_age("key", Some(Duration::from_secs(5))).await,
+        Some(1)
+    );
+    assert_eq!(cache.get("key").await, Some(1));
+}
+
+#[tokio::test]
+async fn single_flight_runs_identical_concurrent_calls_once() {
+    let flight = SingleFlight::<usize>::new();
+    let runs = std::sync::atomic::AtomicUsize::new(0);
+    let work = || async {
+        runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        7usize
+    };
+
+    let (first, second) = tokio::join!(flight.run("key", work), flight.run("key", work));
+
+    assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert_eq!([first.0, second.0], [7, 7]);
+    assert!(first.1 != second.1);
+    assert_eq!(flight.in_flight(), 0);
+
+    let (_, coalesced) = flight.run("key", work).await;
+    assert!(!coalesced);
+    assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn bounded_cache_evicts_least_recently_used_and_counts() {
+    let cache = SearchCache::with_max_entries(Duration::from_secs(60), 3);
+
+    cache.insert("a".to_string(), 1usize).await;
+    cache.insert("b".to_string(), 2usize).await;
+    cache.insert("c".to_string(), 3usize).await;
+    assert_eq!(cache.get("a").await, Some(1));
+    cache.insert("d".to_string(), 4usize).await;
+
+    assert_eq!(cache.get("b").await, None);
+    assert_eq!(cache.get("a").await, Some(1));
+    assert_eq!(cache.get("c").await, Some(3));
+    assert_eq!(cache.get("d").await, Some(4));
+
+    let stats = cache.stats().await;
+    assert_eq!(stats.entries, 3);
+    assert_eq!(stats.max_entries, Some(3));
+    assert_eq!(stats.hits, 4);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.evictions, 1);
+}
+
+#[tokio::test]
+async fn bounded_cache_never_exceeds_max_entries() {
+    let cache = SearchCache::with_max_entries(Duration::from_secs(60), 1_000);
+    for index in 0..5_000usize {
+        cache.insert(format!("key-{index}"), index).await;
+    }
+
+    let stats = cache.stats().await;
+    assert_eq!(stats.max_entries, Some(1_000));
+    // Every shard fills up, and their limits add up to the requested bound.
+    assert_eq!(stats.entries, 1_000);
+    assert_eq!(stats.evictions, 5_000 - stats.entries as u64);
+}
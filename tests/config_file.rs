@@ -0,0 +1,182 @@
+use codex_brave_web_search::config::{ApiKeyConfig, RuntimeConfig, TransportMode};
+use codex_brave_web_search::config_file::load_runtime_config;
+use codex_brave_web_search::constants::DEFAULT_RETRY_COUNT;
+use codex_brave_web_search::service::SearchService;
+use codex_brave_web_search::types::StatusArgs;
+use serial_test::serial;
+
+fn write_config_file(name: &str, contents: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("{name}-{}.toml", std::process::id()));
+    std::fs::write(&path, contents).expect("write config file");
+    path
+}
+
+#[test]
+#[serial]
+fn config_file_values_apply_beneath_env_overrides() {
+    let path = write_config_file(
+        "codex-brave-config",
+        "cache_ttl_secs = 42\nretry_count = 4\ntransport = \"http\"\nthrottle_adaptive = false\n",
+    );
+
+    let config = temp_env::with_vars(
+        [
+            ("CODEX_BRAVE_RETRY_COUNT", Some("7")),
+            ("CODEX_BRAVE_CACHE_TTL_SECS", None),
+            ("CODEX_BRAVE_TRANSPORT", None),
+            ("CODEX_BRAVE_THROTTLE_ADAPTIVE", None),
+        ],
+        || load_runtime_config(Some(&path)),
+    )
+    .expect("valid config file");
+
+    assert_eq!(config.cache_ttl_secs, 42);
+    assert_eq!(config.retry_count, 7);
+    assert_eq!(config.transport, TransportMode::Http);
+    assert!(!config.throttle_adaptive);
+
+    let from_env = temp_env::with_vars(
+        [
+            (
+                "CODEX_BRAVE_CONFIG",
+                Some(path.to_str().expect("utf-8 path")),
+            ),
+            ("CODEX_BRAVE_RETRY_COUNT", None),
+        ],
+        || load_runtime_config(None),
+    )
+    .expect("config file from CODEX_BRAVE_CONFIG");
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(from_env.retry_count, 4);
+}
+
+#[test]
+#[serial]
+fn invalid_config_values_fail_startup() {
+    let unknown = write_config_file("codex-brave-config-unknown", "cache_ttl = 42\n");
+    let error = load_runtime_config(Some(&unknown)).expect_err("unknown key rejected");
+    std::fs::remove_file(&unknown).ok();
+    assert!(error.contains("unknown field `cache_ttl`"), "{error}");
+
+    let out_of_range = write_config_file("codex-brave-config-range", "retry_count = 11\n");
+    let error = temp_env::with_var_unset("CODEX_BRAVE_RETRY_COUNT", || {
+        load_runtime_config(Some(&out_of_range))
+    })
+    .expect_err("out-of-range value rejected");
+    assert!(error.contains("retry_count"), "{error}");
+    assert!(error.contains("at most 10"), "{error}");
+
+    let config = temp_env::with_var("CODEX_BRAVE_RETRY_COUNT", Some("lots"), || {
+        load_runtime_config(Some(&out_of_range))
+    })
+    .expect("env overrides are only warned about");
+    std::fs::remove_file(&out_of_range).ok();
+    assert_eq!(
+        config.config_warnings,
+        vec![r#"CODEX_BRAVE_RETRY_COUNT="lots": expected a non-negative integer"#.to_string()]
+    );
+
+    let mistyped = write_config_file("codex-brave-config-type", "transport = 3\n");
+    let error = load_runtime_config(Some(&mistyped)).expect_err("mistyped value rejected");
+    std::fs::remove_file(&mistyped).ok();
+    assert!(error.contains("invalid"), "{error}");
+}
+
+#[tokio::test]
+#[serial]
+async fn invalid_env_values_are_reported_in_status() {
+    let config = temp_env::with_vars(
+        [
+            ("CODEX_BRAVE_RETRY_COUNT", Some("abc")),
+            ("CODEX_BRAVE_MIRROR_PERCENT", Some("150")),
+            ("CODEX_BRAVE_TRANSPORT", Some("carrier-pigeon")),
+        ],
+        RuntimeConfig::from_env,
+    );
+    assert_eq!(config.retry_count, DEFAULT_RETRY_COUNT);
+    assert_eq!(
+        config.config_warnings.len(),
+        3,
+        "{:?}",
+        config.config_warnings
+    );
+
+    let service = SearchService::new(config).expect("service");
+    let status = service
+        .status(
+            StatusArgs {
+                probe_connectivity: None,
+                verbose: None,
+                include_limits: None,
+            },
+            || false,
+        )
+        .await;
+    let json = serde_json::to_value(&status).expect("serialize status");
+    let warnings = json["config_warnings"].as_array().expect("config_warnings");
+    assert!(warnings.iter().any(|warning| {
+        warning.as_str().is_some_and(|text| {
+            text.starts_with("CODEX_BRAVE_MIRROR_PERCENT=\"150\": must be at most 100")
+        })
+    }));
+}
+
+#[test]
+#[serial]
+fn disk_writing_settings_require_the_archive_feature() {
+    let config = temp_env::with_vars(
+        [
+            ("CODEX_BRAVE_ARCHIVE_DIR", Some("/tmp/codex-brave-archive")),
+            ("CODEX_BRAVE_RECORD_DIR", Some("/tmp/codex-brave-record")),
+        ],
+        RuntimeConfig::from_env,
+    );
+    assert_eq!(config.archive_dir.is_some(), cfg!(feature = "archive"));
+    assert_eq!(config.record.is_some(), cfg!(feature = "archive"));
+    assert_eq!(
+        config.config_warnings,
+        if cfg!(feature = "archive") {
+            Vec::new()
+        } else {
+            vec![
+                r#"CODEX_BRAVE_ARCHIVE_DIR="/tmp/codex-brave-archive": requires a build with the archive feature"#
+                    .to_string(),
+                r#"CODEX_BRAVE_RECORD_DIR="/tmp/codex-brave-record": requires a build with the archive feature"#
+                    .to_string(),
+            ]
+        }
+    );
+}
+
+#[test]
+#[serial]
+fn api_key_is_read_from_a_key_file_or_command() {
+    let key_file = write_config_file("codex-brave-key-file", "file-key\n");
+    let key_from = |file: Option<&str>, command: Option<&str>| {
+        temp_env::with_vars(
+            [
+                ("CODEX_BRAVE_API_KEY_FILE", None),
+                ("BRAVE_SEARCH_API_KEY_FILE", file),
+                ("BRAVE_SEARCH_API_KEY_CMD", command),
+                ("BRAVE_SEARCH_API_KEY", Some("env-key")),
+                ("BRAVE_API_KEY", None),
+            ],
+            ApiKeyConfig::from_env,
+        )
+    };
+    let path = key_file.to_str().expect("utf-8 path");
+
+    let config = key_from(Some(path), Some("echo cmd-key"));
+    assert_eq!(config.key.as_deref(), Some("file-key"));
+    assert_eq!(config.source.as_deref(), Some("BRAVE_SEARCH_API_KEY_FILE"));
+
+    let config = key_from(None, Some("echo cmd-key"));
+    std::fs::remove_file(&key_file).ok();
+    assert_eq!(config.key.as_deref(), Some("cmd-key"));
+    assert_eq!(config.source.as_deref(), Some("BRAVE_SEARCH_API_KEY_CMD"));
+
+    let config = key_from(None, Some("exit 1"));
+    assert_eq!(config.key.as_deref(), Some("env-key"));
+    assert_eq!(config.source.as_deref(), Some("BRAVE_SEARCH_API_KEY"));
+}
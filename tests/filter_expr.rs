@@ -0,0 +1,126 @@
+use codex_brave_web_search::filter_expr::FilterExpr;
+use codex_brave_web_search::types::{BraveSectionName, NormalizedResult, ParsedSection};
+
+// 2026-01-31T00:00:00Z
+const NOW: u64 = 1_769_817_600;
+
+fn result(url: &str, published: Option<&str>) -> NormalizedResult {
+    NormalizedResult {
+        title: "Federal Guidance".to_string(),
+        url: url.to_string(),
+        snippet: "Rust memory safety guidance".to_string(),
+        extra_snippets: Vec::new(),
+        source: Some("Agency".to_string()),
+        age: None,
+        published: published.map(str::to_string),
+        item_type: Some("search_result".to_string()),
+        subtype: None,
+        duration: None,
+        creator: None,
+        publisher: None,
+        views: None,
+        embeddable: None,
+        location: None,
+        is_live: false,
+        breaking: false,
+        hostname: None,
+        thumbnail: None,
+        poi_id: None,
+        local: None,
+        image: None,
+        origin_section: None,
+        provider_rank: 1,
+    }
+}
+
+fn matches(expr: &str, result: &NormalizedResult) -> bool {
+    FilterExpr::parse(expr)
+        .expect("valid filter")
+        .matches(BraveSectionName::Web, result, NOW)
+}
+
+#[test]
+fn domain_and_age_comparisons() {
+    let recent_gov = result("https://www.cisa.gov/guidance", Some("2026-01-20T08:00:00"));
+    let old_gov = result("https://nist.gov/old", Some("2025-06-01"));
+    let recent_com = result("https://example.com/post", Some("2026-01-30"));
+
+    let expr = "domain endswith '.gov' and age < 30d";
+    assert!(matches(expr, &recent_gov));
+    assert!(!matches(expr, &old_gov));
+    assert!(!matches(expr, &recent_com));
+
+    assert!(matches("age >= 26w", &old_gov));
+    assert!(!matches("age < 1y", &result("https://a.gov", None)));
+}
+
+#[test]
+fn boolean_operators_parentheses_and_case_insensitive_text() {
+    let item = result("https://example.com/post", Some("2026-01-30"));
+
+    assert!(matches(
+        "(domain == 'EXAMPLE.com' or domain endswith '.gov') and not live",
+        &item
+    ));
+    assert!(matches(
+        "text contains \"memory safety\" and section == 'web'",
+        &item
+    ));
+    assert!(matches(
+        "title startswith 'federal' and published >= '2026-01-01'",
+        &item
+    ));
+    assert!(!matches("live or source != 'agency'", &item));
+}
+
+#[test]
+fn retain_matching_drops_results_and_empty_sections() {
+    let filter = FilterExpr::parse("domain endswith '.gov'").expect("valid filter");
+    let mut sections = vec![
+        ParsedSection {
+            key: BraveSectionName::Web,
+            label: "Web results".to_string(),
+            provider: "web".to_string(),
+            results: vec![
+                result("https://a.gov/1", None),
+                result("https://b.com/2", None),
+            ],
+            section_limit_reached: false,
+        },
+        ParsedSection {
+            key: BraveSectionName::News,
+            label: "News".to_string(),
+            provider: "news".to_string(),
+            results: vec![result("https://c.com/3", None)],
+            section_limit_reached: false,
+        },
+    ];
+
+    assert_eq!(filter.retain_matching(&mut sections, NOW), 2);
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0].results[0].url, "https://a.gov/1");
+}
+
+#[test]
+fn invalid_expressions_report_position() {
+    for (expr, position) in [
+        ("", 0),
+        ("colour == 'red'", 0),
+        ("age < 30", 6),
+        ("domain contains 5d", 16),
+        ("title == 'open", 9),
+        ("(title == 'a'", 13),
+        ("title == 'a' title", 13),
+        ("live contains 'x'", 14),
+    ] {
+        let error = FilterExpr::parse(expr).expect_err(expr);
+        assert_eq!(error.code(), "INVALID_ARGUMENT", "{expr}");
+        let details = error.details().expect("details");
+        assert_eq!(details["field"], "filter");
+        assert_eq!(details["position"], position, "{expr}");
+    }
+
+    let deep = format!("{}title == 'a'{}", "(".repeat(40), ")".repeat(40));
+    assert!(FilterExpr::parse(&deep).is_err());
+    assert!(FilterExpr::parse(&"a".repeat(600)).is_err());
+}
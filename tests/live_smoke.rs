@@ -21,25 +21,16 @@ fn args_for(search_type: &str) -> BraveWebSearchArgs {
     BraveWebSearchArgs {
         query: "openai".to_string(),
         search_type: Some(search_type.to_string()),
-        result_filter: None,
         max_results: Some(2),
         offset: Some(0),
-        country: None,
         search_language: Some("en".to_string()),
         ui_language: Some("en-US".to_string()),
-        safe_search: None,
-        units: None,
-        freshness: None,
         spellcheck: Some(true),
         extra_snippets: Some(false),
-        text_decorations: None,
         max_lines: Some(120),
         max_bytes: Some(32 * 1024),
         debug: Some(false),
-        include_raw_payload: None,
-        disable_cache: None,
-        disable_throttle: None,
-        include_request_url: None,
+        ..Default::default()
     }
 }
 
@@ -0,0 +1,75 @@
+use codex_brave_web_search::config::RuntimeConfig;
+use codex_brave_web_search::normalization::{
+    normalize_country_with, normalize_ui_language_with, pick_locale_language_with,
+};
+use serial_test::serial;
+
+fn write_locale_file(name: &str, contents: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("{name}-{}.json", std::process::id()));
+    std::fs::write(&path, contents).expect("write locale data file");
+    path
+}
+
+#[test]
+#[serial]
+fn supplemental_locale_file_is_merged_with_builtin_lists() {
+    let path = write_locale_file(
+        "codex-brave-locales",
+        r#"{ "countries": ["vn", "US"], "search_languages": ["Fil"], "ui_languages": ["vi_vn"] }"#,
+    );
+
+    let config = temp_env::with_var("CODEX_BRAVE_LOCALE_DATA_FILE", Some(&path), || {
+        RuntimeConfig::from_env()
+    });
+    std::fs::remove_file(&path).ok();
+
+    assert!(config.locale_data_error.is_none());
+    let locales = &config.locales;
+    assert!(locales.countries.contains(&"VN".to_string()));
+    assert_eq!(
+        locales
+            .countries
+            .iter()
+            .filter(|country| *country == "US")
+            .count(),
+        1
+    );
+    assert!(locales.search_languages.contains(&"fil".to_string()));
+    assert!(locales.ui_languages.contains(&"vi-VN".to_string()));
+
+    assert_eq!(
+        normalize_country_with(Some("vn"), &locales.countries),
+        Some("VN".to_string())
+    );
+    assert_eq!(
+        pick_locale_language_with(Some("fil-PH"), &locales.search_languages),
+        Some("fil".to_string())
+    );
+    assert_eq!(
+        normalize_ui_language_with(Some("vi-vn"), &locales.ui_languages),
+        Some("vi-VN".to_string())
+    );
+}
+
+#[test]
+#[serial]
+fn invalid_locale_file_falls_back_to_builtin_lists_with_error() {
+    let path = write_locale_file("codex-brave-locales-invalid", r#"{ "regions": [] }"#);
+
+    let config = temp_env::with_var("CODEX_BRAVE_LOCALE_DATA_FILE", Some(&path), || {
+        RuntimeConfig::from_env()
+    });
+    std::fs::remove_file(&path).ok();
+
+    assert!(
+        config
+            .locale_data_error
+            .as_deref()
+            .is_some_and(|error| error.contains("CODEX_BRAVE_LOCALE_DATA_FILE"))
+    );
+    assert!(config.locales.countries.contains(&"US".to_string()));
+    assert_eq!(
+        normalize_country_with(Some("vn"), &config.locales.countries),
+        None
+    );
+}
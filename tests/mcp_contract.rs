@@ -1,14 +1,23 @@
 use codex_brave_web_search::config::RuntimeConfig;
 use codex_brave_web_search::constants::{
-    TOOL_BRAVE_WEB_SEARCH, TOOL_BRAVE_WEB_SEARCH_HELP, TOOL_BRAVE_WEB_SEARCH_STATUS,
+    TOOL_BRAVE_BATCH_STATUS, TOOL_BRAVE_COMPARE_PARAMS, TOOL_BRAVE_RELOAD_KEY,
+    TOOL_BRAVE_SCHEDULE_BATCH, TOOL_BRAVE_SUGGEST, TOOL_BRAVE_WEB_SEARCH,
+    TOOL_BRAVE_WEB_SEARCH_HELP, TOOL_BRAVE_WEB_SEARCH_HISTORY, TOOL_BRAVE_WEB_SEARCH_METRICS,
+    TOOL_BRAVE_WEB_SEARCH_REPLAY, TOOL_BRAVE_WEB_SEARCH_STATUS, TOOL_BRAVE_WEB_SEARCH_SUMMARIZE,
 };
+use codex_brave_web_search::http_transport;
 use codex_brave_web_search::mcp_server::BraveSearchMcpServer;
 use codex_brave_web_search::service::SearchService;
 use mcpkit::capability::{ClientCapabilities, ServerCapabilities};
-use mcpkit::protocol::RequestId;
+use mcpkit::error::McpError;
+use mcpkit::protocol::{Notification, RequestId};
 use mcpkit::protocol_version::ProtocolVersion;
 use mcpkit::types::tool::CallToolResult;
-use mcpkit::{Context, NoOpPeer, ToolHandler};
+use mcpkit::{Context, LogLevel, LoggingHandler, NoOpPeer, Peer, ToolHandler};
+use std::future::Future;
+use std::pin::Pin;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
 
 fn make_context() -> (
     RequestId,
@@ -55,7 +64,108 @@ fn parse_tool_error_json(result: mcpkit::types::tool::ToolOutput) -> serde_json:
 }
 
 #[tokio::test]
-async fn lists_three_tools_with_expected_names() {
+async fn streamable_http_serves_initialize_and_tools_list() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let endpoint = format!("http://{}/mcp", listener.local_addr().expect("local addr"));
+    tokio::spawn(http_transport::serve(make_server(), listener));
+    let http = reqwest::Client::new();
+
+    let initialize = http
+        .post(&endpoint)
+        .json(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": ProtocolVersion::LATEST.as_str(),
+                "capabilities": {},
+                "clientInfo": {"name": "http-test", "version": "0"}
+            }
+        }))
+        .send()
+        .await
+        .expect("initialize");
+    assert_eq!(initialize.status(), 200);
+    let session = initialize.headers()["mcp-session-id"]
+        .to_str()
+        .expect("session id")
+        .to_string();
+    let body = initialize
+        .json::<serde_json::Value>()
+        .await
+        .expect("initialize body");
+    assert_eq!(body["id"], 1);
+    assert!(body["result"]["serverInfo"]["name"].is_string());
+
+    let initialized = http
+        .post(&endpoint)
+        .header("mcp-session-id", &session)
+        .json(&serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+        .send()
+        .await
+        .expect("initialized");
+    assert_eq!(initialized.status(), 202);
+
+    let list_tools = serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"});
+    let tools = http
+        .post(&endpoint)
+        .header("mcp-session-id", &session)
+        .json(&list_tools)
+        .send()
+        .await
+        .expect("tools/list")
+        .json::<serde_json::Value>()
+        .await
+        .expect("tools/list body");
+    assert_eq!(tools["id"], 2);
+    let names = tools["result"]["tools"]
+        .as_array()
+        .expect("tools array")
+        .iter()
+        .filter_map(|tool| tool["name"].as_str())
+        .collect::<Vec<_>>();
+    assert!(names.contains(&TOOL_BRAVE_WEB_SEARCH));
+    assert!(names.contains(&TOOL_BRAVE_WEB_SEARCH_STATUS));
+
+    let without_session = http
+        .post(&endpoint)
+        .json(&list_tools)
+        .send()
+        .await
+        .expect("tools/list without session");
+    assert_eq!(without_session.status(), 400);
+
+    let foreign_origin = http
+        .post(&endpoint)
+        .header("origin", "https://attacker.example")
+        .header("mcp-session-id", &session)
+        .json(&list_tools)
+        .send()
+        .await
+        .expect("tools/list from a foreign origin");
+    assert_eq!(foreign_origin.status(), 403);
+
+    let closed = http
+        .delete(&endpoint)
+        .header("mcp-session-id", &session)
+        .send()
+        .await
+        .expect("delete session");
+    assert_eq!(closed.status(), 204);
+    let after_close = http
+        .post(&endpoint)
+        .header("mcp-session-id", &session)
+        .json(&list_tools)
+        .send()
+        .await
+        .expect("tools/list after close");
+    assert_eq!(after_close.status(), 404);
+}
+
+#[tokio::test]
+async fn lists_all_tools_with_expected_names() {
     let server = make_server();
     let (req_id, client_caps, server_caps, protocol_version, peer) = make_context();
     let ctx = Context::new(
@@ -76,10 +186,28 @@ async fn lists_three_tools_with_expected_names() {
         .map(|tool| tool.name.as_str())
         .collect::<Vec<&str>>();
 
-    assert_eq!(names.len(), 3);
+    assert_eq!(
+        names.len(),
+        10 + usize::from(cfg!(feature = "archive")) + usize::from(cfg!(feature = "metrics"))
+    );
     assert!(names.contains(&TOOL_BRAVE_WEB_SEARCH));
     assert!(names.contains(&TOOL_BRAVE_WEB_SEARCH_HELP));
     assert!(names.contains(&TOOL_BRAVE_WEB_SEARCH_STATUS));
+    assert!(names.contains(&TOOL_BRAVE_WEB_SEARCH_SUMMARIZE));
+    assert!(names.contains(&TOOL_BRAVE_SUGGEST));
+    assert!(names.contains(&TOOL_BRAVE_SCHEDULE_BATCH));
+    assert!(names.contains(&TOOL_BRAVE_BATCH_STATUS));
+    assert!(names.contains(&TOOL_BRAVE_COMPARE_PARAMS));
+    assert!(names.contains(&TOOL_BRAVE_RELOAD_KEY));
+    assert_eq!(
+        names.contains(&TOOL_BRAVE_WEB_SEARCH_REPLAY),
+        cfg!(feature = "archive")
+    );
+    assert_eq!(
+        names.contains(&TOOL_BRAVE_WEB_SEARCH_METRICS),
+        cfg!(feature = "metrics")
+    );
+    assert!(names.contains(&TOOL_BRAVE_WEB_SEARCH_HISTORY));
 
     // additionalProperties false for strict unknown-field rejection
     let search_tool = tools
@@ -395,3 +523,115 @@ async fn unknown_parameter_is_rejected_by_schema_deserializer() {
             .is_some_and(|message| message.contains("unknown field"))
     );
 }
+
+#[derive(Default)]
+struct RecordingPeer {
+    notifications: std::sync::Mutex<Vec<Notification>>,
+}
+
+impl Peer for RecordingPeer {
+    fn notify(
+        &self,
+        notification: Notification,
+    ) -> Pin<Box<dyn Future<Output = Result<(), McpError>> + Send + '_>> {
+        self.notifications
+            .lock()
+            .expect("notifications lock")
+            .push(notification);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[tokio::test]
+async fn operational_events_are_sent_as_logging_notifications_at_the_client_level() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("X-RateLimit-Limit", "1, 1000")
+                .insert_header("X-RateLimit-Remaining", "0, 50")
+                .set_body_json(serde_json::json!({"web": {"results": []}})),
+        )
+        .up_to_n_times(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(429))
+        .mount(&upstream)
+        .await;
+
+    let server = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        let mut config = RuntimeConfig::from_env();
+        config.endpoints.web = format!("{}/web", upstream.uri());
+        config.retry_count = 0;
+        BraveSearchMcpServer::new(SearchService::new(config).expect("service should initialize"))
+    });
+    let peer = RecordingPeer::default();
+    let (req_id, client_caps, server_caps, protocol_version, _) = make_context();
+    let ctx = Context::new(
+        &req_id,
+        None,
+        &client_caps,
+        &server_caps,
+        protocol_version,
+        &peer,
+    );
+    let search = |query: &str| {
+        server.call_tool(
+            TOOL_BRAVE_WEB_SEARCH,
+            serde_json::json!({"query": query, "disable_cache": true, "debug": true}),
+            &ctx,
+        )
+    };
+
+    // QUOTA_LOW is a warning, below the requested level, so it is dropped.
+    server.set_level(LogLevel::Error).await.expect("set level");
+    search("quota").await.expect("search executes");
+    assert!(peer.notifications.lock().expect("lock").is_empty());
+
+    server
+        .set_level(LogLevel::Warning)
+        .await
+        .expect("set level");
+    for query in ["one", "two", "three"] {
+        search(query).await.expect("search executes");
+    }
+    let notifications = peer.notifications.lock().expect("lock");
+    assert_eq!(notifications.len(), 1, "{notifications:?}");
+    assert_eq!(notifications[0].method, "notifications/message");
+    let params = notifications[0].params.as_ref().expect("params");
+    assert_eq!(params["level"], "warning");
+    assert_eq!(params["logger"], "brave-web-search");
+    assert_eq!(params["data"]["code"], "RATE_LIMITED");
+}
+
+#[test]
+fn conformance_example_passes_against_the_built_server() {
+    let server = std::path::Path::new(env!("CARGO_BIN_EXE_codex-brave-web-search"));
+    let example = server
+        .with_file_name("examples")
+        .join(format!("conformance{}", std::env::consts::EXE_SUFFIX));
+    // `cargo test` builds the examples; a run filtered to one test target may not.
+    if !example.exists() {
+        eprintln!("skipping: {} is not built", example.display());
+        return;
+    }
+
+    let output = std::process::Command::new(&example)
+        .arg(server)
+        .output()
+        .expect("conformance example should run");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{stdout}{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("ok   tools/list"), "{stdout}");
+    assert!(
+        stdout.contains("conformance: all checks passed"),
+        "{stdout}"
+    );
+}
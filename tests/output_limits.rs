@@ -1,8 +1,15 @@
-use codex_brave_web_search::formatting::enforce_output_limits;
+use codex_brave_web_search::formatting::{
+    annotate_output_limits, enforce_output_limits, enforce_output_limits_with,
+    enforce_token_budget, estimate_tokens, result_diversity,
+};
+use codex_brave_web_search::render::{
+    CompactJsonRenderer, JsonRenderer, MarkdownRenderer, ResponseRenderer,
+};
 use codex_brave_web_search::types::{
-    BraveSectionName, DebugData, SearchMeta, SearchResponse, SearchResultItem, SearchSection,
-    SearchType, WarningEntry,
+    BraveSectionName, DebugData, EffectiveParams, SearchMeta, SearchResponse, SearchResultItem,
+    SearchSection, SearchType, WarningCode, WarningEntry,
 };
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 fn build_result(index: usize) -> SearchResultItem {
     SearchResultItem {
@@ -18,8 +25,20 @@ fn build_result(index: usize) -> SearchResultItem {
         subtype: Some("blog".to_string()),
         duration: None,
         creator: None,
+        publisher: None,
+        views: None,
+        embeddable: None,
         location: None,
         is_live: None,
+        breaking: None,
+        hostname: None,
+        thumbnail: None,
+        local: None,
+        image: None,
+        origin_section: None,
+        word_count: None,
+        estimated_reading_minutes: None,
+        injection_risk: None,
     }
 }
 
@@ -34,8 +53,13 @@ fn oversized_response() -> SearchResponse {
             results: vec![build_result(1), build_result(2)],
             section_limit_reached: false,
         }],
+        results: Vec::new(),
+        section_summaries: Vec::new(),
+        topics: Vec::new(),
+        next_cursor: None,
         meta: SearchMeta {
             query: "openai ".repeat(120),
+            sent_query: None,
             search_type: SearchType::Web,
             requested: 2,
             returned: 2,
@@ -46,17 +70,47 @@ fn oversized_response() -> SearchResponse {
             warnings_count: 2,
             server_version: "0.1.0".to_string(),
             trace_id: "trace-id-1234".to_string(),
+            endpoint_host: "api.search.brave.com".to_string(),
+            endpoint_override: false,
+            effective_params: Some(EffectiveParams {
+                country: None,
+                search_language: None,
+                ui_language: None,
+                safe_search: None,
+                units: None,
+                freshness: None,
+                goggles: None,
+                spellcheck: true,
+                extra_snippets: true,
+                text_decorations: false,
+                inferred: Vec::new(),
+            }),
+            cache_hit: false,
+            cache_age_secs: None,
+            throttle_tokens: 0,
+            rate_limit: None,
+            dedup: None,
+            rerank: None,
+            suppressed_repeats: None,
+            diversity: None,
+            pages_fetched: None,
+            exceeds_limits: false,
+            measured_size: None,
+            estimated_tokens: None,
+            upstream_ms: None,
         },
         warnings: vec![
             WarningEntry {
-                code: "A".to_string(),
+                code: WarningCode::Deduplicated,
                 message: "warning ".repeat(80),
             },
             WarningEntry {
-                code: "B".to_string(),
+                code: WarningCode::OffsetCapped,
                 message: "warning ".repeat(80),
             },
         ],
+        notices: Vec::new(),
+        explanations: Vec::new(),
         debug_data: Some(DebugData {
             request_url: Some("https://example.com/search?q=openai".to_string()),
             raw_payload: Some(serde_json::json!({"payload": "x".repeat(6_000)})),
@@ -64,6 +118,10 @@ fn oversized_response() -> SearchResponse {
             raw_payload_original_bytes: Some(6_500),
             cache_bypassed: false,
             throttle_bypassed: false,
+            accept_language: None,
+            trace_id: None,
+            timings: None,
+            http_version: None,
         }),
     }
 }
@@ -74,7 +132,7 @@ fn enforces_limits_when_only_debug_and_warnings_are_large() {
     response.sections.clear();
     response.meta.returned = 0;
 
-    enforce_output_limits(&mut response, 20, 1024);
+    enforce_output_limits(&mut response, 20, 1024, 0);
 
     let serialized = serde_json::to_string_pretty(&response).expect("serialize response");
     assert!(serialized.lines().count() <= 20);
@@ -94,7 +152,7 @@ fn enforces_limits_when_only_debug_and_warnings_are_large() {
 fn enforces_limits_by_removing_results_and_marking_has_more() {
     let mut response = oversized_response();
 
-    enforce_output_limits(&mut response, 36, 1800);
+    enforce_output_limits(&mut response, 36, 1800, 0);
 
     let serialized = serde_json::to_string_pretty(&response).expect("serialize response");
     assert!(serialized.lines().count() <= 36);
@@ -113,7 +171,7 @@ fn enforces_limits_by_removing_results_and_marking_has_more() {
 fn tiny_limits_can_drop_warning_but_remain_bounded() {
     let mut response = oversized_response();
 
-    enforce_output_limits(&mut response, 20, 640);
+    enforce_output_limits(&mut response, 20, 640, 0);
 
     let serialized = serde_json::to_string_pretty(&response).expect("serialize response");
     assert!(serialized.lines().count() <= 20);
@@ -126,3 +184,256 @@ fn tiny_limits_can_drop_warning_but_remain_bounded() {
                 .any(|warning| warning.code == "OUTPUT_TRUNCATED")
     );
 }
+
+#[test]
+fn section_floor_keeps_a_result_in_every_requested_section() {
+    let mut response = oversized_response();
+    response.debug_data = None;
+    response.sections = [
+        BraveSectionName::Web,
+        BraveSectionName::Discussions,
+        BraveSectionName::News,
+    ]
+    .into_iter()
+    .map(|key| SearchSection {
+        key,
+        label: key.as_str().to_string(),
+        provider: key.as_str().to_string(),
+        results: (1..=3).map(build_result).collect(),
+        section_limit_reached: false,
+    })
+    .collect();
+
+    let mut floored = response.clone();
+    for section in &mut floored.sections {
+        section.results.truncate(1);
+    }
+    let serialized = serde_json::to_string_pretty(&floored).expect("serialize response");
+    let max_lines = serialized.lines().count() + 10;
+    let max_bytes = serialized.len() + 400;
+
+    let mut without_floor = response.clone();
+    enforce_output_limits(&mut without_floor, max_lines, max_bytes, 0);
+    assert!(
+        without_floor
+            .sections
+            .iter()
+            .any(|section| section.results.is_empty())
+    );
+
+    enforce_output_limits(&mut response, max_lines, max_bytes, 1);
+    let serialized = serde_json::to_string_pretty(&response).expect("serialize response");
+    assert!(serialized.lines().count() <= max_lines);
+    assert!(serialized.len() <= max_bytes);
+    assert!(
+        response
+            .sections
+            .iter()
+            .all(|section| !section.results.is_empty())
+    );
+    assert!(response.meta.has_more);
+}
+
+#[test]
+fn tiny_limits_drop_cache_hit_meta_last_resort() {
+    let mut response = oversized_response();
+    response.meta.cache_hit = true;
+    response.meta.cache_age_secs = Some(42);
+
+    enforce_output_limits(&mut response, 20, 4096, 0);
+
+    let serialized = serde_json::to_string_pretty(&response).expect("serialize response");
+    assert!(serialized.lines().count() <= 20);
+    assert!(!response.meta.cache_hit);
+    assert_eq!(response.meta.cache_age_secs, None);
+}
+
+#[test]
+fn limits_are_measured_against_the_rendered_format() {
+    let mut response = oversized_response();
+    response.sections[0].results = (1..=8).map(build_result).collect();
+    response.meta.returned = 8;
+
+    enforce_output_limits_with(&mut response, &MarkdownRenderer, 24, 4096, 0);
+
+    let rendered = MarkdownRenderer.render(&response).expect("render markdown");
+    assert!(rendered.lines().count() <= 24);
+    assert!(rendered.len() <= 4096);
+    assert!(response.meta.returned < 8);
+    // The JSON form of the same response would still be far over the line budget.
+    let json = serde_json::to_string_pretty(&response).expect("serialize response");
+    assert!(json.lines().count() > 24);
+}
+
+/// JSON renderer that counts renders of responses holding more than a couple of results.
+#[derive(Default)]
+struct CountingRenderer {
+    full_renders: AtomicUsize,
+}
+
+impl ResponseRenderer for CountingRenderer {
+    fn format(&self) -> &'static str {
+        "json"
+    }
+
+    fn render(&self, response: &SearchResponse) -> Result<String, serde_json::Error> {
+        let results: usize = response
+            .sections
+            .iter()
+            .map(|section| section.results.len())
+            .sum();
+        if results > 2 {
+            self.full_renders.fetch_add(1, Ordering::Relaxed);
+        }
+        JsonRenderer.render(response)
+    }
+}
+
+#[test]
+fn trimming_many_results_renders_the_full_response_a_bounded_number_of_times() {
+    let mut response = oversized_response();
+    response.debug_data = None;
+    response.sections[0].results = (1..=200).map(build_result).collect();
+    response.meta.returned = 200;
+    let renderer = CountingRenderer::default();
+
+    enforce_output_limits_with(&mut response, &renderer, 300, 96 * 1024, 0);
+
+    assert!(renderer.full_renders.load(Ordering::Relaxed) <= 4);
+    let output = JsonRenderer.render(&response).expect("render json");
+    assert!(output.lines().count() <= 300);
+    assert!(output.len() <= 96 * 1024);
+
+    // The estimate does not over-trim: one more result would not have fit.
+    let returned = response.meta.returned;
+    assert!(returned > 2 && returned < 200);
+    response.sections[0]
+        .results
+        .push(build_result(returned + 1));
+    let with_next = JsonRenderer.render(&response).expect("render json");
+    assert!(with_next.lines().count() > 300 || with_next.len() > 96 * 1024);
+}
+
+#[test]
+fn compact_json_leaves_more_of_the_budget_for_results() {
+    let mut response = oversized_response();
+    response.debug_data = None;
+    response.sections[0].results = (1..=20).map(build_result).collect();
+    response.meta.returned = 20;
+    let mut pretty = response.clone();
+
+    enforce_output_limits_with(&mut response, &CompactJsonRenderer, 300, 12 * 1024, 0);
+    enforce_output_limits_with(&mut pretty, &JsonRenderer, 300, 12 * 1024, 0);
+
+    let compact = CompactJsonRenderer.render(&response).expect("render json");
+    assert_eq!(compact.lines().count(), 1);
+    assert!(compact.len() <= 12 * 1024);
+    assert!(response.meta.returned > pretty.meta.returned);
+}
+
+#[test]
+fn annotate_mode_measures_without_mutating() {
+    let mut response = oversized_response();
+    let warnings = response.warnings.len();
+    let query = response.meta.query.clone();
+
+    assert!(annotate_output_limits(
+        &mut response,
+        &JsonRenderer,
+        20,
+        1024
+    ));
+
+    assert!(response.meta.exceeds_limits);
+    assert_eq!(response.warnings.len(), warnings);
+    assert_eq!(response.meta.query, query);
+    assert!(response.debug_data.is_some());
+    let serialized = JsonRenderer.render(&response).expect("render json");
+    let size = response.meta.measured_size.expect("measured size");
+    assert_eq!(size.lines, serialized.lines().count());
+    assert_eq!(size.bytes, serialized.len());
+
+    let mut small = oversized_response();
+    small.sections.clear();
+    small.debug_data = None;
+    assert!(!annotate_output_limits(
+        &mut small,
+        &JsonRenderer,
+        10_000,
+        1_000_000
+    ));
+    assert!(!small.meta.exceeds_limits);
+    assert!(small.meta.measured_size.is_none());
+}
+
+#[test]
+fn diversity_counts_sites_and_top_domain_share() {
+    let mut response = oversized_response();
+    let urls = [
+        "https://www.example.com/a",
+        "https://other.org/b",
+        "https://m.example.com/c",
+        "https://third.net/d",
+    ];
+    response.sections[0].results = urls
+        .iter()
+        .enumerate()
+        .map(|(index, url)| SearchResultItem {
+            url: (*url).to_string(),
+            ..build_result(index)
+        })
+        .collect();
+
+    let diversity = result_diversity(&response).expect("diversity");
+    assert_eq!(diversity.results, 4);
+    assert_eq!(diversity.distinct_domains, 3);
+    assert!((diversity.distinct_ratio - 0.75).abs() < f64::EPSILON);
+    assert_eq!(diversity.top_domain, "example.com");
+    assert!((diversity.top_domain_share - 0.5).abs() < f64::EPSILON);
+
+    response.sections.clear();
+    assert!(result_diversity(&response).is_none());
+}
+
+#[test]
+fn token_budget_shortens_snippets_before_dropping_results() {
+    assert_eq!(estimate_tokens("abcd efghi  x\n"), 4);
+
+    let mut response = oversized_response();
+    response.summary = "Found 6 results.".to_string();
+    response.meta.query = "openai".to_string();
+    response.debug_data = None;
+    response.warnings.clear();
+    response.sections[0].results = (1..=6)
+        .map(|index| SearchResultItem {
+            snippet: "snippet ".repeat(60),
+            ..build_result(index)
+        })
+        .collect();
+    response.meta.returned = 6;
+
+    let mut roomy = response.clone();
+    enforce_token_budget(&mut roomy, &JsonRenderer, 100_000);
+    let initial = roomy.meta.estimated_tokens.expect("estimate");
+    assert!(roomy.warnings.is_empty());
+    assert_eq!(roomy.sections[0].results.len(), 6);
+
+    let mut trimmed = response.clone();
+    enforce_token_budget(&mut trimmed, &JsonRenderer, initial * 2 / 3);
+    assert!(trimmed.meta.estimated_tokens.expect("estimate") <= initial * 2 / 3);
+    assert_eq!(trimmed.sections[0].results.len(), 6);
+    assert!(
+        trimmed.sections[0]
+            .results
+            .iter()
+            .all(|item| { item.extra_snippets.is_empty() && item.snippet.chars().count() <= 240 })
+    );
+    assert_eq!(trimmed.warnings[0].code, WarningCode::OutputTruncated);
+
+    let mut tight = response;
+    enforce_token_budget(&mut tight, &JsonRenderer, initial / 4);
+    assert!(tight.meta.estimated_tokens.expect("estimate") <= initial / 4);
+    assert!(tight.sections[0].results.len() < 6);
+    assert_eq!(tight.meta.returned, tight.sections[0].results.len());
+    assert!(tight.meta.has_more);
+}
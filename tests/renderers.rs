@@ -0,0 +1,196 @@
+use codex_brave_web_search::render::{
+    CsvRenderer, MarkdownRenderer, RendererRegistry, ResponseRenderer, RssRenderer, TextRenderer,
+};
+use codex_brave_web_search::types::{
+    BraveSectionName, SearchMeta, SearchResponse, SearchResultItem, SearchSection, SearchType,
+};
+use std::sync::Arc;
+
+fn item(title: &str, url: &str, snippet: &str) -> SearchResultItem {
+    SearchResultItem {
+        title: title.to_string(),
+        url: url.to_string(),
+        snippet: snippet.to_string(),
+        extra_snippets: Vec::new(),
+        metadata_lines: Vec::new(),
+        source: Some("Example".to_string()),
+        age: None,
+        published: Some("2026-01-30".to_string()),
+        item_type: None,
+        subtype: None,
+        duration: None,
+        creator: None,
+        publisher: None,
+        views: None,
+        embeddable: None,
+        location: None,
+        is_live: None,
+        breaking: None,
+        hostname: None,
+        thumbnail: None,
+        local: None,
+        image: None,
+        origin_section: None,
+        word_count: None,
+        estimated_reading_minutes: None,
+        injection_risk: None,
+    }
+}
+
+fn response() -> SearchResponse {
+    SearchResponse {
+        api_version: "v1".to_string(),
+        summary: "Found 2 results.".to_string(),
+        sections: vec![SearchSection {
+            key: BraveSectionName::Web,
+            label: "Web results".to_string(),
+            provider: "web".to_string(),
+            results: vec![
+                item(
+                    "Rust & <Safety>",
+                    "https://a.example/1",
+                    "Say \"hi\", then\nleave",
+                ),
+                item("[Guide]", "https://b.example/2", ""),
+            ],
+            section_limit_reached: false,
+        }],
+        results: Vec::new(),
+        section_summaries: Vec::new(),
+        topics: Vec::new(),
+        next_cursor: Some("abc".to_string()),
+        meta: SearchMeta {
+            query: "rust safety".to_string(),
+            sent_query: None,
+            search_type: SearchType::Web,
+            requested: 2,
+            returned: 2,
+            offset: 0,
+            has_more: true,
+            provider: "brave".to_string(),
+            duration_ms: 1,
+            warnings_count: 0,
+            server_version: "0.1.0".to_string(),
+            trace_id: "trace".to_string(),
+            endpoint_host: "api.search.brave.com".to_string(),
+            endpoint_override: false,
+            effective_params: None,
+            cache_hit: false,
+            cache_age_secs: None,
+            throttle_tokens: 0,
+            rate_limit: None,
+            dedup: None,
+            rerank: None,
+            suppressed_repeats: None,
+            diversity: None,
+            pages_fetched: None,
+            exceeds_limits: false,
+            measured_size: None,
+            estimated_tokens: None,
+            upstream_ms: None,
+        },
+        warnings: Vec::new(),
+        notices: Vec::new(),
+        explanations: Vec::new(),
+        debug_data: None,
+    }
+}
+
+#[test]
+fn markdown_groups_results_under_section_headings() {
+    let rendered = MarkdownRenderer.render(&response()).expect("render");
+    assert!(rendered.starts_with("# Brave search: rust safety\n"));
+    assert!(rendered.contains("\n## Web results\n"));
+    assert!(rendered.contains("1. [Rust & <Safety>](https://a.example/1)"));
+    assert!(rendered.contains("2. [\\[Guide\\]](https://b.example/2)"));
+    assert!(rendered.contains("   Example · 2026-01-30\n"));
+    assert!(rendered.contains("\n_web · 2 of 2 results · offset 0 · more available_\n"));
+    assert!(rendered.contains("Next page cursor: `abc`"));
+}
+
+#[test]
+fn markdown_link_destinations_encode_parentheses_and_whitespace() {
+    let mut response = response();
+    response.sections[0].results = vec![item(
+        "Rust (language)",
+        "https://en.wikipedia.org/wiki/Rust_(programming_language) x\ty",
+        "",
+    )];
+    let rendered = MarkdownRenderer.render(&response).expect("render");
+    assert!(rendered.contains(
+        "1. [Rust (language)](https://en.wikipedia.org/wiki/Rust_%28programming_language%29%20x%09y)\n"
+    ));
+}
+
+#[test]
+fn text_lists_results_without_markup() {
+    let rendered = TextRenderer.render(&response()).expect("render");
+    assert!(rendered.starts_with(
+        "Brave search: rust safety\nFound 2 results.\nweb · 2 of 2 results · offset 0 · more available\n"
+    ));
+    assert!(rendered.contains(
+        "\nWeb results\n1. Rust & <Safety>\n   https://a.example/1\n   Example · 2026-01-30\n   Say \"hi\", then leave\n"
+    ));
+    assert!(rendered.contains("2. [Guide]\n   https://b.example/2\n"));
+    assert!(rendered.ends_with("\nNext page cursor: abc\n"));
+}
+
+#[test]
+fn csv_quotes_fields_with_separators() {
+    let rendered = CsvRenderer.render(&response()).expect("render");
+    let mut lines = rendered.lines();
+    assert_eq!(
+        lines.next(),
+        Some("section,rank,title,url,source,published,snippet")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("web,1,Rust & <Safety>,https://a.example/1,Example,2026-01-30,\"Say \"\"hi\"\", then")
+    );
+    assert_eq!(lines.next(), Some("leave\""));
+    assert_eq!(
+        lines.next(),
+        Some("web,2,[Guide],https://b.example/2,Example,2026-01-30,")
+    );
+}
+
+#[test]
+fn rss_escapes_markup() {
+    let rendered = RssRenderer.render(&response()).expect("render");
+    assert!(rendered.contains("<title>Brave search: rust safety</title>"));
+    assert!(rendered.contains("<link>https://search.brave.com/search?q=rust+safety</link>"));
+    assert!(rendered.contains("<title>Rust &amp; &lt;Safety&gt;</title>"));
+    assert!(rendered.contains("<description>Say &quot;hi&quot;, then\nleave</description>"));
+    assert_eq!(rendered.matches("<item>").count(), 2);
+}
+
+struct ShoutRenderer;
+
+impl ResponseRenderer for ShoutRenderer {
+    fn format(&self) -> &'static str {
+        "markdown"
+    }
+
+    fn render(&self, response: &SearchResponse) -> Result<String, serde_json::Error> {
+        Ok(response.summary.to_uppercase())
+    }
+}
+
+#[test]
+fn registry_lookup_is_case_insensitive_and_register_replaces() {
+    let mut registry = RendererRegistry::default();
+    assert_eq!(
+        registry.formats().collect::<Vec<_>>(),
+        ["json", "markdown", "text", "csv", "rss"]
+    );
+    assert!(registry.get("CSV").is_some());
+    assert!(registry.get("yaml").is_none());
+
+    registry.register(Arc::new(ShoutRenderer));
+    assert_eq!(registry.formats().count(), 5);
+    let renderer = registry.get("markdown").expect("markdown renderer");
+    assert_eq!(
+        renderer.render(&response()).expect("render"),
+        "FOUND 2 RESULTS."
+    );
+}
@@ -0,0 +1,382 @@
+use codex_brave_web_search::blocking::SearchServiceBlocking;
+use codex_brave_web_search::config::{ApiKeyConfig, HistoryQueryMode, RuntimeConfig};
+use codex_brave_web_search::constants::SEARCH_LOG_TARGET;
+use codex_brave_web_search::endpoint_health::OverrideHealth;
+use codex_brave_web_search::error::AppError;
+use codex_brave_web_search::events::{EventLevel, OperationalEvents};
+use codex_brave_web_search::logging::JsonLines;
+use codex_brave_web_search::parsing::parse_sections;
+use codex_brave_web_search::provider::SearchProvider;
+use codex_brave_web_search::service::SearchService;
+use codex_brave_web_search::types::{
+    BraveWebSearchArgs, FetchSearchParams, FetchSearchResult, HistoryArgs, QuotaInfo, SearchType,
+    StatusArgs, UpstreamTimings,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+struct StaticProvider {
+    key_config: ApiKeyConfig,
+    calls: AtomicUsize,
+    misconfigured: Vec<SearchType>,
+}
+
+impl StaticProvider {
+    fn new() -> Self {
+        Self {
+            key_config: ApiKeyConfig {
+                key: Some("static".to_string()),
+                source: Some("test".to_string()),
+            },
+            calls: AtomicUsize::new(0),
+            misconfigured: Vec::new(),
+        }
+    }
+}
+
+impl SearchProvider for StaticProvider {
+    fn provider_name(&self) -> &'static str {
+        "static"
+    }
+
+    fn key_config(&self) -> ApiKeyConfig {
+        self.key_config.clone()
+    }
+
+    async fn fetch_search<F>(
+        &self,
+        query: &str,
+        search_type: SearchType,
+        params: &FetchSearchParams,
+        _is_cancelled: F,
+    ) -> Result<FetchSearchResult, AppError>
+    where
+        F: Fn() -> bool,
+    {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let payload = serde_json::json!({
+            "web": {
+                "results": [
+                    {"title": format!("{query} one"), "url": "https://example.com/1"},
+                    {"title": format!("{query} two"), "url": "https://example.com/2"}
+                ]
+            }
+        });
+        let parsed = parse_sections(
+            &payload,
+            search_type,
+            &params.result_filter_values,
+            params.count,
+            params.text_decorations,
+        );
+        Ok(FetchSearchResult {
+            sections: parsed.sections,
+            has_more: parsed.has_more,
+            warnings: parsed.warnings,
+            duplicates: parsed.duplicates,
+            query_echo: query.to_string(),
+            request_url: "static://search".to_string(),
+            raw_payload: payload,
+            raw_payload_bytes: 0,
+            rate_limit: None,
+            quota: None,
+            retries: 0,
+            timings: UpstreamTimings::default(),
+            http_version: None,
+            trace_id: None,
+        })
+    }
+
+    async fn probe<F>(&self, search_type: SearchType, _is_cancelled: F) -> Result<(), AppError>
+    where
+        F: Fn() -> bool,
+    {
+        if search_type == SearchType::Images {
+            return Err(AppError::Upstream("images unsupported".to_string()));
+        }
+        Ok(())
+    }
+
+    fn misconfigured_endpoints(&self) -> Vec<SearchType> {
+        self.misconfigured.clone()
+    }
+}
+
+#[tokio::test]
+async fn service_runs_against_injected_provider() {
+    let service = SearchService::with_provider(RuntimeConfig::from_env(), StaticProvider::new());
+
+    let args = || BraveWebSearchArgs {
+        query: "rust".to_string(),
+        max_results: Some(1),
+        correct_query: Some(true),
+        ..Default::default()
+    };
+    let response = service
+        .execute_web_search(args(), "trace-provider", || false)
+        .await
+        .expect("search should succeed");
+
+    assert_eq!(response.meta.provider, "static");
+    assert_eq!(response.sections[0].results.len(), 1);
+    assert_eq!(response.sections[0].results[0].title, "rust one");
+    assert!(response.meta.sent_query.is_none());
+
+    service
+        .execute_web_search(args(), "trace-provider-cached", || false)
+        .await
+        .expect("cached search should succeed");
+    assert_eq!(service.provider().calls.load(Ordering::SeqCst), 1);
+
+    let status = service
+        .status(
+            StatusArgs {
+                probe_connectivity: Some(true),
+                verbose: None,
+                include_limits: None,
+            },
+            || false,
+        )
+        .await;
+    assert_eq!(status.provider, "static");
+    assert_eq!(status.status, "degraded");
+    assert!(status.key_config.has_key);
+}
+
+#[derive(Clone, Default)]
+struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl std::io::Write for LogBuffer {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().expect("log buffer").extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn each_search_logs_one_structured_json_event() {
+    let buffer = LogBuffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(format!("warn,{SEARCH_LOG_TARGET}=info"))
+        .with_writer(move || writer.clone())
+        .event_format(JsonLines)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let service = SearchService::with_provider(RuntimeConfig::from_env(), StaticProvider::new());
+    let args = || BraveWebSearchArgs {
+        query: "rust".to_string(),
+        max_results: Some(1),
+        ..Default::default()
+    };
+    for _ in 0..2 {
+        service
+            .execute_web_search(args(), "trace-log", || false)
+            .await
+            .expect("search should succeed");
+    }
+
+    let output = String::from_utf8(buffer.0.lock().expect("log buffer").clone()).expect("utf-8");
+    let events = output
+        .lines()
+        .map(|line| {
+            serde_json::from_str::<serde_json::Value>(line).expect("one JSON object per line")
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["level"], "INFO");
+    assert_eq!(events[0]["target"], SEARCH_LOG_TARGET);
+    assert_eq!(events[0]["search_type"], "web");
+    assert_eq!(events[0]["status"], "ok");
+    assert_eq!(events[0]["retries"], 0);
+    assert_eq!(events[0]["cache"], "miss");
+    assert_eq!(events[1]["cache"], "hit");
+    assert_eq!(events[0]["query_hash"], events[1]["query_hash"]);
+    assert_eq!(events[0]["query_hash"].as_str().map(str::len), Some(16));
+    assert!(events[0]["duration_ms"].is_u64());
+    assert!(events[0]["timestamp"].is_string());
+    assert!(!output.contains("rust"));
+}
+
+#[tokio::test]
+async fn history_records_recent_searches_and_survives_restart() {
+    let history_file =
+        std::env::temp_dir().join(format!("codex-brave-history-{}.jsonl", std::process::id()));
+    std::fs::remove_file(&history_file).ok();
+    let mut config = RuntimeConfig::from_env();
+    config.history.size = 2;
+    config.history.file = Some(history_file.clone());
+
+    let service = SearchService::with_provider(config.clone(), StaticProvider::new());
+    for query in ["first", "second", " "] {
+        let args = BraveWebSearchArgs {
+            query: query.to_string(),
+            ..Default::default()
+        };
+        service
+            .execute_web_search(args, &format!("trace-{}", query.trim()), || false)
+            .await
+            .ok();
+    }
+
+    let history = service.history(&HistoryArgs::default(), "trace-history");
+    assert_eq!((history.recorded, history.capacity), (2, 2));
+    assert_eq!(history.entries[0].trace_id, "trace-");
+    assert_eq!(history.entries[0].outcome, "INVALID_ARGUMENT");
+    assert_eq!(history.entries[0].cache, None);
+    assert_eq!(history.entries[1].trace_id, "trace-second");
+    assert_eq!(history.entries[1].outcome, "ok");
+    assert_eq!(history.entries[1].search_type, Some(SearchType::Web));
+    assert_eq!(history.entries[1].cache.as_deref(), Some("miss"));
+    assert!(history.entries[1].query_hash.is_some());
+    assert_eq!(history.entries[1].query, None);
+
+    let errors = service.history(
+        &HistoryArgs {
+            limit: None,
+            errors_only: Some(true),
+        },
+        "trace-history",
+    );
+    assert_eq!(errors.entries.len(), 1);
+
+    config.history.queries = HistoryQueryMode::Redacted;
+    let restarted = SearchService::with_provider(config, StaticProvider::new());
+    restarted
+        .execute_web_search(
+            BraveWebSearchArgs {
+                query: "mail ops@example.com".to_string(),
+                ..Default::default()
+            },
+            "trace-redacted",
+            || false,
+        )
+        .await
+        .expect("search should succeed");
+    let history = restarted.history(&HistoryArgs::default(), "trace-history");
+    let trace_ids = history
+        .entries
+        .iter()
+        .map(|entry| entry.trace_id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(trace_ids, ["trace-redacted", "trace-"]);
+    assert_eq!(history.entries[0].query.as_deref(), Some("mail [email]"));
+    std::fs::remove_file(&history_file).ok();
+}
+
+#[tokio::test]
+async fn status_names_the_env_var_of_a_misconfigured_endpoint() {
+    let mut config = RuntimeConfig::from_env();
+    config.endpoints.news = "https://proxy.internal/news".to_string();
+    let provider = StaticProvider {
+        misconfigured: vec![SearchType::News],
+        ..StaticProvider::new()
+    };
+    let service = SearchService::with_provider(config, provider);
+
+    let status = service
+        .status(
+            StatusArgs {
+                probe_connectivity: None,
+                verbose: None,
+                include_limits: None,
+            },
+            || false,
+        )
+        .await;
+    assert_eq!(status.status, "degraded");
+    let notice = status
+        .notices
+        .iter()
+        .find(|notice| notice.code == "MISCONFIGURED_ENDPOINT")
+        .expect("misconfigured endpoint notice");
+    assert!(notice.message.contains("CODEX_BRAVE_ENDPOINT_NEWS"));
+    assert!(notice.message.contains("proxy.internal"));
+}
+
+#[test]
+fn override_health_flags_after_repeated_failures_until_a_success() {
+    let health = OverrideHealth::default();
+
+    assert_eq!(health.record_failure(SearchType::Web), None);
+    assert_eq!(health.record_failure(SearchType::Web), None);
+    assert!(health.misconfigured().is_empty());
+    assert_eq!(health.record_failure(SearchType::Web), Some(3));
+    // Already flagged: further failures are not reported again.
+    assert_eq!(health.record_failure(SearchType::Web), None);
+    assert_eq!(health.misconfigured(), [SearchType::Web]);
+    assert!(health.is_misconfigured(SearchType::Web));
+    assert!(!health.record_success(SearchType::News));
+    assert!(health.record_success(SearchType::Web));
+    assert!(health.misconfigured().is_empty());
+}
+
+#[test]
+fn blocking_facade_searches_without_caller_runtime() {
+    let service =
+        SearchServiceBlocking::with_provider(RuntimeConfig::from_env(), StaticProvider::new())
+            .expect("blocking service should start");
+
+    let args = || BraveWebSearchArgs {
+        query: "tokio".to_string(),
+        max_results: Some(2),
+        ..Default::default()
+    };
+    let response = service.search(args()).expect("search should succeed");
+    assert_eq!(response.sections[0].results[0].title, "tokio one");
+
+    service
+        .search(args())
+        .expect("cached search should succeed");
+    assert_eq!(service.service().provider().calls.load(Ordering::SeqCst), 1);
+
+    let status = service.status(StatusArgs {
+        probe_connectivity: None,
+        verbose: None,
+        include_limits: None,
+    });
+    assert_eq!(status.provider, "static");
+}
+
+#[test]
+fn operational_events_report_each_condition_once() {
+    let events = OperationalEvents::default();
+    let rate_limited = || AppError::BraveApi {
+        status: 429,
+        message: "rate limited".to_string(),
+        brave_code: None,
+        params: Vec::new(),
+        retry_after_ms: None,
+    };
+
+    for _ in 0..4 {
+        events.observe_error(&rate_limited());
+    }
+    events.observe_error(&AppError::ApiKeyRevoked);
+    events.observe_error(&AppError::ApiKeyRevoked);
+    let low = QuotaInfo {
+        limit: 1_000,
+        remaining: 100,
+    };
+    events.observe_success(Some(low));
+    events.observe_success(Some(low));
+
+    let drained = events.drain();
+    let codes = drained.iter().map(|event| event.code).collect::<Vec<_>>();
+    assert_eq!(codes, ["RATE_LIMITED", "API_KEY_REVOKED", "QUOTA_LOW"]);
+    assert_eq!(drained[1].level, EventLevel::Error);
+    assert!(drained[2].message.contains("100 of 1000"));
+    assert!(events.drain().is_empty());
+
+    // A success resets the streak, so three more 429s report again.
+    for _ in 0..3 {
+        events.observe_error(&rate_limited());
+    }
+    assert_eq!(events.drain().len(), 1);
+}
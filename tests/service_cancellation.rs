@@ -7,25 +7,9 @@ fn minimal_args() -> BraveWebSearchArgs {
     BraveWebSearchArgs {
         query: "openai".to_string(),
         search_type: Some("web".to_string()),
-        result_filter: None,
         max_results: Some(1),
         offset: Some(0),
-        country: None,
-        search_language: None,
-        ui_language: None,
-        safe_search: None,
-        units: None,
-        freshness: None,
-        spellcheck: None,
-        extra_snippets: None,
-        text_decorations: None,
-        max_lines: None,
-        max_bytes: None,
-        debug: None,
-        include_raw_payload: None,
-        disable_cache: None,
-        disable_throttle: None,
-        include_request_url: None,
+        ..Default::default()
     }
 }
 
@@ -44,3 +28,60 @@ async fn cancellation_is_respected_before_network_call() {
 
     assert!(matches!(err, AppError::Cancelled));
 }
+
+#[tokio::test]
+async fn cancellation_aborts_stalled_body_read_immediately() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Sends headers and one body chunk, then stalls without ever finishing the body.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind drip server");
+    let address = listener.local_addr().expect("drip server address");
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.expect("accept");
+        let mut request = [0u8; 4096];
+        let _ = socket.read(&mut request).await;
+        socket
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\n5\r\n{\"web\r\n",
+            )
+            .await
+            .expect("write first chunk");
+        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
+    });
+
+    let mut config = RuntimeConfig::from_env();
+    config.endpoints.web = format!("http://{address}/web");
+    config.retry_count = 0;
+    config.per_attempt_timeout_ms = 10_000;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let cancel_flag = Arc::clone(&cancelled);
+    let cancelled_at = tokio::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        cancel_flag.store(true, Ordering::SeqCst);
+        std::time::Instant::now()
+    });
+
+    let err = service
+        .execute_web_search(minimal_args(), "trace-drip", || {
+            cancelled.load(Ordering::SeqCst)
+        })
+        .await
+        .expect_err("stalled body read should be cancelled");
+    let returned_at = std::time::Instant::now();
+    let cancelled_at = cancelled_at.await.expect("cancel task");
+
+    assert!(matches!(err, AppError::Cancelled));
+    assert!(
+        returned_at.duration_since(cancelled_at) < std::time::Duration::from_millis(100),
+        "cancel latency was {:?}",
+        returned_at.duration_since(cancelled_at)
+    );
+}
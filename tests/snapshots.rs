@@ -1,7 +1,5 @@
 use codex_brave_web_search::config::RuntimeConfig;
-use codex_brave_web_search::constants::{
-    TOOL_BRAVE_WEB_SEARCH, TOOL_BRAVE_WEB_SEARCH_HELP, TOOL_BRAVE_WEB_SEARCH_STATUS,
-};
+use codex_brave_web_search::constants::{TOOL_BRAVE_WEB_SEARCH, TOOL_BRAVE_WEB_SEARCH_HELP};
 use codex_brave_web_search::mcp_server::BraveSearchMcpServer;
 use codex_brave_web_search::service::SearchService;
 use insta::assert_json_snapshot;
@@ -69,6 +67,13 @@ async fn snapshot_help_all() {
     assert_json_snapshot!("help_all", json);
 }
 
+// The snapshot lists the default build features.
+#[cfg(all(
+    feature = "debug-tools",
+    feature = "archive",
+    feature = "metrics",
+    feature = "language-detection"
+))]
 #[tokio::test]
 async fn snapshot_status_no_probe() {
     let server = make_server();
@@ -84,7 +89,7 @@ async fn snapshot_status_no_probe() {
 
     let output = server
         .call_tool(
-            TOOL_BRAVE_WEB_SEARCH_STATUS,
+            codex_brave_web_search::constants::TOOL_BRAVE_WEB_SEARCH_STATUS,
             serde_json::json!({"include_limits": true}),
             &ctx,
         )
@@ -1,4 +1,5 @@
-use codex_brave_web_search::throttle::RequestThrottle;
+use codex_brave_web_search::throttle::{RequestThrottle, TokenLedger};
+use codex_brave_web_search::types::RateLimitInfo;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
@@ -42,3 +43,38 @@ async fn acquire_cancellable_succeeds_without_cancellation() {
 
     assert!(acquired.is_ok());
 }
+
+#[tokio::test]
+async fn acquire_charged_records_only_granted_tokens() {
+    let throttle = RequestThrottle::new(1, 2);
+    let ledger = TokenLedger::default();
+
+    for _ in 0..2 {
+        throttle
+            .acquire_charged(&ledger, &|| false)
+            .await
+            .expect("burst token");
+    }
+    assert_eq!(ledger.consumed(), 2);
+
+    // The bucket is empty now, so a cancelled wait must not be charged.
+    assert!(throttle.acquire_charged(&ledger, &|| true).await.is_err());
+    assert_eq!(ledger.consumed(), 2);
+}
+
+#[tokio::test]
+async fn exhausted_rate_limit_window_drains_the_bucket() {
+    let throttle = RequestThrottle::new(50, 50);
+    throttle
+        .observe_rate_limit(RateLimitInfo {
+            limit: 5,
+            remaining: 0,
+            reset_secs: 1,
+        })
+        .await;
+    assert!((throttle.tokens_per_second().await - 5.0).abs() < f64::EPSILON);
+
+    let started = std::time::Instant::now();
+    throttle.acquire().await;
+    assert!(started.elapsed() >= Duration::from_millis(150));
+}
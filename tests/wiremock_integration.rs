@@ -1,8 +1,31 @@
-use codex_brave_web_search::config::RuntimeConfig;
+use clap::Parser;
+use codex_brave_web_search::batch::BatchScheduler;
+use codex_brave_web_search::blocklist::DomainBlocklist;
+use codex_brave_web_search::cli::{self, Cli};
+use codex_brave_web_search::client::{BraveClient, trace_context_for};
+#[cfg(feature = "language-detection")]
+use codex_brave_web_search::config::QueryLanguageDetection;
+use codex_brave_web_search::config::{
+    HttpPoolSettings, MirrorSettings, RepeatQuerySettings, RuntimeConfig, ThumbnailMode,
+    TransportMode,
+};
+#[cfg(feature = "archive")]
+use codex_brave_web_search::config::{RecordMode, RecordSettings};
+use codex_brave_web_search::formatting::build_response_payload;
+use codex_brave_web_search::normalization::decode_cursor;
+use codex_brave_web_search::notify::sign_payload;
+use codex_brave_web_search::render::ResponseRenderer;
 use codex_brave_web_search::service::SearchService;
-use codex_brave_web_search::types::BraveWebSearchArgs;
+use codex_brave_web_search::types::{
+    BatchJobStatus, BatchQueryStatus, BatchScheduleArgs, BraveWebSearchArgs, CompareParamSet,
+    CompareParamsArgs, KeyState, RateLimitInfo, RerankMode, SearchResponse, SearchType, StatusArgs,
+    SuggestArgs, SummarizeArgs, WarningCode,
+};
+#[cfg(feature = "archive")]
+use codex_brave_web_search::types::{FieldDiff, ReplayArgs};
 use serial_test::serial;
-use wiremock::matchers::{method, path};
+use std::sync::Arc;
+use wiremock::matchers::{header, method, path, query_param, query_param_is_missing};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 fn mock_payload(title: &str, url: &str) -> serde_json::Value {
@@ -24,25 +47,9 @@ fn base_args() -> BraveWebSearchArgs {
     BraveWebSearchArgs {
         query: "openai".to_string(),
         search_type: Some("web".to_string()),
-        result_filter: None,
         max_results: Some(5),
         offset: Some(0),
-        country: None,
-        search_language: None,
-        ui_language: None,
-        safe_search: None,
-        units: None,
-        freshness: None,
-        spellcheck: None,
-        extra_snippets: None,
-        text_decorations: None,
-        max_lines: None,
-        max_bytes: None,
-        debug: None,
-        include_raw_payload: None,
-        disable_cache: None,
-        disable_throttle: None,
-        include_request_url: None,
+        ..Default::default()
     }
 }
 
@@ -52,6 +59,11 @@ fn configure_for_mock_server(server: &MockServer) -> RuntimeConfig {
     config.endpoints.news = format!("{}/news", server.uri());
     config.endpoints.images = format!("{}/images", server.uri());
     config.endpoints.videos = format!("{}/videos", server.uri());
+    config.endpoints.summarizer = format!("{}/summarizer", server.uri());
+    config.endpoints.suggest = format!("{}/suggest", server.uri());
+    config.endpoints.spellcheck = format!("{}/spellcheck", server.uri());
+    config.endpoints.local_pois = format!("{}/local/pois", server.uri());
+    config.endpoints.local_descriptions = format!("{}/local/descriptions", server.uri());
     config.retry_count = 2;
     config.retry_base_delay_ms = 10;
     config.retry_max_delay_ms = 50;
@@ -96,6 +108,83 @@ async fn retries_on_transient_error_then_succeeds() {
     assert_eq!(response.sections.len(), 1);
 }
 
+#[tokio::test]
+#[serial]
+async fn exhausted_rate_limit_retries_report_retry_after() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("Retry-After", "3")
+                .set_body_json(serde_json::json!({
+                    "type": "ErrorResponse",
+                    "error": {"code": "RATE_LIMITED", "detail": "Request rate limit exceeded"}
+                })),
+        )
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let error = service
+        .execute_web_search(base_args(), "trace-rate-limited", || false)
+        .await
+        .expect_err("rate limited search should fail");
+
+    assert_eq!(error.code(), "RATE_LIMITED");
+    let envelope = error.to_envelope("test", "trace-rate-limited");
+    let details = envelope.error.details.expect("details");
+    assert_eq!(details["status"], 429);
+    assert_eq!(details["retry_after_ms"], 3_000);
+    assert_eq!(details["brave_code"], "RATE_LIMITED");
+}
+
+#[tokio::test]
+async fn retry_budget_is_shared_across_requests() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("unavailable"))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let mut config = configure_for_mock_server(&server);
+    config.retry_budget_per_min = 1;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    for (query, trace_id) in [
+        ("first outage", "trace-budget-1"),
+        ("second outage", "trace-budget-2"),
+    ] {
+        let error = service
+            .execute_web_search(
+                BraveWebSearchArgs {
+                    query: query.to_string(),
+                    ..base_args()
+                },
+                trace_id,
+                || false,
+            )
+            .await
+            .expect_err("outage search should fail");
+        assert_eq!(error.code(), "UPSTREAM_ERROR");
+        assert!(
+            error.message().contains("RETRY_BUDGET_EXHAUSTED"),
+            "{}",
+            error.message()
+        );
+    }
+}
+
 #[tokio::test]
 #[serial]
 async fn errors_when_response_body_exceeds_size_limit() {
@@ -190,3 +279,3712 @@ async fn times_out_slow_endpoint_after_retries() {
 
     assert!(err.to_string().contains("timeout"));
 }
+
+#[tokio::test]
+#[serial]
+async fn aliased_locale_params_are_corrected_before_request() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("country", "BR"))
+        .and(query_param("search_lang", "pt-br"))
+        .and(query_param("ui_lang", "en-GB"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let mut args = base_args();
+    args.country = Some("br".to_string());
+    args.search_language = Some("pt".to_string());
+    args.ui_language = Some("en-UK".to_string());
+
+    let response = service
+        .execute_web_search(args, "trace-alias", || false)
+        .await
+        .expect("search should succeed with aliased params");
+
+    let aliased = response
+        .warnings
+        .iter()
+        .filter(|warning| warning.code == "PARAM_ALIASED")
+        .count();
+    assert_eq!(aliased, 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn country_is_inferred_from_ui_language_region() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("country", "DE"))
+        .and(query_param("ui_lang", "de-DE"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let mut args = base_args();
+    args.ui_language = Some("de-DE".to_string());
+
+    let response = service
+        .execute_web_search(args, "trace-infer", || false)
+        .await
+        .expect("search should succeed with inferred country");
+
+    let effective = response
+        .meta
+        .effective_params
+        .expect("effective params present");
+    assert_eq!(effective.country.as_deref(), Some("DE"));
+    assert_eq!(effective.inferred.len(), 2);
+    assert_eq!(effective.inferred[0].field, "country");
+    assert_eq!(effective.inferred[0].source, "ui_language");
+    assert_eq!(effective.inferred[1].field, "extra_snippets");
+}
+
+#[tokio::test]
+#[serial]
+async fn country_inference_can_be_disabled() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param_is_missing("country"))
+        .and(query_param("ui_lang", "de-DE"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = configure_for_mock_server(&server);
+    config.infer_country_from_ui_language = false;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let mut args = base_args();
+    args.ui_language = Some("de-DE".to_string());
+
+    let response = service
+        .execute_web_search(args, "trace-no-infer", || false)
+        .await
+        .expect("search should succeed without inferred country");
+
+    let effective = response
+        .meta
+        .effective_params
+        .expect("effective params present");
+    assert!(effective.country.is_none());
+    assert!(
+        effective
+            .inferred
+            .iter()
+            .all(|param| param.field != "country")
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn extra_snippets_default_follows_output_budget() {
+    let server = MockServer::start().await;
+
+    for (count, enabled) in [("2", "true"), ("10", "false"), ("10", "true")] {
+        Mock::given(method("GET"))
+            .and(path("/web"))
+            .and(query_param("count", count))
+            .and(query_param("extra_snippets", enabled))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(mock_payload("A", "https://example.com/a")),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    for (max_results, enabled) in [(2, "true"), (10, "false")] {
+        let mut args = base_args();
+        args.max_results = Some(max_results);
+        let response = service
+            .execute_web_search(args, "trace-budget", || false)
+            .await
+            .expect("search should succeed");
+        let effective = response
+            .meta
+            .effective_params
+            .expect("effective params present");
+        let decision = effective
+            .inferred
+            .iter()
+            .find(|param| param.field == "extra_snippets")
+            .expect("extra_snippets decision recorded");
+        assert_eq!(decision.value, enabled);
+        assert_eq!(decision.source, "output_budget");
+    }
+
+    let mut explicit = base_args();
+    explicit.max_results = Some(10);
+    explicit.extra_snippets = Some(true);
+    let response = service
+        .execute_web_search(explicit, "trace-explicit", || false)
+        .await
+        .expect("search should succeed");
+    let effective = response
+        .meta
+        .effective_params
+        .expect("effective params present");
+    assert!(effective.extra_snippets);
+    assert!(
+        effective
+            .inferred
+            .iter()
+            .all(|param| param.field != "extra_snippets")
+    );
+}
+
+#[cfg(feature = "language-detection")]
+#[tokio::test]
+#[serial]
+async fn detected_query_language_sets_search_language_and_country() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("search_lang", "fr"))
+        .and(query_param("country", "FR"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = configure_for_mock_server(&server);
+    config.detect_query_language = QueryLanguageDetection::LanguageAndCountry;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let mut args = base_args();
+    args.query = "Quelle est la meilleure boulangerie près de la gare de Lyon ?".to_string();
+
+    let response = service
+        .execute_web_search(args, "trace-detect", || false)
+        .await
+        .expect("search should succeed with detected language");
+
+    assert!(response.warnings.iter().any(|warning| {
+        warning.code == "LANGUAGE_AUTODETECTED" && warning.message.contains("country 'FR'")
+    }));
+    let effective = response
+        .meta
+        .effective_params
+        .expect("effective params present");
+    assert_eq!(effective.search_language.as_deref(), Some("fr"));
+    assert!(
+        effective
+            .inferred
+            .iter()
+            .filter(|param| param.field != "extra_snippets")
+            .all(|param| param.source == "query_language_detection")
+    );
+    assert_eq!(effective.inferred.len(), 3);
+}
+
+#[test]
+#[serial]
+fn capabilities_report_language_detection_build_feature() {
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(RuntimeConfig::from_env()).expect("service init")
+    });
+    assert_eq!(
+        service
+            .capabilities()
+            .build_features
+            .contains(&"language-detection"),
+        cfg!(feature = "language-detection")
+    );
+}
+
+#[test]
+#[serial]
+fn capabilities_report_core_and_server_build_features() {
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(RuntimeConfig::from_env()).expect("service init")
+    });
+    let build_features = service.capabilities().build_features;
+    assert_eq!(build_features[..2], ["core", "server"]);
+}
+
+#[tokio::test]
+#[serial]
+async fn capability_manifest_reflects_configuration_and_is_reported_in_status() {
+    let server = MockServer::start().await;
+    let mut config = configure_for_mock_server(&server);
+    config.transport = TransportMode::Http;
+    config.listen_addr = "127.0.0.1:8931".parse().expect("listen address");
+    config.cache_ttl_secs = 42;
+    config.auto_paginate_max_pages = 0;
+    config.repeat_query.threshold = 0;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let capabilities = service.capabilities();
+    assert_eq!(capabilities.transport, "http");
+    assert_eq!(capabilities.listen_addr.as_deref(), Some("127.0.0.1:8931"));
+    assert_eq!(capabilities.cache.ttl_secs, 42);
+    assert!(!capabilities.features.contains(&"auto_paginate"));
+    assert!(!capabilities.features.contains(&"repeat_query_detection"));
+    assert_eq!(capabilities.endpoints.len(), 4);
+    assert!(
+        capabilities
+            .endpoints
+            .iter()
+            .all(|endpoint| endpoint.overridden && endpoint.host == server.address().to_string())
+    );
+
+    let status = service
+        .status(
+            StatusArgs {
+                probe_connectivity: None,
+                verbose: None,
+                include_limits: None,
+            },
+            || false,
+        )
+        .await;
+    let status = serde_json::to_value(status).expect("serialize status");
+    assert_eq!(status["capabilities"]["transport"], "http");
+    assert_eq!(status["capabilities"]["listen_addr"], "127.0.0.1:8931");
+}
+
+#[tokio::test]
+#[serial]
+async fn safe_search_floor_overrides_lower_requested_value() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("safesearch", "moderate"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut config = configure_for_mock_server(&server);
+    config.safe_search_floors.web = Some("moderate".to_string());
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let mut args = base_args();
+    args.safe_search = Some("off".to_string());
+
+    let response = service
+        .execute_web_search(args, "trace-floor", || false)
+        .await
+        .expect("search should succeed with safe_search floor");
+
+    assert!(
+        response
+            .warnings
+            .iter()
+            .any(|warning| warning.code == "POLICY_OVERRIDDEN")
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn inline_thumbnail_mode_embeds_small_images_and_drops_large_ones() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "query": {"original": "openai", "more_results_available": false},
+            "web": {
+                "results": [
+                    {
+                        "title": "Small",
+                        "url": "https://example.com/small",
+                        "description": "desc",
+                        "thumbnail": {"src": format!("{}/thumbs/small.png", server.uri())}
+                    },
+                    {
+                        "title": "Large",
+                        "url": "https://example.com/large",
+                        "description": "desc",
+                        "thumbnail": {"src": format!("{}/thumbs/large.png", server.uri())}
+                    }
+                ]
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/thumbs/small.png"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(vec![1_u8, 2, 3], "image/png"))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/thumbs/large.png"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(vec![0_u8; 4_096], "image/png"))
+        .mount(&server)
+        .await;
+
+    let mut config = configure_for_mock_server(&server);
+    config.endpoints.thumbnails = server.uri();
+    config.thumbnail_mode = ThumbnailMode::Inline;
+    config.thumbnail_max_bytes = 1_024;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let response = service
+        .execute_web_search(base_args(), "trace-thumbs", || false)
+        .await
+        .expect("search should succeed with inline thumbnails");
+
+    let results = &response.sections[0].results;
+    assert_eq!(
+        results[0].thumbnail.as_deref(),
+        Some("data:image/png;base64,AQID")
+    );
+    assert!(results[1].thumbnail.is_none());
+    assert!(
+        response
+            .warnings
+            .iter()
+            .any(|warning| warning.code == "THUMBNAIL_OMITTED")
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn inline_thumbnails_share_one_deadline_and_skip_other_hosts() {
+    let server = MockServer::start().await;
+    let results = (0..4)
+        .map(|index| {
+            serde_json::json!({
+                "title": format!("Slow {index}"),
+                "url": format!("https://example.com/{index}"),
+                "description": "desc",
+                "thumbnail": {"src": format!("{}/thumbs/slow{index}.png", server.uri())}
+            })
+        })
+        .chain(std::iter::once(serde_json::json!({
+            "title": "Elsewhere",
+            "url": "https://example.com/elsewhere",
+            "description": "desc",
+            "thumbnail": {"src": "http://169.254.169.254/latest/meta-data"}
+        })))
+        .collect::<Vec<_>>();
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "query": {"original": "openai", "more_results_available": false},
+            "web": {"results": results}
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(wiremock::matchers::path_regex("^/thumbs/"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(vec![1_u8, 2, 3], "image/png")
+                .set_delay(std::time::Duration::from_secs(1)),
+        )
+        .mount(&server)
+        .await;
+
+    let mut config = configure_for_mock_server(&server);
+    config.endpoints.thumbnails = server.uri();
+    config.thumbnail_mode = ThumbnailMode::Inline;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let started = std::time::Instant::now();
+    let response = service
+        .execute_web_search(base_args(), "trace-thumbs-deadline", || false)
+        .await
+        .expect("search should succeed when thumbnails time out");
+    let elapsed = started.elapsed();
+
+    // Four 150 ms timeouts back to back would take 600 ms; together they take one.
+    assert!(
+        elapsed < std::time::Duration::from_millis(500),
+        "took {elapsed:?}"
+    );
+    assert!(
+        response.sections[0]
+            .results
+            .iter()
+            .all(|result| result.thumbnail.is_none())
+    );
+    let warning = response
+        .warnings
+        .iter()
+        .find(|warning| warning.code == "THUMBNAIL_OMITTED")
+        .expect("omitted warning");
+    assert!(warning.message.starts_with("5 thumbnail(s) omitted"));
+}
+
+#[tokio::test]
+#[serial]
+async fn override_answering_without_brave_payloads_is_flagged_misconfigured() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"message": "proxy login required"})),
+        )
+        .expect(3)
+        .mount(&server)
+        .await;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let mut flagged = Vec::new();
+    for attempt in 1..=3 {
+        let response = service
+            .execute_web_search(
+                BraveWebSearchArgs {
+                    query: format!("proxy check {attempt}"),
+                    ..base_args()
+                },
+                "trace-misconfigured",
+                || false,
+            )
+            .await
+            .expect("search should succeed");
+        flagged.push(
+            response
+                .warnings
+                .iter()
+                .any(|warning| warning.code == "MISCONFIGURED_ENDPOINT"),
+        );
+    }
+    assert_eq!(flagged, [false, false, true]);
+
+    let status = service
+        .status(
+            StatusArgs {
+                probe_connectivity: None,
+                verbose: None,
+                include_limits: None,
+            },
+            || false,
+        )
+        .await;
+    assert_eq!(status.status, "degraded");
+    let notice = status
+        .notices
+        .iter()
+        .find(|notice| notice.code == "MISCONFIGURED_ENDPOINT")
+        .expect("misconfigured endpoint notice");
+    assert!(notice.message.contains("CODEX_BRAVE_ENDPOINT_WEB"));
+}
+
+#[tokio::test]
+#[serial]
+async fn meta_reports_overridden_endpoint_host() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .mount(&server)
+        .await;
+
+    let config = configure_for_mock_server(&server);
+    assert!(
+        !RuntimeConfig::from_env()
+            .endpoints
+            .is_override(SearchType::Web)
+    );
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let response = service
+        .execute_web_search(base_args(), "trace-endpoint", || false)
+        .await
+        .expect("search should succeed");
+
+    let expected_host = server.uri().trim_start_matches("http://").to_string();
+    assert_eq!(response.meta.endpoint_host, expected_host);
+    assert!(response.meta.endpoint_override);
+}
+
+#[tokio::test]
+#[serial]
+async fn legacy_key_env_adds_suppressible_notice() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .mount(&server)
+        .await;
+
+    let legacy_key_env = [
+        ("BRAVE_SEARCH_API_KEY", None),
+        ("BRAVE_API_KEY", Some("test-key")),
+    ];
+    let service = temp_env::with_vars(legacy_key_env, || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let response = service
+        .execute_web_search(base_args(), "trace-notice", || false)
+        .await
+        .expect("search should succeed");
+    assert!(
+        response
+            .notices
+            .iter()
+            .any(|notice| notice.code == "LEGACY_API_KEY_ENV")
+    );
+    assert!(response.warnings.is_empty());
+
+    let mut config = configure_for_mock_server(&server);
+    config.suppressed_notices = vec!["LEGACY_API_KEY_ENV".to_string()];
+    let service = temp_env::with_vars(legacy_key_env, || {
+        SearchService::new(config).expect("service init")
+    });
+    let response = service
+        .execute_web_search(base_args(), "trace-notice-suppressed", || false)
+        .await
+        .expect("search should succeed");
+    assert!(response.notices.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn api_version_v2_flattens_results_and_adds_pagination() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "query": {"original": "openai", "more_results_available": true},
+            "web": {"results": [
+                {"title": "A", "url": "https://example.com/a", "description": "desc"},
+                {"title": "B", "url": "https://example.com/b", "description": "desc"}
+            ]},
+            "discussions": {"results": [
+                {"title": "C", "url": "https://example.com/c", "description": "desc"}
+            ]}
+        })))
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let mut args = base_args();
+    args.api_version = Some("v2".to_string());
+    args.result_filter = Some(vec!["web".to_string(), "discussions".to_string()]);
+    let response = service
+        .execute_web_search(args, "trace-v2", || false)
+        .await
+        .expect("search should succeed");
+    let payload = build_response_payload(&response).expect("build v2 payload");
+
+    assert_eq!(payload["api_version"], "v2");
+    assert!(payload.get("sections").is_none());
+    let results = payload["results"].as_array().expect("flat results");
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[2]["section"], "discussions");
+    assert_eq!(results[2]["rank"], 1);
+    assert_eq!(payload["pagination"]["next_offset"], 1);
+    assert!(payload["timings"]["upstream_ms"].is_u64());
+
+    let mut args = base_args();
+    args.api_version = Some("v9".to_string());
+    let response = service
+        .execute_web_search(args, "trace-v9", || false)
+        .await
+        .expect("search should succeed");
+    assert_eq!(response.api_version, "v1");
+    assert!(
+        response
+            .warnings
+            .iter()
+            .any(|warning| warning.code == "INVALID_API_VERSION")
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn explain_mode_reports_provider_rank_and_dedup_decisions() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "query": {"original": "openai", "more_results_available": false},
+            "web": {"results": [
+                {"title": "A", "url": "https://example.com/a", "description": "desc"}
+            ]},
+            "discussions": {"results": [
+                {"title": "A again", "url": "https://example.com/a/", "description": "desc"},
+                {"title": "C", "url": "https://example.com/c", "description": "desc"}
+            ]}
+        })))
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let mut args = base_args();
+    args.explain = Some(true);
+    args.result_filter = Some(vec!["web".to_string(), "discussions".to_string()]);
+    let response = service
+        .execute_web_search(args, "trace-explain", || false)
+        .await
+        .expect("search should succeed");
+
+    assert_eq!(response.explanations.len(), 2);
+    let kept = &response.explanations[0];
+    assert_eq!(kept.provider_rank, 1);
+    assert!(
+        kept.reasons
+            .iter()
+            .any(|reason| reason.starts_with("Kept over duplicate discussions #1"))
+    );
+    let moved = &response.explanations[1];
+    assert_eq!((moved.position, moved.provider_rank), (1, 2));
+    assert!(
+        moved
+            .reasons
+            .iter()
+            .any(|reason| reason.starts_with("Moved up"))
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn summarize_polls_summarizer_until_complete() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("summary", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "query": {"original": "what is rust"},
+            "summarizer": {"type": "summarizer", "key": "sum-key-1"}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/summarizer"))
+        .and(query_param("key", "sum-key-1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"type": "summarizer", "status": "pending"})),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/summarizer"))
+        .and(query_param("key", "sum-key-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "type": "summarizer",
+            "status": "complete",
+            "title": "Rust",
+            "summary": [
+                {"type": "token", "data": "Rust is a <strong>systems</strong> language. "},
+                {"type": "inline_reference", "data": {"url": "https://www.rust-lang.org/"}},
+                {"type": "token", "data": " It is memory safe."}
+            ],
+            "enrichments": {
+                "context": [
+                    {"title": "Rust", "url": "https://www.rust-lang.org/"},
+                    {"title": "Rust again", "url": "https://www.rust-lang.org/"},
+                    {"title": "Wikipedia", "url": "https://en.wikipedia.org/wiki/Rust"}
+                ]
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // One token in the bucket: the first poll has to wait for the search's token to refill.
+    let mut config = configure_for_mock_server(&server);
+    config.throttle_rate_per_sec = 20;
+    config.throttle_burst = 1;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let response = service
+        .execute_summarize(
+            SummarizeArgs {
+                query: "what is rust".to_string(),
+                country: None,
+                search_language: None,
+                ui_language: None,
+                safe_search: None,
+            },
+            "trace-summarize",
+            || false,
+        )
+        .await
+        .expect("summarize should succeed");
+
+    assert_eq!(response.title.as_deref(), Some("Rust"));
+    assert_eq!(
+        response.summary,
+        "Rust is a systems language. [1] It is memory safe."
+    );
+    assert_eq!(response.sources.len(), 2);
+    assert_eq!(
+        response.sources[1].url,
+        "https://en.wikipedia.org/wiki/Rust"
+    );
+    assert_eq!(response.meta.query, "what is rust");
+
+    #[cfg(feature = "metrics")]
+    {
+        let metrics = service.metrics("trace-summarize-metrics").await;
+        assert_eq!(metrics.upstream_latency.calls, 3);
+        assert_eq!(metrics.throttle.waits, 1);
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn summarize_without_summarizer_key_is_upstream_error() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let error = service
+        .execute_summarize(
+            SummarizeArgs {
+                query: "openai".to_string(),
+                country: None,
+                search_language: None,
+                ui_language: None,
+                safe_search: None,
+            },
+            "trace-summarize-missing",
+            || false,
+        )
+        .await
+        .expect_err("missing summarizer key should fail");
+
+    assert_eq!(error.code(), "UPSTREAM_ERROR");
+}
+
+#[tokio::test]
+#[serial]
+async fn truncated_query_is_reported_as_sent_query() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("q", "openai gpt"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let mut config = configure_for_mock_server(&server);
+    config.max_query_length = 10;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let mut args = base_args();
+    args.query = "  openai gpt models  ".to_string();
+    args.disable_cache = Some(true);
+    args.debug = Some(true);
+    let response = service
+        .execute_web_search(args, "trace-sent-query", || false)
+        .await
+        .expect("search should succeed");
+    assert_eq!(response.meta.sent_query.as_deref(), Some("openai gpt"));
+    assert!(
+        response
+            .warnings
+            .iter()
+            .any(|warning| warning.code == "QUERY_TRUNCATED")
+    );
+
+    let mut args = base_args();
+    args.query = "openai gpt".to_string();
+    args.disable_cache = Some(true);
+    args.debug = Some(true);
+    let response = service
+        .execute_web_search(args, "trace-sent-query-unchanged", || false)
+        .await
+        .expect("search should succeed");
+    assert!(response.meta.sent_query.is_none());
+}
+
+#[tokio::test]
+async fn clean_urls_strips_tracking_params_but_keeps_other_params_and_fragments() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(mock_payload(
+            "Tracked",
+            "https://example.com/post?utm_source=feed&id=7&fbclid=abc&lang=en#comments",
+        )))
+        .mount(&server)
+        .await;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let cleaned = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                clean_urls: Some(true),
+                ..base_args()
+            },
+            "trace-clean-urls",
+            || false,
+        )
+        .await
+        .expect("search should succeed");
+    assert_eq!(
+        cleaned.sections[0].results[0].url,
+        "https://example.com/post?id=7&lang=en#comments"
+    );
+
+    let untouched = service
+        .execute_web_search(base_args(), "trace-raw-urls", || false)
+        .await
+        .expect("search should succeed");
+    assert_eq!(
+        untouched.sections[0].results[0].url,
+        "https://example.com/post?utm_source=feed&id=7&fbclid=abc&lang=en#comments"
+    );
+}
+
+#[tokio::test]
+async fn query_operator_arguments_compile_into_the_sent_query() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param(
+            "q",
+            r#"rust async site:docs.rs filetype:pdf intitle:"tokio runtime" -beta"#,
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://docs.rs/a")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("q", "rust site:docs.rs"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("B", "https://docs.rs/b")),
+        )
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let mut config = configure_for_mock_server(&server);
+    config.max_query_length = 24;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config.clone()).expect("service init")
+    });
+    let error = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                query: "rust".to_string(),
+                site: Some("https://docs.rs/".to_string()),
+                exclude_terms: Some(vec!["-nightly".to_string()]),
+                ..base_args()
+            },
+            "trace-operators-too-long",
+            || false,
+        )
+        .await
+        .expect_err("an operator past max_query_length is rejected, not dropped");
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+    assert_eq!(error.details().expect("details")["field"], "exclude_terms");
+
+    config.max_query_length = 400;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+    let response = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                query: "rust async".to_string(),
+                site: Some("Docs.rs".to_string()),
+                filetype: Some(".PDF".to_string()),
+                intitle: Some("tokio \"runtime\"".to_string()),
+                exclude_terms: Some(vec!["beta".to_string()]),
+                ..base_args()
+            },
+            "trace-operators",
+            || false,
+        )
+        .await
+        .expect("search should succeed");
+    assert!(response.warnings.is_empty(), "{:?}", response.warnings);
+
+    let error = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                site: Some("not a host".to_string()),
+                ..base_args()
+            },
+            "trace-operators-invalid",
+            || false,
+        )
+        .await
+        .expect_err("invalid site is rejected");
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+    assert_eq!(error.details().expect("details")["field"], "site");
+}
+
+#[tokio::test]
+#[serial]
+async fn suggest_returns_deduplicated_suggestions_and_caches() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/suggest"))
+        .and(query_param("q", "rust asy"))
+        .and(query_param("count", "3"))
+        .and(query_param("country", "DE"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "type": "suggest",
+            "query": {"original": "rust asy"},
+            "results": [
+                {"query": "rust async"},
+                {"query": "Rust Async"},
+                {"query": "rust async trait"},
+                {"query": "rust async book"}
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    for trace_id in ["trace-suggest-1", "trace-suggest-2"] {
+        let response = service
+            .execute_suggest(
+                SuggestArgs {
+                    query: " rust asy ".to_string(),
+                    country: Some("de".to_string()),
+                    count: Some(3),
+                },
+                trace_id,
+                || false,
+            )
+            .await
+            .expect("suggest should succeed");
+
+        assert_eq!(
+            response.suggestions,
+            vec!["rust async", "rust async trait", "rust async book"]
+        );
+        assert_eq!(response.meta.country.as_deref(), Some("DE"));
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn scheduled_batch_paces_queries_and_accumulates_results() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("q", "rust"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Rust", "https://www.rust-lang.org/")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("q", "tokio"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let scheduler = BatchScheduler::new(std::sync::Arc::new(service));
+
+    let scheduled = scheduler
+        .schedule(BatchScheduleArgs {
+            queries: vec!["rust".to_string(), " ".to_string(), "tokio".to_string()],
+            deadline_secs: Some(1),
+            search_type: None,
+            max_results: Some(3),
+            country: None,
+            search_language: None,
+        })
+        .expect("schedule should succeed");
+    assert_eq!(scheduled.total, 2);
+    assert_eq!(scheduled.interval_ms, 500);
+    assert!(scheduled.warnings.is_empty());
+
+    let mut status = scheduler.status(&scheduled.job_id).expect("job exists");
+    for _ in 0..50 {
+        if status.status == BatchJobStatus::Done {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        status = scheduler.status(&scheduled.job_id).expect("job exists");
+    }
+
+    assert_eq!(status.status, BatchJobStatus::Partial);
+    assert_eq!((status.completed, status.failed), (1, 1));
+    assert_eq!(status.results[0].status, BatchQueryStatus::Done);
+    assert_eq!(
+        status.results[0].response.as_ref().unwrap()["sections"][0]["results"][0]["title"],
+        "Rust"
+    );
+    assert_eq!(status.results[1].status, BatchQueryStatus::Failed);
+    assert!(status.results[1].error.is_some());
+
+    let error = scheduler.status("missing-job").expect_err("unknown job id");
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+}
+
+#[tokio::test]
+#[serial]
+async fn persisted_batch_job_resumes_pending_queries() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("q", "tokio"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("Tokio", "https://tokio.rs/")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let state_dir =
+        std::env::temp_dir().join(format!("codex-brave-batches-{}", std::process::id()));
+    std::fs::create_dir_all(&state_dir).expect("create state dir");
+    let job_file = state_dir.join("job-1.json");
+    std::fs::write(
+        &job_file,
+        serde_json::json!({
+            "job_id": "job-1",
+            "status": "running",
+            "interval_ms": 10,
+            "template": {"queries": [], "max_results": 2},
+            "results": [
+                {"query": "rust", "status": "done", "response": {"cached": true}},
+                {"query": "tokio", "status": "pending"}
+            ],
+            "finished_at_unix_secs": null
+        })
+        .to_string(),
+    )
+    .expect("write job file");
+
+    let mut config = configure_for_mock_server(&server);
+    config.batch_state_dir = Some(state_dir.clone());
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+    let scheduler = BatchScheduler::new(std::sync::Arc::new(service));
+
+    assert_eq!(
+        scheduler.status("job-1").expect("job loaded").status,
+        BatchJobStatus::Running
+    );
+    assert_eq!(scheduler.resume(), 1);
+
+    let mut status = scheduler.status("job-1").expect("job exists");
+    for _ in 0..50 {
+        if status.status == BatchJobStatus::Done {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        status = scheduler.status("job-1").expect("job exists");
+    }
+
+    assert_eq!(status.status, BatchJobStatus::Done);
+    assert_eq!(status.completed, 2);
+    assert_eq!(
+        status.results[0].response,
+        Some(serde_json::json!({"cached": true}))
+    );
+
+    // The final state is written after the in-memory job is updated, off the store lock.
+    let read_persisted = || -> serde_json::Value {
+        serde_json::from_slice(&std::fs::read(&job_file).expect("job file")).expect("json")
+    };
+    let mut persisted = read_persisted();
+    for _ in 0..50 {
+        if persisted["status"] == "done" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        persisted = read_persisted();
+    }
+    assert_eq!(persisted["status"], "done");
+    assert_eq!(persisted["results"][1]["status"], "done");
+    std::fs::remove_dir_all(&state_dir).ok();
+}
+
+#[tokio::test]
+#[serial]
+async fn completed_batch_is_posted_to_webhook_and_drop_dir() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Rust", "https://www.rust-lang.org/")),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/hook"))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&server)
+        .await;
+
+    let drop_dir = std::env::temp_dir().join(format!("codex-brave-notify-{}", std::process::id()));
+    let mut config = configure_for_mock_server(&server);
+    config.notify.webhook_url = Some(format!("{}/hook", server.uri()));
+    config.notify.webhook_secret = Some("s3cret".to_string());
+    config.notify.drop_dir = Some(drop_dir.clone());
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+    let scheduler = BatchScheduler::new(std::sync::Arc::new(service));
+
+    let scheduled = scheduler
+        .schedule(BatchScheduleArgs {
+            queries: vec!["rust".to_string()],
+            ..Default::default()
+        })
+        .expect("schedule should succeed");
+
+    let mut deliveries = Vec::new();
+    for _ in 0..50 {
+        deliveries = server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|request| request.url.path() == "/hook")
+            .collect::<Vec<_>>();
+        if deliveries.len() == 2 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+
+    assert_eq!(deliveries.len(), 2, "one failed attempt plus one retry");
+    let delivery = &deliveries[1];
+    assert_eq!(
+        delivery.headers.get("x-codex-brave-signature").unwrap(),
+        &format!("sha256={}", sign_payload("s3cret", &delivery.body))
+    );
+    let body: serde_json::Value = serde_json::from_slice(&delivery.body).expect("json body");
+    assert_eq!(body["event"], "batch.completed");
+    assert_eq!(body["id"], scheduled.job_id.as_str());
+    assert_eq!(body["data"]["status"], "done");
+
+    let dropped = drop_dir.join(format!("batch.completed-{}.json", scheduled.job_id));
+    let dropped: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(dropped).expect("drop file")).expect("json");
+    assert_eq!(dropped["data"]["completed"], 1);
+    std::fs::remove_dir_all(&drop_dir).ok();
+}
+
+#[tokio::test]
+#[serial]
+async fn max_cache_age_forces_refresh_and_errors_instead_of_stale() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Cached", "https://example.com/cached")),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    service
+        .execute_web_search(base_args(), "trace-age-1", || false)
+        .await
+        .expect("first search should succeed");
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+    let error = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                max_cache_age_secs: Some(0),
+                ..base_args()
+            },
+            "trace-age-2",
+            || false,
+        )
+        .await
+        .expect_err("stale cache must not be served");
+    assert_eq!(error.code(), "UPSTREAM_ERROR");
+
+    let cached = service
+        .execute_web_search(base_args(), "trace-age-3", || false)
+        .await
+        .expect("cache still serves requests without max age");
+    assert_eq!(cached.sections[0].results[0].title, "Cached");
+}
+
+#[tokio::test]
+#[serial]
+async fn debug_tools_feature_controls_payload_and_url_echo() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Debug", "https://example.com/debug")),
+        )
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let response = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                debug: Some(true),
+                include_raw_payload: Some(true),
+                include_request_url: Some(true),
+                ..base_args()
+            },
+            "trace-debug-tools",
+            || false,
+        )
+        .await
+        .expect("debug search should succeed");
+    let debug_data = response.debug_data.expect("debug data");
+    let unavailable = response
+        .warnings
+        .iter()
+        .any(|warning| warning.code == "DEBUG_TOOLS_UNAVAILABLE");
+
+    if cfg!(feature = "debug-tools") {
+        assert!(debug_data.raw_payload.is_some());
+        assert!(
+            debug_data
+                .request_url
+                .is_some_and(|url| url.contains("/web?"))
+        );
+        assert!(!unavailable);
+    } else {
+        assert!(debug_data.raw_payload.is_none());
+        assert!(debug_data.request_url.is_none());
+        assert!(unavailable);
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn raw_payload_fields_reduce_the_debug_payload() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Fields", "https://example.com/fields")),
+        )
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let response = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                debug: Some(true),
+                include_raw_payload: Some(true),
+                raw_payload_fields: Some(vec![
+                    "/web/results/0/title".to_string(),
+                    "results".to_string(),
+                ]),
+                ..base_args()
+            },
+            "trace-raw-fields",
+            || false,
+        )
+        .await
+        .expect("debug search should succeed");
+
+    assert!(response.warnings.iter().any(|warning| {
+        warning.code == "INVALID_RAW_PAYLOAD_FIELD" && warning.message.contains("'results'")
+    }));
+    let raw_payload = response.debug_data.expect("debug data").raw_payload;
+    if cfg!(feature = "debug-tools") {
+        assert_eq!(
+            raw_payload,
+            Some(serde_json::json!({"/web/results/0/title": "Fields"}))
+        );
+    } else {
+        assert!(raw_payload.is_none());
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn debug_timings_break_down_each_phase() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Timed", "https://example.com/timed")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let args = BraveWebSearchArgs {
+        debug: Some(true),
+        ..base_args()
+    };
+
+    let fetched = service
+        .execute_web_search(args.clone(), "trace-timings-1", || false)
+        .await
+        .expect("first search should succeed");
+    let timings = fetched
+        .debug_data
+        .and_then(|debug_data| debug_data.timings)
+        .expect("timings");
+    assert_eq!(timings.upstream_attempts_ms.len(), 1);
+    assert!(timings.body_read_ms.is_some());
+    assert!(timings.parse_ms.is_some());
+    assert!(timings.output_limits_ms >= 0.0);
+
+    let cached = service
+        .execute_web_search(args, "trace-timings-2", || false)
+        .await
+        .expect("cached search should succeed");
+    let timings = cached
+        .debug_data
+        .and_then(|debug_data| debug_data.timings)
+        .expect("timings");
+    assert!(timings.upstream_attempts_ms.is_empty());
+    assert!(timings.body_read_ms.is_none());
+    assert!(timings.parse_ms.is_none());
+}
+
+#[tokio::test]
+async fn pool_settings_reach_the_client_and_protocol_is_reported() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Pooled", "https://example.com/p")),
+        )
+        .mount(&server)
+        .await;
+
+    let debug_args = |query: &str| BraveWebSearchArgs {
+        query: query.to_string(),
+        debug: Some(true),
+        ..base_args()
+    };
+    let mut config = configure_for_mock_server(&server);
+    config.http_pool = HttpPoolSettings {
+        pool_max_idle_per_host: Some(2),
+        pool_idle_timeout_secs: Some(30),
+        tcp_keepalive_secs: Some(60),
+        http2_prior_knowledge: false,
+        connect_timeout_ms: Some(1_000),
+    };
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config.clone()).expect("service init")
+    });
+    let fetched = service
+        .execute_web_search(debug_args("http one"), "trace-pool-1", || false)
+        .await
+        .expect("search should succeed");
+    assert_eq!(
+        fetched
+            .debug_data
+            .and_then(|debug_data| debug_data.http_version)
+            .as_deref(),
+        Some("HTTP/1.1")
+    );
+
+    config.http_pool.http2_prior_knowledge = true;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+    let fetched = service
+        .execute_web_search(debug_args("http two"), "trace-pool-2", || false)
+        .await
+        .expect("search over HTTP/2 should succeed");
+    assert_eq!(
+        fetched
+            .debug_data
+            .and_then(|debug_data| debug_data.http_version)
+            .as_deref(),
+        Some("HTTP/2.0")
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn rate_limit_headers_retune_the_throttle() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("X-RateLimit-Limit", "20, 15000")
+                .insert_header("X-RateLimit-Remaining", "19, 14999")
+                .insert_header("X-RateLimit-Reset", "1, 2592000")
+                .set_body_json(mock_payload("Limited", "https://example.com/limited")),
+        )
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let status_args = || StatusArgs {
+        probe_connectivity: None,
+        verbose: None,
+        include_limits: None,
+    };
+
+    let before = service.status(status_args(), || false).await;
+    assert!(before.settings.throttle_adaptive);
+    assert!((before.settings.throttle_effective_rate_per_sec - 2.0).abs() < f64::EPSILON);
+
+    service
+        .execute_web_search(base_args(), "trace-rate-limit", || false)
+        .await
+        .expect("search should succeed");
+
+    let after = service.status(status_args(), || false).await;
+    assert!((after.settings.throttle_effective_rate_per_sec - 20.0).abs() < f64::EPSILON);
+}
+
+#[tokio::test]
+#[serial]
+async fn rate_limit_window_is_reported_in_meta_and_status() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("X-RateLimit-Limit", "20, 15000")
+                .insert_header("X-RateLimit-Remaining", "7, 14999")
+                .insert_header("X-RateLimit-Reset", "1, 2592000")
+                .set_body_json(mock_payload("Limited", "https://example.com/limited")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let status_args = || StatusArgs {
+        probe_connectivity: None,
+        verbose: None,
+        include_limits: None,
+    };
+    assert!(
+        service
+            .status(status_args(), || false)
+            .await
+            .rate_limit
+            .is_none()
+    );
+
+    let fetched = service
+        .execute_web_search(base_args(), "trace-rate-limit-meta-1", || false)
+        .await
+        .expect("search should succeed");
+    let expected = RateLimitInfo {
+        limit: 20,
+        remaining: 7,
+        reset_secs: 1,
+    };
+    assert_eq!(fetched.meta.rate_limit, Some(expected));
+
+    let cached = service
+        .execute_web_search(base_args(), "trace-rate-limit-meta-2", || false)
+        .await
+        .expect("cached search should succeed");
+    assert!(cached.meta.rate_limit.is_none());
+
+    let status = service.status(status_args(), || false).await;
+    let observed = status.rate_limit.expect("observed rate limit");
+    assert_eq!(observed.window, expected);
+    let json = serde_json::to_value(&status).expect("status serializes");
+    assert_eq!(json["rate_limit"]["remaining"], 7);
+}
+
+#[tokio::test]
+#[serial]
+async fn filter_expression_post_filters_cached_results() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Filtered", "https://example.com/filtered")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let with_filter = |filter: &str| BraveWebSearchArgs {
+        filter: Some(filter.to_string()),
+        ..base_args()
+    };
+
+    let kept = service
+        .execute_web_search(
+            with_filter("domain == 'example.com'"),
+            "trace-filter-1",
+            || false,
+        )
+        .await
+        .expect("matching filter should succeed");
+    assert_eq!(kept.meta.returned, 1);
+
+    let dropped = service
+        .execute_web_search(
+            with_filter("domain endswith '.gov'"),
+            "trace-filter-2",
+            || false,
+        )
+        .await
+        .expect("non-matching filter should succeed");
+    assert_eq!(dropped.meta.returned, 0);
+    assert!(
+        dropped
+            .warnings
+            .iter()
+            .any(|warning| warning.code == "RESULTS_FILTERED")
+    );
+
+    let error = service
+        .execute_web_search(with_filter("domain ~ 'x'"), "trace-filter-3", || false)
+        .await
+        .expect_err("invalid filter must be rejected");
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+}
+
+#[tokio::test]
+#[serial]
+async fn cached_responses_report_cache_hit_in_meta() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Cached", "https://example.com/cached")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let fresh = service
+        .execute_web_search(base_args(), "trace-hit-1", || false)
+        .await
+        .expect("first search should succeed");
+    assert!(!fresh.meta.cache_hit);
+    assert_eq!(fresh.meta.cache_age_secs, None);
+    assert_eq!(fresh.meta.throttle_tokens, 1);
+
+    let cached = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                debug: Some(true),
+                ..base_args()
+            },
+            "trace-hit-2",
+            || false,
+        )
+        .await
+        .expect("second search should be served from cache");
+    assert!(cached.meta.cache_hit);
+    assert_eq!(cached.meta.cache_age_secs, Some(0));
+    assert_eq!(cached.meta.throttle_tokens, 0);
+    let payload = build_response_payload(&cached).expect("build payload");
+    assert!(payload["meta"].get("throttle_tokens").is_none());
+    assert!(
+        cached
+            .notices
+            .iter()
+            .any(|notice| notice.code == "CACHE_HIT")
+    );
+
+    let quiet = service
+        .execute_web_search(base_args(), "trace-hit-3", || false)
+        .await
+        .expect("cached search without debug");
+    assert!(quiet.meta.cache_hit);
+    assert!(
+        quiet
+            .notices
+            .iter()
+            .all(|notice| notice.code != "CACHE_HIT")
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn concurrent_identical_searches_share_one_upstream_call() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Shared", "https://example.com/shared"))
+                .set_delay(std::time::Duration::from_millis(80)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    // freshness bypasses the cache, so only coalescing can keep this to one upstream call
+    let args = || BraveWebSearchArgs {
+        freshness: Some("pd".to_string()),
+        ..base_args()
+    };
+    let (first, second) = tokio::join!(
+        service.execute_web_search(args(), "trace-flight-1", || false),
+        service.execute_web_search(args(), "trace-flight-2", || false),
+    );
+
+    let first = first.expect("leader should succeed");
+    let second = second.expect("follower should succeed");
+    assert_eq!(first.sections[0].results[0].title, "Shared");
+    assert_eq!(second.sections[0].results[0].title, "Shared");
+    assert_eq!(
+        [first.meta.upstream_ms, second.meta.upstream_ms]
+            .iter()
+            .filter(|ms| ms.is_some())
+            .count(),
+        1
+    );
+    // Only the leader's upstream call takes a throttle token.
+    assert_eq!(first.meta.throttle_tokens + second.meta.throttle_tokens, 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn next_cursor_resumes_pagination_with_same_params() {
+    let server = MockServer::start().await;
+
+    let news_page = |title: &str, more: bool| {
+        serde_json::json!({
+            "type": "news",
+            "query": {"original": "openai", "more_results_available": more},
+            "results": [{"title": title, "url": "https://example.com/news", "description": "desc"}]
+        })
+    };
+    Mock::given(method("GET"))
+        .and(path("/news"))
+        .and(query_param_is_missing("offset"))
+        .and(query_param("country", "DE"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(news_page("Page one", true)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/news"))
+        .and(query_param("q", "openai"))
+        .and(query_param("offset", "1"))
+        .and(query_param("count", "3"))
+        .and(query_param("country", "DE"))
+        .and(query_param("freshness", "pw"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(news_page("Page two", false)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let first = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                search_type: Some("news".to_string()),
+                max_results: Some(3),
+                country: Some("de".to_string()),
+                freshness: Some("pw".to_string()),
+                ..base_args()
+            },
+            "trace-cursor-1",
+            || false,
+        )
+        .await
+        .expect("first page should succeed");
+    let cursor = first
+        .next_cursor
+        .clone()
+        .expect("next_cursor when has_more");
+
+    let second = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                query: String::new(),
+                cursor: Some(cursor),
+                ..Default::default()
+            },
+            "trace-cursor-2",
+            || false,
+        )
+        .await
+        .expect("cursor page should succeed");
+    assert_eq!(second.meta.offset, 1);
+    assert_eq!(second.meta.search_type, SearchType::News);
+    assert!(second.next_cursor.is_none());
+
+    let error = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                cursor: Some("not-a-cursor".to_string()),
+                ..base_args()
+            },
+            "trace-cursor-3",
+            || false,
+        )
+        .await
+        .expect_err("corrupted cursor is rejected");
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+}
+
+#[tokio::test]
+#[serial]
+async fn correct_query_searches_spellchecked_query() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/spellcheck"))
+        .and(query_param("q", "opnai"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "type": "spellcheck",
+            "query": {"original": "opnai"},
+            "results": [{"query": "openai"}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("q", "openai"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let args = || BraveWebSearchArgs {
+        query: "opnai".to_string(),
+        correct_query: Some(true),
+        ..base_args()
+    };
+    let response = service
+        .execute_web_search(args(), "trace-correct-query", || false)
+        .await
+        .expect("search should succeed");
+
+    assert_eq!(response.meta.sent_query.as_deref(), Some("openai"));
+    assert!(
+        response
+            .warnings
+            .iter()
+            .any(|warning| warning.code == "QUERY_CORRECTED")
+    );
+    assert_eq!(response.meta.throttle_tokens, 2);
+
+    // The spellcheck pre-flight and the search are both cached now, so the repeat is free.
+    let repeat = service
+        .execute_web_search(args(), "trace-correct-query-repeat", || false)
+        .await
+        .expect("repeat should succeed");
+    assert!(repeat.meta.cache_hit);
+    assert_eq!(repeat.meta.sent_query.as_deref(), Some("openai"));
+    assert_eq!(repeat.meta.throttle_tokens, 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn location_results_are_enriched_with_poi_details() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("result_filter", "locations"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "query": {"original": "coffee near me"},
+            "locations": {
+                "results": [
+                    {
+                        "id": "poi-1",
+                        "title": "Bean There",
+                        "url": "https://beanthere.example/",
+                        "postal_address": {"displayAddress": "1 Main St, Springfield"}
+                    },
+                    {
+                        "id": "poi-2",
+                        "title": "Grounds",
+                        "provider_url": "https://grounds.example/"
+                    }
+                ]
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/local/pois"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "type": "local_pois",
+            "results": [{
+                "id": "poi-1",
+                "postal_address": {"displayAddress": "1 Main St, Springfield"},
+                "contact": {"telephone": "+1 555 0100"},
+                "rating": {"ratingValue": 4.5, "reviewCount": 120},
+                "opening_hours": {
+                    "days": [[{"abbr_name": "Mon", "opens": "07:00", "closes": "18:00"}]]
+                }
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/local/descriptions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "type": "local_descriptions",
+            "results": [{"id": "poi-2", "description": "Small-batch roaster."}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let mut args = base_args();
+    args.query = "coffee near me".to_string();
+    args.result_filter = Some(vec!["locations".to_string()]);
+    let response = service
+        .execute_web_search(args, "trace-locations", || false)
+        .await
+        .expect("search should succeed");
+
+    let section = &response.sections[0];
+    assert_eq!(section.key.as_str(), "locations");
+    let first = section.results[0].local.as_ref().expect("poi details");
+    assert_eq!(first.phone.as_deref(), Some("+1 555 0100"));
+    assert_eq!(first.rating, Some(4.5));
+    assert_eq!(first.review_count, Some(120));
+    assert_eq!(first.opening_hours, vec!["Mon 07:00-18:00"]);
+    assert_eq!(
+        section.results[0].location.as_deref(),
+        Some("1 Main St, Springfield")
+    );
+    assert_eq!(section.results[1].url, "https://grounds.example/");
+    assert_eq!(
+        section.results[1]
+            .local
+            .as_ref()
+            .and_then(|local| local.description.as_deref()),
+        Some("Small-batch roaster.")
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn goggles_are_forwarded_and_part_of_the_cache_key() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param(
+            "goggles",
+            "https://goggles.example.com/tech.goggle",
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("G", "https://example.com/g")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param_is_missing("goggles"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let mut args = base_args();
+    args.goggles = Some("https://goggles.example.com/tech.goggle".to_string());
+    let goggled = service
+        .execute_web_search(args, "trace-goggles", || false)
+        .await
+        .expect("search should succeed");
+    assert_eq!(goggled.sections[0].results[0].title, "G");
+
+    let mut args = base_args();
+    args.goggles = Some("javascript:alert(1)".to_string());
+    let plain = service
+        .execute_web_search(args, "trace-goggles-invalid", || false)
+        .await
+        .expect("search should succeed");
+    assert_eq!(plain.sections[0].results[0].title, "A");
+    assert!(
+        plain
+            .warnings
+            .iter()
+            .any(|warning| warning.code == "INVALID_GOGGLES")
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn format_argument_selects_registered_renderer() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Rendered", "https://example.com/rendered")),
+        )
+        .mount(&server)
+        .await;
+
+    let mut service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    service.register_renderer(Arc::new(UrlListRenderer));
+    let with_format = |format: &str| BraveWebSearchArgs {
+        format: Some(format.to_string()),
+        ..base_args()
+    };
+
+    let response = service
+        .execute_web_search(with_format("Markdown"), "trace-format-1", || false)
+        .await
+        .expect("markdown search should succeed");
+    let markdown = service
+        .render_response(&response, Some("Markdown"), None)
+        .expect("render markdown");
+    assert!(markdown.contains("[Rendered](https://example.com/rendered)"));
+
+    let response = service
+        .execute_web_search(with_format("urls"), "trace-format-2", || false)
+        .await
+        .expect("custom format should succeed");
+    let urls = service
+        .render_response(&response, Some("urls"), None)
+        .expect("render urls");
+    assert_eq!(urls, "https://example.com/rendered");
+
+    let compact = BraveWebSearchArgs {
+        compact: Some(true),
+        ..base_args()
+    };
+    let response = service
+        .execute_web_search(compact, "trace-format-compact", || false)
+        .await
+        .expect("compact search should succeed");
+    let json = service
+        .render_response(&response, None, Some(true))
+        .expect("render compact json");
+    assert_eq!(json.lines().count(), 1);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&json).expect("valid json")["sections"][0]["results"]
+            [0]["url"],
+        "https://example.com/rendered"
+    );
+
+    let error = service
+        .execute_web_search(with_format("yaml"), "trace-format-3", || false)
+        .await
+        .expect_err("unknown format must be rejected");
+    assert_eq!(error.code(), "INVALID_ARGUMENT");
+    let allowed = &error.details().expect("details")["allowed"];
+    assert_eq!(
+        allowed,
+        &serde_json::json!(["json", "markdown", "text", "csv", "rss", "urls"])
+    );
+}
+
+struct UrlListRenderer;
+
+impl ResponseRenderer for UrlListRenderer {
+    fn format(&self) -> &'static str {
+        "urls"
+    }
+
+    fn render(&self, response: &SearchResponse) -> Result<String, serde_json::Error> {
+        Ok(response
+            .sections
+            .iter()
+            .flat_map(|section| &section.results)
+            .map(|item| item.url.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn trace_propagation_sends_traceparent_and_baggage() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Traced", "https://example.com/traced")),
+        )
+        .mount(&server)
+        .await;
+
+    let mut config = configure_for_mock_server(&server);
+    config.trace_propagation = true;
+    config.trace_baggage = Some("tenant=acme".to_string());
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let response = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                debug: Some(true),
+                disable_cache: Some(true),
+                ..base_args()
+            },
+            "0f8e4a52-6c1d-4e7b-9a3f-2b5c8d9e1a70",
+            || false,
+        )
+        .await
+        .expect("search should succeed");
+    let debug = response.debug_data.expect("debug data");
+    assert_eq!(
+        debug.trace_id.as_deref(),
+        Some("0f8e4a526c1d4e7b9a3f2b5c8d9e1a70")
+    );
+
+    let requests = server.received_requests().await.expect("recorded requests");
+    let headers = &requests[0].headers;
+    let traceparent = headers
+        .get("traceparent")
+        .and_then(|value| value.to_str().ok())
+        .expect("traceparent header");
+    let parts = traceparent.split('-').collect::<Vec<_>>();
+    assert_eq!(parts.len(), 4);
+    assert_eq!(parts[0], "00");
+    assert_eq!(parts[1], "0f8e4a526c1d4e7b9a3f2b5c8d9e1a70");
+    assert_eq!(parts[2].len(), 16);
+    assert_eq!(parts[3], "01");
+    assert_eq!(
+        headers.get("baggage").and_then(|value| value.to_str().ok()),
+        Some("tenant=acme")
+    );
+
+    // Non-UUID trace ids (e.g. batch jobs) are hashed into a valid 16-byte trace id.
+    let hashed = trace_context_for("job-1-0", None);
+    assert_eq!(hashed.trace_id.len(), 32);
+    assert!(hashed.trace_id.bytes().all(|byte| byte.is_ascii_hexdigit()));
+    assert_eq!(hashed.trace_id, trace_context_for("job-1-0", None).trace_id);
+}
+
+#[tokio::test]
+#[serial]
+async fn accept_language_follows_locale_unless_overridden() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Sprache", "https://example.com/sprache")),
+        )
+        .mount(&server)
+        .await;
+
+    let args = || BraveWebSearchArgs {
+        ui_language: Some("de-DE".to_string()),
+        search_language: Some("de".to_string()),
+        debug: Some(true),
+        disable_cache: Some(true),
+        ..base_args()
+    };
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let derived = service
+        .execute_web_search(args(), "trace-lang-1", || false)
+        .await
+        .expect("search should succeed");
+    assert_eq!(
+        derived
+            .debug_data
+            .and_then(|debug| debug.accept_language)
+            .as_deref(),
+        Some("de-DE,de;q=0.9")
+    );
+
+    let mut config = configure_for_mock_server(&server);
+    config.accept_language = Some("en-US".to_string());
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+    service
+        .execute_web_search(args(), "trace-lang-2", || false)
+        .await
+        .expect("search should succeed");
+
+    let sent = server
+        .received_requests()
+        .await
+        .expect("recorded requests")
+        .iter()
+        .map(|request| {
+            request
+                .headers
+                .get("accept-language")
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sent,
+        [
+            Some("de-DE,de;q=0.9".to_string()),
+            Some("en-US".to_string())
+        ]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn mirror_compares_shadow_results_without_affecting_response() {
+    let primary = MockServer::start().await;
+    let mirror = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Primary", "https://example.com/primary")),
+        )
+        .mount(&primary)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/shadow/web"))
+        .and(query_param("q", "openai"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Primary", "https://example.com/primary")),
+        )
+        .mount(&mirror)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/shadow/web"))
+        .and(query_param("q", "rust"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Shadow", "https://example.com/shadow")),
+        )
+        .mount(&mirror)
+        .await;
+
+    let mut config = configure_for_mock_server(&primary);
+    config.mirror = Some(MirrorSettings {
+        url: format!("{}/shadow", mirror.uri()),
+        percent: 100,
+        send_api_key: false,
+    });
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    for query in ["openai", "rust"] {
+        let response = service
+            .execute_web_search(
+                BraveWebSearchArgs {
+                    query: query.to_string(),
+                    ..base_args()
+                },
+                "trace-mirror",
+                || false,
+            )
+            .await
+            .expect("search should succeed");
+        assert_eq!(
+            response.sections[0].results[0].url,
+            "https://example.com/primary"
+        );
+    }
+
+    let mut stats = None;
+    for _ in 0..100 {
+        let current = service
+            .status(
+                StatusArgs {
+                    probe_connectivity: None,
+                    verbose: None,
+                    include_limits: None,
+                },
+                || false,
+            )
+            .await
+            .mirror
+            .expect("mirror stats");
+        if current.matched + current.mismatched + current.failed == 2 {
+            stats = Some(current);
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    let stats = stats.expect("mirror requests should complete");
+    assert_eq!(stats.mirrored, 2);
+    assert_eq!(stats.matched, 1);
+    assert_eq!(stats.mismatched, 1);
+    assert_eq!(stats.failed, 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn mirror_rejection_leaves_primary_key_and_retry_budget_alone() {
+    let primary = MockServer::start().await;
+    let mirror = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(header("x-subscription-token", "test-key"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Primary", "https://example.com/primary")),
+        )
+        .mount(&primary)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/shadow/web"))
+        .and(query_param("q", "openai"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("unavailable"))
+        .mount(&mirror)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/shadow/web"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("unauthorized"))
+        .mount(&mirror)
+        .await;
+
+    let mut config = configure_for_mock_server(&primary);
+    config.retry_count = 2;
+    config.retry_budget_per_min = 1;
+    config.mirror = Some(MirrorSettings {
+        url: format!("{}/shadow", mirror.uri()),
+        percent: 100,
+        send_api_key: false,
+    });
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    for (position, query) in ["openai", "rust", "tokio"].into_iter().enumerate() {
+        let response = service
+            .execute_web_search(
+                BraveWebSearchArgs {
+                    query: query.to_string(),
+                    ..base_args()
+                },
+                "trace-mirror-401",
+                || false,
+            )
+            .await
+            .expect("primary search should keep working after mirror 401s");
+        assert_eq!(
+            response.sections[0].results[0].url,
+            "https://example.com/primary"
+        );
+        for _ in 0..100 {
+            let failed = service
+                .status(
+                    StatusArgs {
+                        probe_connectivity: None,
+                        verbose: None,
+                        include_limits: None,
+                    },
+                    || false,
+                )
+                .await
+                .mirror
+                .expect("mirror stats")
+                .failed;
+            // Wait for the mirror's 401 to land before the next primary search.
+            if failed == position as u64 + 1 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    }
+
+    let mirrored = mirror.received_requests().await.expect("recorded requests");
+    assert_eq!(mirrored.len(), 3, "mirror requests are never retried");
+    assert!(
+        mirrored
+            .iter()
+            .all(|request| !request.headers.contains_key("x-subscription-token")),
+        "the API key is not sent to the mirror by default"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn compare_params_groups_overlap_and_differences() {
+    let server = MockServer::start().await;
+    let payload = |urls: &[&str]| {
+        serde_json::json!({
+            "query": {"original": "openai", "more_results_available": false},
+            "web": {
+                "results": urls
+                    .iter()
+                    .map(|url| serde_json::json!({"title": url, "url": url, "description": "desc"}))
+                    .collect::<Vec<_>>()
+            }
+        })
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("country", "US"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(payload(&[
+            "https://shared.example/a",
+            "https://us.example/only",
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("country", "DE"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(payload(&[
+            "https://de.example/only",
+            "https://shared.example/a/",
+        ])))
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let response = service
+        .execute_compare_params(
+            CompareParamsArgs {
+                query: "openai".to_string(),
+                search_type: None,
+                max_results: Some(5),
+                a: CompareParamSet {
+                    country: Some("US".to_string()),
+                    ..Default::default()
+                },
+                b: CompareParamSet {
+                    country: Some("de".to_string()),
+                    ..Default::default()
+                },
+            },
+            "trace-compare",
+            || false,
+        )
+        .await
+        .expect("compare should succeed");
+
+    assert_eq!(response.overlap.len(), 1);
+    assert_eq!(response.overlap[0].url, "https://shared.example/a");
+    assert_eq!(
+        (response.overlap[0].rank_a, response.overlap[0].rank_b),
+        (1, 2)
+    );
+    assert_eq!(response.only_in_a.len(), 1);
+    assert_eq!(response.only_in_a[0].url, "https://us.example/only");
+    assert_eq!(response.only_in_b.len(), 1);
+    assert_eq!(response.only_in_b[0].url, "https://de.example/only");
+    assert_eq!((response.meta.returned_a, response.meta.returned_b), (2, 2));
+}
+
+#[cfg(feature = "metrics")]
+#[tokio::test]
+#[serial]
+async fn metrics_count_searches_cache_errors_and_upstream_latency() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    for _ in 0..2 {
+        service
+            .execute_web_search(base_args(), "trace-metrics", || false)
+            .await
+            .expect("search should succeed");
+    }
+    let mut invalid = base_args();
+    invalid.query = "   ".to_string();
+    service
+        .execute_web_search(invalid, "trace-metrics-invalid", || false)
+        .await
+        .expect_err("blank query should fail");
+
+    let metrics = service.metrics("trace-metrics-snapshot").await;
+    assert_eq!(metrics.searches.total, 2);
+    assert_eq!(metrics.searches.by_type.get("web"), Some(&2));
+    assert_eq!((metrics.cache.hits, metrics.cache.misses), (1, 1));
+    assert_eq!(metrics.errors.get("INVALID_ARGUMENT"), Some(&1));
+    assert_eq!(metrics.upstream_latency.calls, 1);
+    assert!(metrics.upstream_latency.p50_ms.is_some());
+    assert_eq!(
+        metrics.upstream_latency.p50_ms,
+        metrics.upstream_latency.p95_ms
+    );
+    assert_eq!(metrics.throttle.waits, 0);
+    assert_eq!(metrics.meta.trace_id, "trace-metrics-snapshot");
+}
+
+#[cfg(feature = "archive")]
+#[tokio::test]
+#[serial]
+async fn replay_reruns_archived_search_uncached_and_diffs_responses() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("country", "DE"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("country", "DE"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("B", "https://example.com/a")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let archive_dir =
+        std::env::temp_dir().join(format!("codex-brave-archive-{}", std::process::id()));
+    let mut config = configure_for_mock_server(&server);
+    config.archive_dir = Some(archive_dir.clone());
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let mut args = base_args();
+    args.country = Some("de".to_string());
+    service
+        .execute_web_search(args, "trace-archived", || false)
+        .await
+        .expect("search should succeed");
+
+    let replay = service
+        .execute_replay(
+            ReplayArgs {
+                trace_id: "trace-archived".to_string(),
+            },
+            "trace-replayed",
+            || false,
+        )
+        .await
+        .expect("replay should succeed");
+
+    assert_eq!(replay.original_trace_id, "trace-archived");
+    assert_eq!(replay.meta.trace_id, "trace-replayed");
+    assert_eq!(
+        replay.diff,
+        vec![FieldDiff {
+            path: "sections[0].results[0].title".to_string(),
+            original: Some(serde_json::json!("A")),
+            replayed: Some(serde_json::json!("B")),
+        }]
+    );
+    assert!(archive_dir.join("trace-replayed.json").exists());
+
+    for trace_id in ["unknown-trace", "../trace-archived"] {
+        let error = service
+            .execute_replay(
+                ReplayArgs {
+                    trace_id: trace_id.to_string(),
+                },
+                "trace-missing",
+                || false,
+            )
+            .await
+            .expect_err("unknown trace id should fail");
+        assert_eq!(error.code(), "INVALID_ARGUMENT");
+    }
+    std::fs::remove_dir_all(&archive_dir).ok();
+}
+
+#[tokio::test]
+#[serial]
+async fn news_topic_composes_query_and_counts_topic_facets() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/news"))
+        .and(query_param("q", "openai technology"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "type": "news",
+            "query": {"original": "openai technology", "more_results_available": true},
+            "results": [
+                {"title": "New AI chips unveiled", "url": "https://example.com/tech/chips", "description": "Software and hardware"},
+                {"title": "Startup raises funding", "url": "https://example.com/technology/funding", "description": "desc"},
+                {"title": "Investors cheer stock rally", "url": "https://example.com/markets/rally", "description": "desc"},
+                {"title": "Weekend weather", "url": "https://example.com/local/weather", "description": "desc"}
+            ]
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+
+    let response = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                search_type: Some("news".to_string()),
+                topic: Some("Tech".to_string()),
+                ..base_args()
+            },
+            "trace-topic",
+            || false,
+        )
+        .await
+        .expect("topic search should succeed");
+
+    assert_eq!(
+        response.meta.sent_query.as_deref(),
+        Some("openai technology")
+    );
+    let facets = response
+        .topics
+        .iter()
+        .map(|facet| (facet.topic.as_str(), facet.count))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        facets,
+        vec![("technology", 2), ("business", 1), ("other", 1)]
+    );
+
+    // The cursor keeps the topic without appending the term a second time.
+    let next = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                query: String::new(),
+                cursor: response.next_cursor.clone(),
+                ..Default::default()
+            },
+            "trace-topic-2",
+            || false,
+        )
+        .await
+        .expect("cursor page should succeed");
+    assert!(!next.topics.is_empty());
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("q", "openai"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    let web = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                topic: Some("sports".to_string()),
+                ..base_args()
+            },
+            "trace-topic-web",
+            || false,
+        )
+        .await
+        .expect("web search should succeed");
+    assert!(web.topics.is_empty());
+    assert!(
+        web.warnings
+            .iter()
+            .any(|warning| warning.code == "TOPIC_IGNORED")
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn oversized_snippet_is_capped_instead_of_evicting_other_results() {
+    let server = MockServer::start().await;
+    let huge = "x".repeat(50 * 1_024);
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "query": {"original": "openai", "more_results_available": false},
+            "web": {
+                "results": [
+                    {"title": "Huge", "url": "https://example.com/huge", "description": huge},
+                    {"title": "B", "url": "https://example.com/b", "description": "desc"},
+                    {"title": "C", "url": "https://example.com/c", "description": "desc"}
+                ]
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let mut config = configure_for_mock_server(&server);
+    config.output_limits.max_snippet_chars = 500;
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let response = service
+        .execute_web_search(base_args(), "trace-field-caps", || false)
+        .await
+        .expect("search should succeed");
+
+    let results = &response.sections[0].results;
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].snippet.chars().count(), 500);
+    assert!(results[0].snippet.ends_with('…'));
+    assert!(
+        response
+            .warnings
+            .iter()
+            .any(|warning| warning.code == "FIELDS_TRUNCATED")
+    );
+}
+
+async fn mount_repeat_payload(server: &MockServer, expected_calls: u64) {
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .expect(expected_calls)
+        .mount(server)
+        .await;
+}
+
+/// Runs `args` three times against a service that flags the third identical search as a repeat,
+/// and returns the third response.
+async fn third_repeat(
+    server: &MockServer,
+    args: impl Fn() -> BraveWebSearchArgs,
+) -> SearchResponse {
+    let mut config = configure_for_mock_server(server);
+    config.repeat_query = RepeatQuerySettings {
+        threshold: 2,
+        window_secs: 60,
+    };
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    for attempt in 1..=2 {
+        let response = service
+            .execute_web_search(args(), "trace-repeat", || false)
+            .await
+            .expect("search should succeed");
+        assert!(
+            !response
+                .warnings
+                .iter()
+                .any(|warning| warning.code == "REPEATED_QUERY"),
+            "attempt {attempt}"
+        );
+    }
+
+    let third = service
+        .execute_web_search(args(), "trace-repeat", || false)
+        .await
+        .expect("repeat should succeed");
+    let warning = third
+        .warnings
+        .iter()
+        .find(|warning| warning.code == "REPEATED_QUERY")
+        .expect("repeat warning");
+    assert!(warning.message.contains("3 times in the last 60s"));
+    third
+}
+
+#[tokio::test]
+#[serial]
+async fn repeated_query_warns_and_is_served_from_cache() {
+    let server = MockServer::start().await;
+    mount_repeat_payload(&server, 1).await;
+
+    let third = third_repeat(&server, base_args).await;
+    assert!(third.meta.cache_hit);
+    assert!(third.warnings.iter().any(|warning| {
+        warning.code == "REPEATED_QUERY" && warning.message.contains("Served from the cached")
+    }));
+}
+
+#[tokio::test]
+#[serial]
+async fn repeated_query_with_disable_cache_still_fetches_upstream() {
+    let server = MockServer::start().await;
+    mount_repeat_payload(&server, 3).await;
+
+    let third = third_repeat(&server, || BraveWebSearchArgs {
+        debug: Some(true),
+        disable_cache: Some(true),
+        ..base_args()
+    })
+    .await;
+    assert!(!third.meta.cache_hit);
+}
+
+#[tokio::test]
+#[serial]
+async fn repeated_query_respects_max_cache_age_secs() {
+    let server = MockServer::start().await;
+    mount_repeat_payload(&server, 3).await;
+
+    // A zero max age treats every cached entry as stale, repeats included.
+    let third = third_repeat(&server, || BraveWebSearchArgs {
+        max_cache_age_secs: Some(0),
+        ..base_args()
+    })
+    .await;
+    assert!(!third.meta.cache_hit);
+}
+
+#[tokio::test]
+#[serial]
+async fn revoked_key_fails_fast_and_degrades_status() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+            "type": "ErrorResponse",
+            "error": {"detail": "The provided subscription token is invalid."}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let status_args = || StatusArgs {
+        probe_connectivity: None,
+        verbose: None,
+        include_limits: None,
+    };
+
+    service
+        .execute_web_search(base_args(), "trace-key-1", || false)
+        .await
+        .expect("first search should succeed");
+    let status = service.status(status_args(), || false).await;
+    assert_eq!(status.status, "ok");
+    assert_eq!(status.key_config.state, KeyState::Valid);
+
+    for query in ["second", "third"] {
+        let error = service
+            .execute_web_search(
+                BraveWebSearchArgs {
+                    query: query.to_string(),
+                    ..base_args()
+                },
+                "trace-key-2",
+                || false,
+            )
+            .await
+            .expect_err("revoked key should fail");
+        assert_eq!(error.code(), "API_KEY_REVOKED");
+        assert!(error.details().expect("details")["remediation"].is_array());
+    }
+
+    let status = service.status(status_args(), || false).await;
+    assert_eq!(status.status, "degraded");
+    assert_eq!(status.key_config.state, KeyState::Invalid);
+}
+
+#[tokio::test]
+#[serial]
+async fn reloaded_key_file_clears_revoked_state_and_reprobes() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(header("X-Subscription-Token", "old-key"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("A", "https://example.com/a")),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(header("X-Subscription-Token", "old-key"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(header("X-Subscription-Token", "new-key"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(mock_payload("B", "https://example.com/b")),
+        )
+        .mount(&server)
+        .await;
+
+    let key_file = std::env::temp_dir().join(format!("codex-brave-key-{}.txt", std::process::id()));
+    std::fs::write(&key_file, "old-key\n").expect("write key file");
+    let key_env = [
+        ("CODEX_BRAVE_API_KEY_FILE", Some(key_file.as_os_str())),
+        ("BRAVE_SEARCH_API_KEY", None),
+        ("BRAVE_API_KEY", None),
+    ];
+    let config = configure_for_mock_server(&server);
+    let client = temp_env::with_vars(key_env, || {
+        BraveClient::new(config.clone()).expect("client init")
+    });
+    let service = SearchService::with_provider(config, client.clone());
+    let status_args = |probe_connectivity| StatusArgs {
+        probe_connectivity: Some(probe_connectivity),
+        verbose: None,
+        include_limits: None,
+    };
+
+    service
+        .execute_web_search(base_args(), "trace-reload-1", || false)
+        .await
+        .expect("old key works at first");
+    let error = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                query: "second".to_string(),
+                ..base_args()
+            },
+            "trace-reload-2",
+            || false,
+        )
+        .await
+        .expect_err("old key is revoked");
+    assert_eq!(error.code(), "API_KEY_REVOKED");
+    assert_eq!(
+        service.status(status_args(false), || false).await.status,
+        "degraded"
+    );
+
+    std::fs::write(&key_file, "new-key\n").expect("rotate key file");
+    let reloaded = temp_env::with_vars(key_env, || client.reload_key());
+    assert_eq!(reloaded.key.as_deref(), Some("new-key"));
+    assert_eq!(reloaded.source.as_deref(), Some("CODEX_BRAVE_API_KEY_FILE"));
+    assert_eq!(client.key_state(), KeyState::Unverified);
+
+    let status = service.status(status_args(true), || false).await;
+    assert_eq!(status.status, "ok");
+    assert_eq!(status.key_config.state, KeyState::Valid);
+    assert!(!status.probe.expect("probe ran").degraded);
+    service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                query: "third".to_string(),
+                ..base_args()
+            },
+            "trace-reload-3",
+            || false,
+        )
+        .await
+        .expect("rotated key works");
+
+    let _ = std::fs::remove_file(&key_file);
+}
+
+#[tokio::test]
+#[serial]
+async fn delta_only_drops_results_returned_by_earlier_pages() {
+    let server = MockServer::start().await;
+    let web_page = |urls: &[&str], more: bool| {
+        serde_json::json!({
+            "query": {"original": "openai", "more_results_available": more},
+            "web": {"results": urls
+                .iter()
+                .map(|url| serde_json::json!({"title": format!("Title {url}"), "url": url}))
+                .collect::<Vec<_>>()}
+        })
+    };
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param_is_missing("offset"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(web_page(
+            &["https://example.com/a", "https://example.com/b"],
+            true,
+        )))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("offset", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(web_page(
+            &["https://EXAMPLE.com/b/", "https://example.com/c"],
+            true,
+        )))
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let first = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                max_results: Some(2),
+                ..base_args()
+            },
+            "trace-delta-1",
+            || false,
+        )
+        .await
+        .expect("first page should succeed");
+    assert_eq!(first.meta.suppressed_repeats, None);
+    let cursor = first.next_cursor.clone().expect("next_cursor");
+
+    let continuation = |delta_only| BraveWebSearchArgs {
+        query: String::new(),
+        cursor: Some(cursor.clone()),
+        delta_only: Some(delta_only),
+        ..Default::default()
+    };
+    let delta = service
+        .execute_web_search(continuation(true), "trace-delta-2", || false)
+        .await
+        .expect("delta page should succeed");
+    let urls = delta.sections[0]
+        .results
+        .iter()
+        .map(|item| item.url.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(urls, ["https://example.com/c"]);
+    assert_eq!(delta.meta.suppressed_repeats, Some(1));
+
+    let full = service
+        .execute_web_search(continuation(false), "trace-delta-3", || false)
+        .await
+        .expect("full page should succeed");
+    assert_eq!(full.sections[0].results.len(), 2);
+    assert_eq!(full.meta.suppressed_repeats, None);
+}
+
+#[tokio::test]
+#[serial]
+async fn auto_paginate_fills_max_results_from_follow_up_pages() {
+    let server = MockServer::start().await;
+    let web_page = |urls: &[&str]| {
+        serde_json::json!({
+            "query": {"original": "openai", "more_results_available": true},
+            "web": {"results": urls
+                .iter()
+                .map(|url| serde_json::json!({"title": format!("Title {url}"), "url": url}))
+                .collect::<Vec<_>>()}
+        })
+    };
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param_is_missing("offset"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(web_page(&[
+            "https://example.com/a",
+            "https://example.com/b",
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("offset", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(web_page(&[
+            "https://example.com/b?utm_source=feed",
+            "https://example.com/c",
+            "https://example.com/d",
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .and(query_param("offset", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(web_page(&[])))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let response = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                max_results: Some(4),
+                auto_paginate: Some(true),
+                explain: Some(true),
+                ..base_args()
+            },
+            "trace-auto-paginate",
+            || false,
+        )
+        .await
+        .expect("auto-paginated search should succeed");
+
+    let urls = response.sections[0]
+        .results
+        .iter()
+        .map(|item| item.url.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        urls,
+        [
+            "https://example.com/a",
+            "https://example.com/b",
+            "https://example.com/c",
+            "https://example.com/d",
+        ]
+    );
+    assert_eq!(response.meta.pages_fetched, Some(2));
+    assert_eq!(response.meta.returned, 4);
+    assert_eq!(
+        response
+            .explanations
+            .iter()
+            .map(|explanation| explanation.provider_rank)
+            .collect::<Vec<_>>(),
+        [1, 2, 6, 7]
+    );
+
+    let cursor = decode_cursor(&response.next_cursor.expect("next_cursor")).expect("cursor");
+    assert_eq!(cursor.offset, 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn api_version_v2_next_offset_skips_auto_paginated_pages() {
+    let server = MockServer::start().await;
+    for (offset, url) in [
+        (None, "https://example.com/a"),
+        (Some("1"), "https://example.com/b"),
+    ] {
+        let mock = Mock::given(method("GET")).and(path("/web"));
+        let mock = match offset {
+            Some(offset) => mock.and(query_param("offset", offset)),
+            None => mock.and(query_param_is_missing("offset")),
+        };
+        mock.respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "query": {"original": "openai", "more_results_available": true},
+            "web": {"results": [{"title": "Title", "url": url}]}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    }
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let response = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                max_results: Some(2),
+                auto_paginate: Some(true),
+                api_version: Some("v2".to_string()),
+                ..base_args()
+            },
+            "trace-v2-auto-paginate",
+            || false,
+        )
+        .await
+        .expect("auto-paginated search should succeed");
+    assert_eq!(response.meta.pages_fetched, Some(2));
+
+    let payload = build_response_payload(&response).expect("build v2 payload");
+    assert_eq!(payload["pagination"]["offset"], 0);
+    assert_eq!(payload["pagination"]["next_offset"], 2);
+    let cursor = decode_cursor(&response.next_cursor.expect("next_cursor")).expect("cursor");
+    assert_eq!(cursor.offset, 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn offline_mode_serves_fixtures_without_an_api_key() {
+    let fixture_dir =
+        std::env::temp_dir().join(format!("codex-brave-offline-{}", std::process::id()));
+    std::fs::create_dir_all(fixture_dir.join("web")).expect("fixture dir");
+    std::fs::write(
+        fixture_dir.join("web.json"),
+        mock_payload("Fallback", "https://example.com/fallback").to_string(),
+    )
+    .expect("write fallback fixture");
+    std::fs::write(
+        fixture_dir.join("web").join("rust-async.json"),
+        mock_payload("Per query", "https://example.com/rust-async").to_string(),
+    )
+    .expect("write per-query fixture");
+
+    let key_env = [
+        ("BRAVE_SEARCH_API_KEY", None),
+        ("BRAVE_API_KEY", None),
+        ("CODEX_BRAVE_API_KEY_FILE", None),
+        (
+            "CODEX_BRAVE_OFFLINE_FIXTURE_DIR",
+            Some(fixture_dir.as_os_str()),
+        ),
+    ];
+    let service = temp_env::with_vars(key_env, || {
+        SearchService::new(RuntimeConfig::from_env()).expect("service init")
+    });
+    assert!(
+        service
+            .capabilities()
+            .features
+            .contains(&"offline_fixtures")
+    );
+
+    let per_query = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                query: "Rust async?".to_string(),
+                ..base_args()
+            },
+            "trace-offline-1",
+            || false,
+        )
+        .await
+        .expect("per-query fixture should be served");
+    assert_eq!(per_query.sections[0].results[0].title, "Per query");
+    assert!(
+        per_query
+            .warnings
+            .iter()
+            .any(|warning| warning.code == WarningCode::OfflineMode)
+    );
+
+    let fallback = service
+        .execute_web_search(base_args(), "trace-offline-2", || false)
+        .await
+        .expect("fallback fixture should be served");
+    assert_eq!(fallback.sections[0].results[0].title, "Fallback");
+
+    let missing = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                search_type: Some("news".to_string()),
+                ..base_args()
+            },
+            "trace-offline-3",
+            || false,
+        )
+        .await
+        .expect_err("news has no fixture");
+    assert!(missing.message().contains("news.json"));
+
+    std::fs::remove_dir_all(&fixture_dir).ok();
+}
+
+#[cfg(feature = "archive")]
+#[tokio::test]
+#[serial]
+async fn recorded_payloads_replay_without_contacting_brave() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(mock_payload("Recorded", "https://example.com/recorded")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let record_dir =
+        std::env::temp_dir().join(format!("codex-brave-record-{}", std::process::id()));
+    let mut config = configure_for_mock_server(&server);
+    config.record = Some(RecordSettings {
+        dir: record_dir.clone(),
+        mode: RecordMode::Record,
+    });
+    let recorder = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config.clone()).expect("service init")
+    });
+    recorder
+        .execute_web_search(base_args(), "trace-record", || false)
+        .await
+        .expect("recorded search should succeed");
+    assert_eq!(
+        std::fs::read_dir(&record_dir).expect("record dir").count(),
+        1
+    );
+
+    config.record = Some(RecordSettings {
+        dir: record_dir.clone(),
+        mode: RecordMode::Replay,
+    });
+    let key_env = [
+        ("BRAVE_SEARCH_API_KEY", None::<&str>),
+        ("BRAVE_API_KEY", None),
+        ("CODEX_BRAVE_API_KEY_FILE", None),
+    ];
+    let replayer = temp_env::with_vars(key_env, || {
+        SearchService::new(config).expect("service init")
+    });
+    let replayed = replayer
+        .execute_web_search(base_args(), "trace-replay", || false)
+        .await
+        .expect("replayed search should succeed");
+    assert_eq!(replayed.sections[0].results[0].title, "Recorded");
+
+    let unrecorded = replayer
+        .execute_web_search(
+            BraveWebSearchArgs {
+                query: "never recorded".to_string(),
+                ..base_args()
+            },
+            "trace-replay-miss",
+            || false,
+        )
+        .await
+        .expect_err("unrecorded search must not reach Brave");
+    assert!(
+        unrecorded
+            .message()
+            .contains("CODEX_BRAVE_RECORD_MODE=record")
+    );
+
+    std::fs::remove_dir_all(&record_dir).ok();
+}
+
+#[tokio::test]
+#[serial]
+async fn cli_commands_print_search_status_and_schema() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/news"))
+        .and(query_param("count", "3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "type": "news",
+            "results": [{"title": "CLI headline", "url": "https://news.example/cli"}]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let service = || {
+        temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+            SearchService::new(configure_for_mock_server(&server)).expect("service init")
+        })
+    };
+    let parse = |args: &[&str]| {
+        Cli::try_parse_from(std::iter::once("codex-brave-web-search").chain(args.iter().copied()))
+            .expect("valid command line")
+            .command
+            .expect("subcommand")
+    };
+
+    let printed = cli::run(
+        parse(&["search", "openai", "--type", "news", "--max-results", "3"]),
+        service(),
+    )
+    .await
+    .expect("search command");
+    assert!(printed.contains("CLI headline"));
+
+    let status = cli::run(parse(&["status"]), service())
+        .await
+        .expect("status command");
+    let status = serde_json::from_str::<serde_json::Value>(&status).expect("status JSON");
+    assert_eq!(status["status"], "ok");
+
+    let schema = cli::run(parse(&["schema", "brave_web_search"]), service())
+        .await
+        .expect("schema command");
+    let schema = serde_json::from_str::<serde_json::Value>(&schema).expect("schema JSON");
+    assert_eq!(schema["name"], "brave_web_search");
+    assert_eq!(schema["inputSchema"]["additionalProperties"], false);
+
+    let unknown = cli::run(parse(&["schema", "nope"]), service())
+        .await
+        .expect_err("unknown tool");
+    assert_eq!(unknown.code(), "INVALID_ARGUMENT");
+}
+
+#[tokio::test]
+#[serial]
+async fn api_key_echoed_by_upstream_is_redacted_from_cli_output() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(422).set_body_json(serde_json::json!({
+            "error": {"code": "SUBSCRIPTION_TOKEN_INVALID", "detail": "bad token secret-key-123456"}
+        })))
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("secret-key-123456"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let command = Cli::try_parse_from(["codex-brave-web-search", "search", "openai"])
+        .expect("valid command line")
+        .command
+        .expect("subcommand");
+    let error = cli::run(command, service)
+        .await
+        .expect_err("upstream rejects the request");
+
+    let message = error.to_string();
+    assert!(!message.contains("secret-key-123456"), "{message}");
+    assert!(message.contains("***REDACTED***"), "{message}");
+}
+
+#[tokio::test]
+async fn rerank_reorders_results_and_is_reported_in_meta() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "query": {"original": "openai", "more_results_available": false},
+            "web": { "results": [
+                { "title": "Unrelated", "url": "https://a.example/", "description": "desc" },
+                { "title": "OpenAI news", "url": "https://b.example/", "description": "openai" }
+            ]}
+        })))
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let response = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                rerank: Some("Keyword".to_string()),
+                ..base_args()
+            },
+            "trace-rerank",
+            || false,
+        )
+        .await
+        .expect("search should succeed");
+    assert_eq!(response.sections[0].results[0].url, "https://b.example/");
+    assert_eq!(response.meta.rerank, Some(RerankMode::Keyword));
+
+    let response = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                rerank: Some("shuffle".to_string()),
+                ..base_args()
+            },
+            "trace-rerank-invalid",
+            || false,
+        )
+        .await
+        .expect("search should succeed");
+    assert_eq!(response.sections[0].results[0].url, "https://a.example/");
+    assert_eq!(response.meta.rerank, None);
+    assert!(
+        response
+            .warnings
+            .iter()
+            .any(|warning| warning.code == "INVALID_RERANK")
+    );
+}
+
+#[tokio::test]
+async fn published_date_bounds_filter_results_and_warn() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "query": {"original": "openai", "more_results_available": false},
+            "web": { "results": [
+                { "title": "Old", "url": "https://a.example/", "description": "desc",
+                  "page_age": "2022-05-01T00:00:00" },
+                { "title": "New", "url": "https://b.example/", "description": "desc",
+                  "page_age": "2024-05-01T00:00:00" }
+            ]}
+        })))
+        .mount(&server)
+        .await;
+
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(configure_for_mock_server(&server)).expect("service init")
+    });
+    let response = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                published_after: Some("2024-01-01".to_string()),
+                published_before: Some("someday".to_string()),
+                ..base_args()
+            },
+            "trace-published",
+            || false,
+        )
+        .await
+        .expect("search should succeed");
+
+    assert_eq!(response.sections[0].results.len(), 1);
+    assert_eq!(response.sections[0].results[0].url, "https://b.example/");
+    assert!(response.warnings.iter().any(|warning| {
+        warning.code == "RESULTS_FILTERED" && warning.message.contains("removed 1 result(s)")
+    }));
+    assert!(response.warnings.iter().any(|warning| {
+        warning.code == "INVALID_PUBLISHED_DATE" && warning.message.contains("published_before")
+    }));
+}
+
+#[tokio::test]
+async fn safe_search_minimum_is_enforced_for_every_search_type() {
+    let server = MockServer::start().await;
+    for endpoint in ["/web", "/news"] {
+        Mock::given(method("GET"))
+            .and(path(endpoint))
+            .and(query_param("safesearch", "strict"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(mock_payload("A", "https://example.com/a")),
+            )
+            .mount(&server)
+            .await;
+    }
+
+    let mut config = configure_for_mock_server(&server);
+    config.safe_search_floors.news = Some("moderate".to_string());
+    config.safe_search_floors.minimum = Some("strict".to_string());
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+
+    let response = service
+        .execute_web_search(
+            BraveWebSearchArgs {
+                search_type: Some("news".to_string()),
+                safe_search: Some("off".to_string()),
+                ..base_args()
+            },
+            "trace-safe-min",
+            || false,
+        )
+        .await
+        .expect("search should succeed under the safe-search minimum");
+    let codes = response
+        .warnings
+        .iter()
+        .map(|warning| warning.code.as_str())
+        .collect::<Vec<&str>>();
+    assert!(codes.contains(&"POLICY_OVERRIDDEN"), "{codes:?}");
+    assert!(codes.contains(&"SAFE_SEARCH_ENFORCED"), "{codes:?}");
+
+    let response = service
+        .execute_web_search(base_args(), "trace-safe-min-default", || false)
+        .await
+        .expect("omitted safe_search is set to the minimum");
+    assert!(
+        response
+            .warnings
+            .iter()
+            .all(|warning| warning.code != "SAFE_SEARCH_ENFORCED")
+    );
+}
+
+#[tokio::test]
+async fn blocked_domains_are_removed_with_a_redacted_count() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/web"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "query": {"original": "openai", "more_results_available": false},
+            "web": { "results": [
+                { "title": "Bad", "url": "https://login.phish.example/", "description": "desc" },
+                { "title": "Good", "url": "https://docs.example/", "description": "desc" },
+                { "title": "Worse", "url": "https://phish.example/kit", "description": "desc" }
+            ]}
+        })))
+        .mount(&server)
+        .await;
+
+    let mut config = configure_for_mock_server(&server);
+    config.blocked_domains = DomainBlocklist::parse("phish.example");
+    let service = temp_env::with_var("BRAVE_SEARCH_API_KEY", Some("test-key"), || {
+        SearchService::new(config).expect("service init")
+    });
+    let response = service
+        .execute_web_search(base_args(), "trace-blocked", || false)
+        .await
+        .expect("search should succeed");
+
+    assert_eq!(response.sections[0].results.len(), 1);
+    assert_eq!(response.sections[0].results[0].url, "https://docs.example/");
+    let warning = response
+        .warnings
+        .iter()
+        .find(|warning| warning.code == "RESULTS_BLOCKED")
+        .expect("blocked warning");
+    assert!(warning.message.starts_with("2 result(s)"));
+    assert!(!warning.message.contains("phish"));
+    assert_eq!(service.capabilities().policy.blocked_domain_patterns, 1);
+}